@@ -0,0 +1,254 @@
+use serde::{
+  Deserialize,
+  Serialize,
+};
+use solana_program::{
+  pubkey::{
+    Pubkey,
+  },
+};
+use std::{
+  collections::{
+    BTreeMap,
+  },
+  fs,
+  path::{
+    Path,
+    PathBuf,
+  },
+};
+use vault_client::merkle::{
+  redemption_leaf,
+  redemption_multi_leaf,
+  MerkleTree,
+};
+
+use crate::recipient::{
+  parse_pubkey,
+  Recipient,
+};
+use crate::Result;
+
+pub const DISTRIBUTION_FILE: &str = "distribution.json";
+pub const PROOF_DIR: &str = "proofs";
+
+/// Schedule-wide parameters of a distribution.
+pub struct DistributionConfig {
+  pub event_id: u64,
+  /// Unlock time shared by every recipient. When zero, each recipient carries its own
+  /// timestamp and the leaves are hashed in the V2 layout.
+  pub timestamp: i64,
+  pub use_multi_token: bool,
+  pub receiving_token_mint: Option<Pubkey>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct FundingFile {
+  pub mint: String,
+  pub amount: u64,
+}
+
+/// Summary written to `distribution.json`, consumed when building `create_schedule`.
+#[derive(Serialize, Deserialize)]
+pub struct DistributionFile {
+  pub event_id: u64,
+  pub timestamp: i64,
+  pub use_multi_token: bool,
+  pub receiving_token_mint: Option<String>,
+  pub merkle_root: String,
+  pub user_count: u16,
+  pub funding: Vec<FundingFile>,
+  pub sending_amount_total: u64,
+}
+
+/// Everything a recipient needs to call `redeem_token` or `redeem_token_multi`.
+#[derive(Serialize, Deserialize)]
+pub struct ProofFile {
+  pub index: u16,
+  pub address: String,
+  pub timestamp: i64,
+  pub receiving_token_mint: Option<String>,
+  pub receiving_amount: u64,
+  pub sending_amount: u64,
+  pub proofs: Vec<String>,
+}
+
+pub struct Distribution {
+  pub file: DistributionFile,
+  pub proofs: Vec<ProofFile>,
+}
+
+pub fn build_distribution(config: &DistributionConfig, recipients: &[Recipient]) -> Result<Distribution> {
+  validate_recipients(config, recipients)?;
+
+  let leaves = recipients.iter()
+    .map(|recipient| compute_leaf(config.use_multi_token, config.timestamp, recipient))
+    .collect();
+  let tree = MerkleTree::new(leaves);
+
+  let proofs = recipients.iter()
+    .map(|recipient| ProofFile {
+      index: recipient.index,
+      address: recipient.address.to_string(),
+      timestamp: recipient.timestamp.unwrap_or(config.timestamp),
+      receiving_token_mint: recipient.receiving_token_mint.map(|mint| mint.to_string()),
+      receiving_amount: recipient.receiving_amount,
+      sending_amount: recipient.sending_amount,
+      proofs: tree.proof(usize::from(recipient.index)).iter().map(hex::encode).collect(),
+    })
+    .collect();
+
+  let sending_amount_total = recipients.iter()
+    .try_fold(0u64, |total, recipient| total.checked_add(recipient.sending_amount))
+    .ok_or("total sending amount overflows u64")?;
+
+  let file = DistributionFile {
+    event_id: config.event_id,
+    timestamp: config.timestamp,
+    use_multi_token: config.use_multi_token,
+    receiving_token_mint: config.receiving_token_mint.map(|mint| mint.to_string()),
+    merkle_root: hex::encode(tree.root()),
+    user_count: tree.leaf_count() as u16,
+    funding: compute_funding(config, recipients)?
+      .into_iter()
+      .map(|(mint, amount)| FundingFile { mint: mint.to_string(), amount })
+      .collect(),
+    sending_amount_total,
+  };
+
+  Ok(Distribution {
+    file,
+    proofs,
+  })
+}
+
+/// Returns the amount of each mint the vault must hold so every recipient can redeem.
+/// SOL payouts are reported under the system program id, in lamports.
+pub fn compute_funding(config: &DistributionConfig, recipients: &[Recipient]) -> Result<BTreeMap<Pubkey, u64>> {
+  let mut funding = BTreeMap::new();
+  for recipient in recipients {
+    let mint = if config.use_multi_token {
+      recipient.receiving_token_mint
+    } else {
+      config.receiving_token_mint
+    };
+    let mint = mint.ok_or_else(|| format!("recipient #{}: missing receiving_token_mint", recipient.index))?;
+    let total: &mut u64 = funding.entry(mint).or_default();
+    *total = total.checked_add(recipient.receiving_amount)
+      .ok_or_else(|| format!("total funding of {} overflows u64", mint))?;
+  }
+  Ok(funding)
+}
+
+/// Hashes the Merkle leaf of `recipient` exactly like `redemption_data`/`redemption_multi_data`
+/// encode it on-chain. `schedule_timestamp` selects the V1 layout when set.
+pub fn compute_leaf(use_multi_token: bool, schedule_timestamp: i64, recipient: &Recipient) -> [u8; 32] {
+  let timestamp = if schedule_timestamp > 0 { None } else { Some(recipient.timestamp.unwrap_or_default()) };
+  if use_multi_token {
+    redemption_multi_leaf(recipient.index, timestamp, &recipient.address, recipient.receiving_token_mint.as_ref().unwrap(), recipient.receiving_amount, recipient.sending_amount)
+  } else {
+    redemption_leaf(recipient.index, timestamp, &recipient.address, recipient.receiving_amount, recipient.sending_amount)
+  }
+}
+
+/// Re-hashes every proof file of a distribution and checks it against the Merkle root
+/// using the same routine as the program.
+pub fn verify_distribution(distribution: &Distribution) -> Result<()> {
+  let file = &distribution.file;
+  let root = parse_hash(&file.merkle_root)?;
+
+  if distribution.proofs.len() != usize::from(file.user_count) {
+    return Err(format!("expected {} proofs, found {}", file.user_count, distribution.proofs.len()).into());
+  }
+
+  let mut seen = vec![false; usize::from(file.user_count)];
+  for proof in &distribution.proofs {
+    let slot = seen.get_mut(usize::from(proof.index))
+      .ok_or_else(|| format!("proof #{}: index out of range", proof.index))?;
+    if *slot {
+      return Err(format!("proof #{}: duplicated index", proof.index).into());
+    }
+    *slot = true;
+
+    let receiving_token_mint = match (&proof.receiving_token_mint, file.use_multi_token) {
+      (Some(mint), true) => Some(parse_pubkey(mint)?),
+      (None, true) => return Err(format!("proof #{}: missing receiving_token_mint", proof.index).into()),
+      _ => None,
+    };
+    let recipient = Recipient {
+      index: proof.index,
+      address: parse_pubkey(&proof.address)?,
+      receiving_token_mint,
+      receiving_amount: proof.receiving_amount,
+      sending_amount: proof.sending_amount,
+      timestamp: Some(proof.timestamp),
+    };
+    let leaf = compute_leaf(file.use_multi_token, file.timestamp, &recipient);
+    let proofs = proof.proofs.iter()
+      .map(|value| parse_hash(value))
+      .collect::<Result<Vec<_>>>()?;
+    if !vault::shared::verify_proof(proofs, root, leaf) {
+      return Err(format!("proof #{}: does not match merkle root", proof.index).into());
+    }
+  }
+
+  Ok(())
+}
+
+pub fn write_distribution(out_dir: &Path, distribution: &Distribution) -> Result<()> {
+  let proof_dir = out_dir.join(PROOF_DIR);
+  fs::create_dir_all(&proof_dir)?;
+  fs::write(out_dir.join(DISTRIBUTION_FILE), serde_json::to_string_pretty(&distribution.file)?)?;
+  for proof in &distribution.proofs {
+    fs::write(proof_path(&proof_dir, proof.index), serde_json::to_string_pretty(proof)?)?;
+  }
+  Ok(())
+}
+
+pub fn read_distribution(out_dir: &Path) -> Result<Distribution> {
+  let file: DistributionFile = serde_json::from_slice(&fs::read(out_dir.join(DISTRIBUTION_FILE))?)?;
+  let proof_dir = out_dir.join(PROOF_DIR);
+  let proofs = (0..file.user_count)
+    .map(|index| {
+      let path = proof_path(&proof_dir, index);
+      let data = fs::read(&path)
+        .map_err(|err| format!("{}: {}", path.display(), err))?;
+      Ok(serde_json::from_slice(&data)?)
+    })
+    .collect::<Result<Vec<ProofFile>>>()?;
+  Ok(Distribution {
+    file,
+    proofs,
+  })
+}
+
+pub fn parse_hash(value: &str) -> Result<[u8; 32]> {
+  let mut bytes = [0u8; 32];
+  hex::decode_to_slice(value.trim_start_matches("0x"), &mut bytes)
+    .map_err(|_| format!("invalid 32 bytes hash {}", value))?;
+  Ok(bytes)
+}
+
+fn proof_path(proof_dir: &Path, index: u16) -> PathBuf {
+  proof_dir.join(format!("{}.json", index))
+}
+
+fn validate_recipients(config: &DistributionConfig, recipients: &[Recipient]) -> Result<()> {
+  if !config.use_multi_token && config.receiving_token_mint.is_none() {
+    return Err("single token distributions require --receiving-token-mint".into());
+  }
+  for recipient in recipients {
+    if recipient.receiving_amount == 0 {
+      return Err(format!("recipient #{}: receiving_amount must be greater than zero", recipient.index).into());
+    }
+    if config.use_multi_token && recipient.receiving_token_mint.is_none() {
+      return Err(format!("recipient #{}: missing receiving_token_mint", recipient.index).into());
+    }
+    match (config.timestamp > 0, recipient.timestamp) {
+      (true, Some(_)) => return Err(format!("recipient #{}: timestamp must not be set when --timestamp is used", recipient.index).into()),
+      (false, None) => return Err(format!("recipient #{}: missing timestamp", recipient.index).into()),
+      _ => {},
+    }
+  }
+  Ok(())
+}
@@ -0,0 +1,86 @@
+use serde::{
+  Serialize,
+};
+use solana_program::{
+  instruction::{
+    Instruction,
+  },
+  pubkey::{
+    Pubkey,
+  },
+  system_program::{
+    ID as SYSTEM_PROGRAM_ID,
+  },
+};
+
+use crate::distribution::{
+  parse_hash,
+  DistributionFile,
+};
+use crate::recipient::{
+  parse_pubkey,
+};
+use crate::Result;
+
+/// Accounts of `create_schedule` that are not part of the distribution itself.
+pub struct CreateScheduleAccounts {
+  pub program_id: Pubkey,
+  pub admin: Pubkey,
+  pub vault: Pubkey,
+  pub receiving_token_account: Pubkey,
+  pub sending_token_mint: Pubkey,
+  pub sending_token_account: Pubkey,
+}
+
+#[derive(Serialize)]
+pub struct AccountMetaFile {
+  pub pubkey: String,
+  pub is_signer: bool,
+  pub is_writable: bool,
+}
+
+/// JSON form of an instruction. `data` is base58 encoded.
+#[derive(Serialize)]
+pub struct InstructionFile {
+  pub program_id: String,
+  pub accounts: Vec<AccountMetaFile>,
+  pub data: String,
+}
+
+impl From<&Instruction> for InstructionFile {
+  fn from(instruction: &Instruction) -> Self {
+    InstructionFile {
+      program_id: instruction.program_id.to_string(),
+      accounts: instruction.accounts.iter()
+        .map(|meta| AccountMetaFile {
+          pubkey: meta.pubkey.to_string(),
+          is_signer: meta.is_signer,
+          is_writable: meta.is_writable,
+        })
+        .collect(),
+      data: bs58::encode(&instruction.data).into_string(),
+    }
+  }
+}
+
+pub fn create_schedule(accounts: &CreateScheduleAccounts, distribution: &DistributionFile) -> Result<Instruction> {
+  let receiving_token_mint = match &distribution.receiving_token_mint {
+    Some(mint) => parse_pubkey(mint)?,
+    None => SYSTEM_PROGRAM_ID,
+  };
+
+  Ok(vault_client::instruction::create_schedule(
+    &accounts.admin,
+    &accounts.vault,
+    distribution.user_count,
+    distribution.event_id,
+    distribution.timestamp,
+    parse_hash(&distribution.merkle_root)?,
+    distribution.use_multi_token,
+    &receiving_token_mint,
+    &accounts.receiving_token_account,
+    &accounts.sending_token_mint,
+    &accounts.sending_token_account,
+    &accounts.program_id,
+  ))
+}
@@ -0,0 +1,5 @@
+pub mod distribution;
+pub mod instruction;
+pub mod recipient;
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
@@ -0,0 +1,165 @@
+use clap::{
+  Parser,
+  Subcommand,
+};
+use solana_program::{
+  pubkey::{
+    Pubkey,
+  },
+  system_program::{
+    ID as SYSTEM_PROGRAM_ID,
+  },
+};
+use std::{
+  fs,
+  path::{
+    PathBuf,
+  },
+};
+
+use vault_cli::{
+  distribution::{
+    self,
+    DistributionConfig,
+  },
+  instruction::{
+    self,
+    CreateScheduleAccounts,
+    InstructionFile,
+  },
+  recipient,
+  Result,
+};
+
+/// Offline tooling to prepare Coin98 Vault distributions.
+#[derive(Parser)]
+#[command(name = "vault-cli", version)]
+struct Cli {
+  #[command(subcommand)]
+  command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+  /// Build the Merkle tree of a recipient list and write the root and per-user proofs
+  Build {
+    /// Recipient list in .csv or .json format
+    #[arg(long)]
+    input: PathBuf,
+    /// Directory receiving distribution.json and proofs/<index>.json
+    #[arg(long)]
+    out_dir: PathBuf,
+    #[arg(long)]
+    event_id: u64,
+    /// Unlock time shared by all recipients. Omit to use the per-recipient `timestamp` column
+    #[arg(long, default_value_t = 0)]
+    timestamp: i64,
+    /// Build a `redeem_token_multi` distribution, each recipient must have a `receiving_token_mint`
+    #[arg(long)]
+    multi_token: bool,
+    /// Token paid out by a single token distribution, use the system program id for SOL
+    #[arg(long)]
+    receiving_token_mint: Option<Pubkey>,
+  },
+  /// Check every proof of a built distribution against its Merkle root
+  Validate {
+    #[arg(long)]
+    out_dir: PathBuf,
+  },
+  /// Print the amount of each token the vault must hold to pay a recipient list
+  Funding {
+    #[arg(long)]
+    input: PathBuf,
+    #[arg(long)]
+    multi_token: bool,
+    #[arg(long)]
+    receiving_token_mint: Option<Pubkey>,
+  },
+  /// Print or save the `create_schedule` instruction of a built distribution
+  CreateSchedule {
+    #[arg(long)]
+    out_dir: PathBuf,
+    #[arg(long, default_value_t = vault::ID)]
+    program_id: Pubkey,
+    #[arg(long)]
+    admin: Pubkey,
+    #[arg(long)]
+    vault: Pubkey,
+    /// Vault signer owned token account paying the distribution
+    #[arg(long, default_value_t = SYSTEM_PROGRAM_ID)]
+    receiving_token_account: Pubkey,
+    #[arg(long, default_value_t = SYSTEM_PROGRAM_ID)]
+    sending_token_mint: Pubkey,
+    #[arg(long, default_value_t = SYSTEM_PROGRAM_ID)]
+    sending_token_account: Pubkey,
+    /// Write the instruction as JSON to this file instead of stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+  },
+}
+
+fn main() {
+  if let Err(err) = run(Cli::parse()) {
+    eprintln!("error: {}", err);
+    std::process::exit(1);
+  }
+}
+
+fn run(cli: Cli) -> Result<()> {
+  match cli.command {
+    Command::Build { input, out_dir, event_id, timestamp, multi_token, receiving_token_mint } => {
+      let recipients = recipient::load_recipients(&input)?;
+      let config = DistributionConfig {
+        event_id,
+        timestamp,
+        use_multi_token: multi_token,
+        receiving_token_mint,
+      };
+      let distribution = distribution::build_distribution(&config, &recipients)?;
+      distribution::verify_distribution(&distribution)?;
+      distribution::write_distribution(&out_dir, &distribution)?;
+
+      println!("Merkle root: {}", distribution.file.merkle_root);
+      println!("User count:  {}", distribution.file.user_count);
+      for funding in &distribution.file.funding {
+        println!("Funding:     {} {}", funding.amount, funding.mint);
+      }
+    },
+    Command::Validate { out_dir } => {
+      let distribution = distribution::read_distribution(&out_dir)?;
+      distribution::verify_distribution(&distribution)?;
+      println!("{} proofs match merkle root {}", distribution.proofs.len(), distribution.file.merkle_root);
+    },
+    Command::Funding { input, multi_token, receiving_token_mint } => {
+      let recipients = recipient::load_recipients(&input)?;
+      let config = DistributionConfig {
+        event_id: 0,
+        timestamp: 0,
+        use_multi_token: multi_token,
+        receiving_token_mint,
+      };
+      for (mint, amount) in distribution::compute_funding(&config, &recipients)? {
+        println!("{} {}", amount, mint);
+      }
+    },
+    Command::CreateSchedule { out_dir, program_id, admin, vault, receiving_token_account, sending_token_mint, sending_token_account, output } => {
+      let distribution = distribution::read_distribution(&out_dir)?;
+      distribution::verify_distribution(&distribution)?;
+      let accounts = CreateScheduleAccounts {
+        program_id,
+        admin,
+        vault,
+        receiving_token_account,
+        sending_token_mint,
+        sending_token_account,
+      };
+      let instruction = instruction::create_schedule(&accounts, &distribution.file)?;
+      let json = serde_json::to_string_pretty(&InstructionFile::from(&instruction))?;
+      match output {
+        Some(path) => fs::write(path, json)?,
+        None => println!("{}", json),
+      }
+    },
+  }
+  Ok(())
+}
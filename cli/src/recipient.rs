@@ -0,0 +1,106 @@
+use serde::{
+  Deserialize,
+};
+use solana_program::{
+  pubkey::{
+    Pubkey,
+  },
+};
+use std::{
+  convert::{
+    TryFrom,
+  },
+  fs::{
+    File,
+  },
+  path::{
+    Path,
+  },
+  str::{
+    FromStr,
+  },
+};
+
+use crate::Result;
+
+/// A row of the recipient list as written by the distribution operator.
+/// Amounts are expressed in the smallest unit of the token (lamports for SOL).
+#[derive(Deserialize)]
+struct RecipientRecord {
+  address: String,
+  receiving_amount: u64,
+  #[serde(default)]
+  sending_amount: u64,
+  #[serde(default)]
+  receiving_token_mint: Option<String>,
+  #[serde(default)]
+  timestamp: Option<i64>,
+}
+
+/// A validated recipient. `index` is the position of the recipient in the input
+/// file and becomes its slot in `Schedule.redemptions`.
+#[derive(Clone, Debug)]
+pub struct Recipient {
+  pub index: u16,
+  pub address: Pubkey,
+  pub receiving_token_mint: Option<Pubkey>,
+  pub receiving_amount: u64,
+  pub sending_amount: u64,
+  pub timestamp: Option<i64>,
+}
+
+/// Reads a recipient list from a `.csv` or `.json` file. CSV files must have a
+/// header row with the same column names as the JSON object keys.
+pub fn load_recipients(path: &Path) -> Result<Vec<Recipient>> {
+  let extension = path.extension()
+    .and_then(|value| value.to_str())
+    .map(|value| value.to_ascii_lowercase());
+
+  let records: Vec<RecipientRecord> = match extension.as_deref() {
+    Some("csv") => {
+      let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_path(path)?;
+      reader.deserialize().collect::<std::result::Result<_, _>>()?
+    },
+    Some("json") => {
+      serde_json::from_reader(File::open(path)?)?
+    },
+    _ => return Err(format!("unsupported recipient file {}, expected .csv or .json", path.display()).into()),
+  };
+
+  if records.is_empty() {
+    return Err("recipient list is empty".into());
+  }
+  if records.len() > usize::from(u16::MAX) {
+    return Err(format!("recipient list has {} entries, a schedule supports at most {}", records.len(), u16::MAX).into());
+  }
+
+  records.into_iter()
+    .enumerate()
+    .map(|(index, record)| {
+      let index = u16::try_from(index)?;
+      let address = parse_pubkey(&record.address)
+        .map_err(|err| format!("recipient #{}: {}", index, err))?;
+      let receiving_token_mint = match record.receiving_token_mint.as_deref() {
+        Some(value) if !value.is_empty() => Some(
+          parse_pubkey(value).map_err(|err| format!("recipient #{}: {}", index, err))?
+        ),
+        _ => None,
+      };
+      Ok(Recipient {
+        index,
+        address,
+        receiving_token_mint,
+        receiving_amount: record.receiving_amount,
+        sending_amount: record.sending_amount,
+        timestamp: record.timestamp,
+      })
+    })
+    .collect()
+}
+
+pub fn parse_pubkey(value: &str) -> Result<Pubkey> {
+  Pubkey::from_str(value.trim())
+    .map_err(|_| format!("invalid public key {}", value).into())
+}
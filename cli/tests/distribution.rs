@@ -0,0 +1,188 @@
+use solana_program::{
+  pubkey::{
+    Pubkey,
+  },
+  system_program,
+};
+use vault_cli::distribution::{
+  build_distribution,
+  compute_funding,
+  verify_distribution,
+  Distribution,
+  DistributionConfig,
+};
+use vault_cli::recipient::{
+  Recipient,
+};
+use vault_client::merkle::{
+  redemption_leaf,
+  MerkleTree,
+};
+
+fn recipient(index: u16, receiving_token_mint: Option<Pubkey>, receiving_amount: u64, timestamp: Option<i64>) -> Recipient {
+  Recipient {
+    index,
+    address: Pubkey::new_unique(),
+    receiving_token_mint,
+    receiving_amount,
+    sending_amount: 0,
+    timestamp,
+  }
+}
+
+fn single_token_config(receiving_token_mint: Pubkey) -> DistributionConfig {
+  DistributionConfig {
+    event_id: 1,
+    timestamp: 1_700_000_000,
+    use_multi_token: false,
+    receiving_token_mint: Some(receiving_token_mint),
+  }
+}
+
+fn multi_token_config() -> DistributionConfig {
+  DistributionConfig {
+    event_id: 1,
+    timestamp: 0,
+    use_multi_token: true,
+    receiving_token_mint: None,
+  }
+}
+
+fn verify_err(distribution: &Distribution) -> String {
+  match verify_distribution(distribution) {
+    Ok(_) => panic!("distribution should be rejected"),
+    Err(err) => err.to_string(),
+  }
+}
+
+#[test]
+fn compute_funding_of_single_token_distribution() {
+  let mint = Pubkey::new_unique();
+  let recipients = vec![
+    recipient(0, None, 100, None),
+    recipient(1, None, 250, None),
+  ];
+
+  let funding = compute_funding(&single_token_config(mint), &recipients).unwrap();
+
+  assert_eq!(funding.into_iter().collect::<Vec<_>>(), vec![(mint, 350)]);
+}
+
+#[test]
+fn compute_funding_reports_sol_under_system_program() {
+  let recipients = vec![recipient(0, None, 1_000_000_000, None)];
+
+  let funding = compute_funding(&single_token_config(system_program::ID), &recipients).unwrap();
+
+  assert_eq!(funding.get(&system_program::ID), Some(&1_000_000_000));
+}
+
+#[test]
+fn compute_funding_of_multi_token_distribution() {
+  let mint0 = Pubkey::new_unique();
+  let mint1 = Pubkey::new_unique();
+  let recipients = vec![
+    recipient(0, Some(mint0), 100, Some(0)),
+    recipient(1, Some(mint1), 200, Some(0)),
+    recipient(2, Some(mint0), 300, Some(0)),
+  ];
+
+  let funding = compute_funding(&multi_token_config(), &recipients).unwrap();
+
+  assert_eq!(funding.len(), 2);
+  assert_eq!(funding[&mint0], 400);
+  assert_eq!(funding[&mint1], 200);
+}
+
+#[test]
+fn compute_funding_rejects_missing_mint_and_overflow() {
+  let recipients = vec![recipient(0, None, 100, Some(0))];
+  let err = compute_funding(&multi_token_config(), &recipients).unwrap_err();
+  assert_eq!(err.to_string(), "recipient #0: missing receiving_token_mint");
+
+  let mint = Pubkey::new_unique();
+  let recipients = vec![
+    recipient(0, None, u64::MAX, None),
+    recipient(1, None, 1, None),
+  ];
+  let err = compute_funding(&single_token_config(mint), &recipients).unwrap_err();
+  assert_eq!(err.to_string(), format!("total funding of {} overflows u64", mint));
+}
+
+#[test]
+fn build_distribution_matches_merkle_tree() {
+  let mint = Pubkey::new_unique();
+  let recipients = vec![
+    recipient(0, None, 100, None),
+    recipient(1, None, 200, None),
+    recipient(2, None, 300, None),
+  ];
+
+  let distribution = build_distribution(&single_token_config(mint), &recipients).unwrap();
+
+  let merkle_tree = MerkleTree::new(
+    recipients.iter()
+      .map(|recipient| redemption_leaf(recipient.index, None, &recipient.address, recipient.receiving_amount, 0))
+      .collect(),
+  );
+  assert_eq!(distribution.file.merkle_root, hex::encode(merkle_tree.root()));
+  assert_eq!(distribution.file.user_count, 3);
+  assert_eq!(distribution.proofs.len(), 3);
+  assert_eq!(distribution.proofs[2].timestamp, 1_700_000_000);
+  verify_distribution(&distribution).unwrap();
+}
+
+#[test]
+fn verify_multi_token_distribution() {
+  let recipients = vec![
+    recipient(0, Some(Pubkey::new_unique()), 100, Some(1_700_000_000)),
+    recipient(1, Some(Pubkey::new_unique()), 200, Some(1_800_000_000)),
+  ];
+
+  let distribution = build_distribution(&multi_token_config(), &recipients).unwrap();
+
+  verify_distribution(&distribution).unwrap();
+}
+
+#[test]
+fn verify_distribution_rejects_tampered_proofs() {
+  let mint = Pubkey::new_unique();
+  let recipients = vec![
+    recipient(0, None, 100, None),
+    recipient(1, None, 200, None),
+    recipient(2, None, 300, None),
+  ];
+
+  let mut distribution = build_distribution(&single_token_config(mint), &recipients).unwrap();
+  distribution.proofs[1].receiving_amount = 201;
+  assert_eq!(verify_err(&distribution), "proof #1: does not match merkle root");
+
+  let mut distribution = build_distribution(&single_token_config(mint), &recipients).unwrap();
+  distribution.proofs[0].proofs[0] = hex::encode([0u8; 32]);
+  assert_eq!(verify_err(&distribution), "proof #0: does not match merkle root");
+
+  let mut distribution = build_distribution(&single_token_config(mint), &recipients).unwrap();
+  distribution.proofs[2].index = 0;
+  assert_eq!(verify_err(&distribution), "proof #0: duplicated index");
+
+  let mut distribution = build_distribution(&single_token_config(mint), &recipients).unwrap();
+  distribution.proofs[2].index = 3;
+  assert_eq!(verify_err(&distribution), "proof #3: index out of range");
+
+  let mut distribution = build_distribution(&single_token_config(mint), &recipients).unwrap();
+  distribution.proofs.pop();
+  assert_eq!(verify_err(&distribution), "expected 3 proofs, found 2");
+}
+
+#[test]
+fn verify_multi_token_distribution_rejects_missing_mint() {
+  let recipients = vec![
+    recipient(0, Some(Pubkey::new_unique()), 100, Some(0)),
+    recipient(1, Some(Pubkey::new_unique()), 200, Some(0)),
+  ];
+
+  let mut distribution = build_distribution(&multi_token_config(), &recipients).unwrap();
+  distribution.proofs[1].receiving_token_mint = None;
+
+  assert_eq!(verify_err(&distribution), "proof #1: missing receiving_token_mint");
+}
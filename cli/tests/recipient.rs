@@ -0,0 +1,132 @@
+use solana_program::{
+  pubkey::{
+    Pubkey,
+  },
+};
+use std::{
+  fs,
+  path::{
+    PathBuf,
+  },
+};
+use vault_cli::recipient::{
+  load_recipients,
+  Recipient,
+};
+
+/// Writes `content` to a file of the temp directory unique to this test process.
+fn write_input(name: &str, content: &str) -> PathBuf {
+  let path = std::env::temp_dir().join(format!("vault-cli-{}-{}", std::process::id(), name));
+  fs::write(&path, content).unwrap();
+  path
+}
+
+fn load(name: &str, content: &str) -> vault_cli::Result<Vec<Recipient>> {
+  let path = write_input(name, content);
+  let result = load_recipients(&path);
+  fs::remove_file(&path).unwrap();
+  result
+}
+
+fn load_err(name: &str, content: &str) -> String {
+  match load(name, content) {
+    Ok(_) => panic!("{} should be rejected", name),
+    Err(err) => err.to_string(),
+  }
+}
+
+#[test]
+fn load_csv_recipients() {
+  let user0 = Pubkey::new_unique();
+  let user1 = Pubkey::new_unique();
+  let mint = Pubkey::new_unique();
+  let content = format!(
+    "address,receiving_amount,sending_amount,receiving_token_mint,timestamp\n {} , 100 ,5,,\n{},200,0,{},1700000000\n",
+    user0, user1, mint,
+  );
+
+  let recipients = load("single.csv", &content).unwrap();
+
+  assert_eq!(recipients.len(), 2);
+  assert_eq!(recipients[0].index, 0);
+  assert_eq!(recipients[0].address, user0);
+  assert_eq!(recipients[0].receiving_amount, 100);
+  assert_eq!(recipients[0].sending_amount, 5);
+  assert_eq!(recipients[0].receiving_token_mint, None);
+  assert_eq!(recipients[0].timestamp, None);
+  assert_eq!(recipients[1].index, 1);
+  assert_eq!(recipients[1].address, user1);
+  assert_eq!(recipients[1].receiving_token_mint, Some(mint));
+  assert_eq!(recipients[1].timestamp, Some(1_700_000_000));
+}
+
+#[test]
+fn load_csv_recipients_with_required_columns_only() {
+  let user = Pubkey::new_unique();
+  let content = format!("address,receiving_amount\n{},42\n", user);
+
+  let recipients = load("required.CSV", &content).unwrap();
+
+  assert_eq!(recipients.len(), 1);
+  assert_eq!(recipients[0].receiving_amount, 42);
+  assert_eq!(recipients[0].sending_amount, 0);
+  assert_eq!(recipients[0].receiving_token_mint, None);
+}
+
+#[test]
+fn load_json_recipients() {
+  let user0 = Pubkey::new_unique();
+  let user1 = Pubkey::new_unique();
+  let mint = Pubkey::new_unique();
+  let content = format!(
+    r#"[
+      {{ "address": "{}", "receiving_amount": 100 }},
+      {{ "address": "{}", "receiving_amount": 200, "sending_amount": 7, "receiving_token_mint": "{}", "timestamp": 1700000000 }}
+    ]"#,
+    user0, user1, mint,
+  );
+
+  let recipients = load("multi.json", &content).unwrap();
+
+  assert_eq!(recipients.len(), 2);
+  assert_eq!(recipients[0].address, user0);
+  assert_eq!(recipients[0].sending_amount, 0);
+  assert_eq!(recipients[0].timestamp, None);
+  assert_eq!(recipients[1].index, 1);
+  assert_eq!(recipients[1].sending_amount, 7);
+  assert_eq!(recipients[1].receiving_token_mint, Some(mint));
+  assert_eq!(recipients[1].timestamp, Some(1_700_000_000));
+}
+
+#[test]
+fn load_json_recipients_treats_empty_mint_as_none() {
+  let user = Pubkey::new_unique();
+  let content = format!(r#"[{{ "address": "{}", "receiving_amount": 1, "receiving_token_mint": "" }}]"#, user);
+
+  let recipients = load("empty_mint.json", &content).unwrap();
+
+  assert_eq!(recipients[0].receiving_token_mint, None);
+}
+
+#[test]
+fn load_recipients_rejects_invalid_input() {
+  let user = Pubkey::new_unique();
+
+  let err = load_err("list.txt", "address,receiving_amount\n");
+  assert!(err.contains("unsupported recipient file"), "{}", err);
+
+  let err = load_err("empty.csv", "address,receiving_amount\n");
+  assert_eq!(err, "recipient list is empty");
+
+  let err = load_err("empty.json", "[]");
+  assert_eq!(err, "recipient list is empty");
+
+  let err = load_err("address.csv", &format!("address,receiving_amount\n{},1\nnot-a-key,2\n", user));
+  assert_eq!(err, "recipient #1: invalid public key not-a-key");
+
+  let err = load_err("mint.json", &format!(r#"[{{ "address": "{}", "receiving_amount": 1, "receiving_token_mint": "mint" }}]"#, user));
+  assert_eq!(err, "recipient #0: invalid public key mint");
+
+  load_err("amount.csv", &format!("address,receiving_amount\n{},-1\n", user));
+  load_err("missing.json", &format!(r#"[{{ "address": "{}" }}]"#, user));
+}
@@ -0,0 +1,153 @@
+use anchor_lang::{
+  AccountDeserialize,
+  AnchorDeserialize,
+  Result,
+};
+use std::{
+  convert::{
+    TryFrom,
+    TryInto,
+  },
+};
+pub use vault::state::{
+  BitmapSchedule,
+  ClaimStatus,
+  EpochClaim,
+  ObjType,
+  Schedule,
+  ScheduleEpoch,
+  ScheduleMint,
+  ScheduleStake,
+  ScheduleTranche,
+  ScheduleVoucher,
+  Tranche,
+  Vault,
+  VaultAllowlist,
+  VaultDestinations,
+  VaultFee,
+  VaultReserve,
+  VaultSwap,
+  Voucher,
+};
+
+/// Byte offset of the first entry of `Schedule.redemptions` in the account data:
+/// discriminator, obj_type, nonce, event_id, vault_id, timestamp, merkle_root (32 bytes),
+/// receiving/sending token mints and accounts, is_active and the length of `redemptions`.
+pub const SCHEDULE_REDEMPTIONS_OFFSET: usize = 8 + 1 + 1 + 8 + 32 + 8 + (4 + 32) + 32 + 32 + 32 + 32 + 1 + 4;
+
+/// Decodes the raw data of a `Vault` account, discriminator included. Vaults not migrated
+/// yet decode with `version` 0.
+pub fn decode_vault(data: &[u8]) -> Result<Vault> {
+  Vault::try_deserialize_versioned(data)
+}
+
+/// Decodes the raw data of a `Schedule` account, discriminator included. Schedules not
+/// migrated yet decode with `version` 0.
+pub fn decode_schedule(data: &[u8]) -> Result<Schedule> {
+  Schedule::try_deserialize_versioned(data)
+}
+
+/// Decodes the raw data of a `BitmapSchedule` account, discriminator included, into its
+/// header and redemption bitmap, see `BitmapSchedule::is_redeemed`.
+pub fn decode_bitmap_schedule(data: &[u8]) -> Result<(BitmapSchedule, Vec<u8>)> {
+  let (schedule, bitmap) = BitmapSchedule::try_from_account_data(data)?;
+  Ok((schedule, bitmap.to_vec()))
+}
+
+/// Decodes the raw data of a `ScheduleEpoch` account, discriminator included.
+pub fn decode_schedule_epoch(data: &[u8]) -> Result<ScheduleEpoch> {
+  ScheduleEpoch::try_deserialize(&mut &data[..])
+}
+
+/// Decodes the raw data of an `EpochClaim` account, discriminator included.
+pub fn decode_epoch_claim(data: &[u8]) -> Result<EpochClaim> {
+  EpochClaim::try_deserialize(&mut &data[..])
+}
+
+/// Decodes the raw data of a `ScheduleMint` account, discriminator included.
+pub fn decode_schedule_mint(data: &[u8]) -> Result<ScheduleMint> {
+  ScheduleMint::try_deserialize(&mut &data[..])
+}
+
+/// Decodes the raw data of a `ScheduleStake` account, discriminator included.
+pub fn decode_schedule_stake(data: &[u8]) -> Result<ScheduleStake> {
+  ScheduleStake::try_deserialize(&mut &data[..])
+}
+
+/// Decodes the raw data of a `ScheduleTranche` account, discriminator included.
+pub fn decode_schedule_tranche(data: &[u8]) -> Result<ScheduleTranche> {
+  ScheduleTranche::try_deserialize(&mut &data[..])
+}
+
+/// Decodes the raw data of a `ScheduleVoucher` account, discriminator included.
+pub fn decode_schedule_voucher(data: &[u8]) -> Result<ScheduleVoucher> {
+  ScheduleVoucher::try_deserialize(&mut &data[..])
+}
+
+/// Decodes the raw data of a `VaultReserve` account, discriminator included.
+pub fn decode_vault_reserve(data: &[u8]) -> Result<VaultReserve> {
+  VaultReserve::try_deserialize(&mut &data[..])
+}
+
+/// Decodes the raw data of a `VaultFee` account, discriminator included.
+pub fn decode_vault_fee(data: &[u8]) -> Result<VaultFee> {
+  VaultFee::try_deserialize(&mut &data[..])
+}
+
+/// Decodes the raw data of a `VaultAllowlist` account, discriminator included.
+pub fn decode_vault_allowlist(data: &[u8]) -> Result<VaultAllowlist> {
+  VaultAllowlist::try_deserialize(&mut &data[..])
+}
+
+/// Decodes the raw data of a `VaultDestinations` account, discriminator included.
+pub fn decode_vault_destinations(data: &[u8]) -> Result<VaultDestinations> {
+  VaultDestinations::try_deserialize(&mut &data[..])
+}
+
+/// Decodes the raw data of a `VaultSwap` account, discriminator included.
+pub fn decode_vault_swap(data: &[u8]) -> Result<VaultSwap> {
+  VaultSwap::try_deserialize(&mut &data[..])
+}
+
+/// Decodes the return data of a `get_claim_status` instruction.
+pub fn decode_claim_status(data: &[u8]) -> Result<ClaimStatus> {
+  Ok(ClaimStatus::try_from_slice(data)?)
+}
+
+/// Returns the byte offset of the redemption flag of `index` in a `Schedule` account of
+/// `user_count` entries, `None` when the index is out of range. Past the last entry lies
+/// the `version` byte, which must never be read as a flag.
+pub fn redemption_offset(index: u16, user_count: u16) -> Option<usize> {
+  if index >= user_count {
+    return None;
+  }
+  Some(SCHEDULE_REDEMPTIONS_OFFSET + usize::from(index))
+}
+
+/// Returns the `(offset, length)` data slice to request from the RPC node to read the
+/// redemption flag of `index` without fetching the whole `Schedule`. `user_count` can be
+/// read with `redemptions_len_data_slice` when not known already.
+pub fn redemption_data_slice(index: u16, user_count: u16) -> Option<(usize, usize)> {
+  redemption_offset(index, user_count).map(|offset| (offset, 1))
+}
+
+/// Returns the `(offset, length)` data slice holding the length prefix of
+/// `Schedule.redemptions`, that is the user count of the schedule.
+pub fn redemptions_len_data_slice() -> (usize, usize) {
+  (SCHEDULE_REDEMPTIONS_OFFSET - 4, 4)
+}
+
+/// Interprets the bytes returned for `redemptions_len_data_slice`.
+pub fn redemptions_len_from_slice(data: &[u8]) -> Option<u16> {
+  let data: [u8; 4] = data.try_into().ok()?;
+  u16::try_from(u32::from_le_bytes(data)).ok()
+}
+
+/// Interprets the single byte returned for `redemption_data_slice`.
+pub fn is_redeemed_from_slice(data: &[u8]) -> Option<bool> {
+  match data {
+    [0] => Some(false),
+    [1] => Some(true),
+    _ => None,
+  }
+}
@@ -0,0 +1,1640 @@
+use anchor_lang::{
+  AnchorSerialize,
+  InstructionData,
+};
+use solana_program::{
+  ed25519_program::{
+    ID as ED25519_PROGRAM_ID,
+  },
+  instruction::{
+    AccountMeta,
+    Instruction,
+  },
+  pubkey::{
+    Pubkey,
+  },
+  system_program::{
+    ID as SYSTEM_PROGRAM_ID,
+  },
+  sysvar::{
+    instructions::ID as SYSVAR_INSTRUCTIONS_ID,
+  },
+};
+use vault::external::mpl_bubblegum::{
+  spl_account_compression::ID as COMPRESSION_PROGRAM_ID,
+  spl_noop::ID as LOG_WRAPPER_ID,
+  ID as BUBBLEGUM_PROGRAM_ID,
+};
+use vault::external::mpl_token_metadata::{
+  ID as TOKEN_METADATA_PROGRAM_ID,
+};
+use vault::external::spl_associated_token::{
+  ID as ASSOCIATED_TOKEN_PROGRAM_ID,
+};
+use vault::external::spl_token::{
+  ID as TOKEN_PROGRAM_ID,
+};
+use vault::state::{
+  Voucher,
+};
+
+use crate::pda::{
+  find_epoch_claim_address,
+  find_schedule_address,
+  find_schedule_epoch_address,
+  find_schedule_mint_address,
+  find_schedule_stake_address,
+  find_schedule_tranche_address,
+  find_schedule_voucher_address,
+  find_vault_address,
+  find_vault_allowlist_address,
+  find_vault_destinations_address,
+  find_vault_fee_address,
+  find_vault_reserve_address,
+  find_vault_signer_address,
+  find_vault_swap_address,
+  get_master_edition_address,
+  get_metadata_address,
+};
+
+pub fn create_vault(
+  payer: &Pubkey,
+  vault_path: Vec<u8>,
+  program_id: &Pubkey,
+) -> Instruction {
+  let (vault, _) = find_vault_address(&vault_path, program_id);
+  let data = vault::instruction::CreateVault {
+    _vault_path: vault_path,
+  };
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new(*payer, true),
+      AccountMeta::new(vault, false),
+      AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ],
+    data: data.data(),
+  }
+}
+
+pub fn set_vault(
+  owner: &Pubkey,
+  vault: &Pubkey,
+  admins: Vec<Pubkey>,
+  program_id: &Pubkey,
+) -> Instruction {
+  let data = vault::instruction::SetVault {
+    admins,
+  };
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new_readonly(*owner, true),
+      AccountMeta::new(*vault, false),
+    ],
+    data: data.data(),
+  }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_schedule(
+  admin: &Pubkey,
+  vault: &Pubkey,
+  user_count: u16,
+  event_id: u64,
+  timestamp: i64,
+  merkle_root: [u8; 32],
+  use_multi_token: bool,
+  receiving_token_mint: &Pubkey,
+  receiving_token_account: &Pubkey,
+  sending_token_mint: &Pubkey,
+  sending_token_account: &Pubkey,
+  program_id: &Pubkey,
+) -> Instruction {
+  let (schedule, _) = find_schedule_address(event_id, program_id);
+  let (vault_allowlist, _) = find_vault_allowlist_address(vault, program_id);
+  let (vault_signer, _) = find_vault_signer_address(vault, program_id);
+  let (vault_destinations, _) = find_vault_destinations_address(vault, program_id);
+  let data = vault::instruction::CreateSchedule {
+    user_count,
+    event_id,
+    timestamp,
+    merkle_root,
+    use_multi_token,
+    receiving_token_mint: *receiving_token_mint,
+    receiving_token_account: *receiving_token_account,
+    sending_token_mint: *sending_token_mint,
+    sending_token_account: *sending_token_account,
+  };
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new(*admin, true),
+      AccountMeta::new_readonly(*vault, false),
+      AccountMeta::new(schedule, false),
+      AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+      AccountMeta::new_readonly(vault_allowlist, false),
+      AccountMeta::new_readonly(vault_signer, false),
+      AccountMeta::new_readonly(*receiving_token_account, false),
+      AccountMeta::new_readonly(*sending_token_account, false),
+      AccountMeta::new_readonly(vault_destinations, false),
+    ],
+    data: data.data(),
+  }
+}
+
+/// Creates a schedule of `merkle::redemption_nft_leaf` leaves. Unlike the other schedule
+/// creators it takes no vault allowlist nor token accounts.
+pub fn create_nft_schedule(
+  admin: &Pubkey,
+  vault: &Pubkey,
+  user_count: u16,
+  event_id: u64,
+  timestamp: i64,
+  merkle_root: [u8; 32],
+  program_id: &Pubkey,
+) -> Instruction {
+  let (schedule, _) = find_schedule_address(event_id, program_id);
+  let (vault_destinations, _) = find_vault_destinations_address(vault, program_id);
+  let data = vault::instruction::CreateNftSchedule {
+    user_count,
+    event_id,
+    timestamp,
+    merkle_root,
+  };
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new(*admin, true),
+      AccountMeta::new_readonly(*vault, false),
+      AccountMeta::new(schedule, false),
+      AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+      AccountMeta::new_readonly(vault_destinations, false),
+    ],
+    data: data.data(),
+  }
+}
+
+/// Creates a schedule without root, post the root of each epoch with `set_schedule_epoch`.
+pub fn create_epoch_schedule(
+  admin: &Pubkey,
+  vault: &Pubkey,
+  event_id: u64,
+  receiving_token_mint: &Pubkey,
+  receiving_token_account: &Pubkey,
+  program_id: &Pubkey,
+) -> Instruction {
+  let (schedule, _) = find_schedule_address(event_id, program_id);
+  let (schedule_epoch, _) = find_schedule_epoch_address(&schedule, program_id);
+  let (vault_allowlist, _) = find_vault_allowlist_address(vault, program_id);
+  let (vault_signer, _) = find_vault_signer_address(vault, program_id);
+  let (vault_destinations, _) = find_vault_destinations_address(vault, program_id);
+  let data = vault::instruction::CreateEpochSchedule {
+    event_id,
+    receiving_token_mint: *receiving_token_mint,
+    receiving_token_account: *receiving_token_account,
+  };
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new(*admin, true),
+      AccountMeta::new_readonly(*vault, false),
+      AccountMeta::new(schedule, false),
+      AccountMeta::new(schedule_epoch, false),
+      AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+      AccountMeta::new_readonly(vault_allowlist, false),
+      AccountMeta::new_readonly(vault_signer, false),
+      AccountMeta::new_readonly(*receiving_token_account, false),
+      AccountMeta::new_readonly(vault_destinations, false),
+    ],
+    data: data.data(),
+  }
+}
+
+/// Posts the root of `merkle::redemption_epoch_leaf` leaves for `epoch`, which must be
+/// greater than the current epoch of the schedule.
+pub fn set_schedule_epoch(
+  admin: &Pubkey,
+  vault: &Pubkey,
+  schedule: &Pubkey,
+  epoch: u64,
+  timestamp: i64,
+  merkle_root: [u8; 32],
+  program_id: &Pubkey,
+) -> Instruction {
+  let (schedule_epoch, _) = find_schedule_epoch_address(schedule, program_id);
+  let (vault_destinations, _) = find_vault_destinations_address(vault, program_id);
+  let data = vault::instruction::SetScheduleEpoch {
+    epoch,
+    timestamp,
+    merkle_root,
+  };
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new(*admin, true),
+      AccountMeta::new_readonly(*vault, false),
+      AccountMeta::new(*schedule, false),
+      AccountMeta::new(schedule_epoch, false),
+      AccountMeta::new_readonly(vault_destinations, false),
+    ],
+    data: data.data(),
+  }
+}
+
+/// Creates a schedule paying out vouchers signed by `voucher_signer`. Nonces of the
+/// vouchers must be lower than `nonce_count`.
+#[allow(clippy::too_many_arguments)]
+pub fn create_voucher_schedule(
+  admin: &Pubkey,
+  vault: &Pubkey,
+  event_id: u64,
+  nonce_count: u32,
+  timestamp: i64,
+  voucher_signer: &Pubkey,
+  receiving_token_mint: &Pubkey,
+  receiving_token_account: &Pubkey,
+  program_id: &Pubkey,
+) -> Instruction {
+  let (schedule, _) = find_schedule_address(event_id, program_id);
+  let (schedule_voucher, _) = find_schedule_voucher_address(&schedule, program_id);
+  let (vault_allowlist, _) = find_vault_allowlist_address(vault, program_id);
+  let (vault_signer, _) = find_vault_signer_address(vault, program_id);
+  let (vault_destinations, _) = find_vault_destinations_address(vault, program_id);
+  let data = vault::instruction::CreateVoucherSchedule {
+    event_id,
+    nonce_count,
+    timestamp,
+    voucher_signer: *voucher_signer,
+    receiving_token_mint: *receiving_token_mint,
+    receiving_token_account: *receiving_token_account,
+  };
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new(*admin, true),
+      AccountMeta::new_readonly(*vault, false),
+      AccountMeta::new(schedule, false),
+      AccountMeta::new(schedule_voucher, false),
+      AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+      AccountMeta::new_readonly(vault_allowlist, false),
+      AccountMeta::new_readonly(vault_signer, false),
+      AccountMeta::new_readonly(*receiving_token_account, false),
+      AccountMeta::new_readonly(vault_destinations, false),
+    ],
+    data: data.data(),
+  }
+}
+
+pub fn set_schedule_status(
+  admin: &Pubkey,
+  vault: &Pubkey,
+  schedule: &Pubkey,
+  is_active: bool,
+  program_id: &Pubkey,
+) -> Instruction {
+  let data = vault::instruction::SetScheduleStatus {
+    is_active,
+  };
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new(*admin, true),
+      AccountMeta::new_readonly(*vault, false),
+      AccountMeta::new(*schedule, false),
+    ],
+    data: data.data(),
+  }
+}
+
+/// Sets whether `redeem_token` and `redeem_token_multi` require `user_token` to be owned by
+/// the claiming user. The schedule must be migrated to the current layout first.
+pub fn set_schedule_owner_check(
+  admin: &Pubkey,
+  vault: &Pubkey,
+  schedule: &Pubkey,
+  verify_user_token_owner: bool,
+  program_id: &Pubkey,
+) -> Instruction {
+  let data = vault::instruction::SetScheduleOwnerCheck {
+    verify_user_token_owner,
+  };
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new(*admin, true),
+      AccountMeta::new_readonly(*vault, false),
+      AccountMeta::new(*schedule, false),
+    ],
+    data: data.data(),
+  }
+}
+
+/// Creates a `BitmapSchedule`, see `account::decode_bitmap_schedule`. Its redemptions are
+/// claimed with `redeem_token_bitmap` and proofs of `merkle::redemption_leaf`.
+#[allow(clippy::too_many_arguments)]
+pub fn create_bitmap_schedule(
+  admin: &Pubkey,
+  vault: &Pubkey,
+  user_count: u16,
+  event_id: u64,
+  timestamp: i64,
+  merkle_root: [u8; 32],
+  receiving_token_mint: &Pubkey,
+  receiving_token_account: &Pubkey,
+  sending_token_mint: &Pubkey,
+  sending_token_account: &Pubkey,
+  program_id: &Pubkey,
+) -> Instruction {
+  let (schedule, _) = find_schedule_address(event_id, program_id);
+  let (vault_allowlist, _) = find_vault_allowlist_address(vault, program_id);
+  let (vault_signer, _) = find_vault_signer_address(vault, program_id);
+  let (vault_destinations, _) = find_vault_destinations_address(vault, program_id);
+  let data = vault::instruction::CreateBitmapSchedule {
+    user_count,
+    event_id,
+    timestamp,
+    merkle_root,
+    receiving_token_mint: *receiving_token_mint,
+    receiving_token_account: *receiving_token_account,
+    sending_token_mint: *sending_token_mint,
+    sending_token_account: *sending_token_account,
+  };
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new(*admin, true),
+      AccountMeta::new_readonly(*vault, false),
+      AccountMeta::new(schedule, false),
+      AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+      AccountMeta::new_readonly(vault_allowlist, false),
+      AccountMeta::new_readonly(vault_signer, false),
+      AccountMeta::new_readonly(*receiving_token_account, false),
+      AccountMeta::new_readonly(*sending_token_account, false),
+      AccountMeta::new_readonly(vault_destinations, false),
+    ],
+    data: data.data(),
+  }
+}
+
+pub fn set_bitmap_schedule_status(
+  admin: &Pubkey,
+  vault: &Pubkey,
+  schedule: &Pubkey,
+  is_active: bool,
+  program_id: &Pubkey,
+) -> Instruction {
+  let data = vault::instruction::SetBitmapScheduleStatus {
+    is_active,
+  };
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new(*admin, true),
+      AccountMeta::new_readonly(*vault, false),
+      AccountMeta::new(*schedule, false),
+    ],
+    data: data.data(),
+  }
+}
+
+/// Sets whether `redeem_token_bitmap` requires `user_token` to be owned by the claiming user.
+pub fn set_bitmap_schedule_owner_check(
+  admin: &Pubkey,
+  vault: &Pubkey,
+  schedule: &Pubkey,
+  verify_user_token_owner: bool,
+  program_id: &Pubkey,
+) -> Instruction {
+  let data = vault::instruction::SetBitmapScheduleOwnerCheck {
+    verify_user_token_owner,
+  };
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new(*admin, true),
+      AccountMeta::new_readonly(*vault, false),
+      AccountMeta::new(*schedule, false),
+    ],
+    data: data.data(),
+  }
+}
+
+/// Appends a tranche of `user_count` redemptions to a merkle schedule. The tranche indexes
+/// start at the current length of `Schedule.redemptions`, see `account::decode_schedule_tranche`.
+pub fn append_schedule_tranche(
+  admin: &Pubkey,
+  vault: &Pubkey,
+  schedule: &Pubkey,
+  user_count: u16,
+  timestamp: i64,
+  merkle_root: [u8; 32],
+  program_id: &Pubkey,
+) -> Instruction {
+  let (schedule_tranche, _) = find_schedule_tranche_address(schedule, program_id);
+  let (vault_destinations, _) = find_vault_destinations_address(vault, program_id);
+  let data = vault::instruction::AppendScheduleTranche {
+    user_count,
+    timestamp,
+    merkle_root,
+  };
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new(*admin, true),
+      AccountMeta::new_readonly(*vault, false),
+      AccountMeta::new(*schedule, false),
+      AccountMeta::new(schedule_tranche, false),
+      AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+      AccountMeta::new_readonly(vault_destinations, false),
+    ],
+    data: data.data(),
+  }
+}
+
+/// Sets how much a mint-on-claim schedule can mint. The vault signer must be the mint
+/// authority of the receiving mint.
+pub fn set_schedule_mint(
+  admin: &Pubkey,
+  vault: &Pubkey,
+  schedule: &Pubkey,
+  mint_cap: u64,
+  program_id: &Pubkey,
+) -> Instruction {
+  let (schedule_mint, _) = find_schedule_mint_address(schedule, program_id);
+  let data = vault::instruction::SetScheduleMint {
+    mint_cap,
+  };
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new(*admin, true),
+      AccountMeta::new_readonly(*vault, false),
+      AccountMeta::new_readonly(*schedule, false),
+      AccountMeta::new(schedule_mint, false),
+      AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ],
+    data: data.data(),
+  }
+}
+
+/// Whitelists the staking program of `redeem_token_stake` and the accounts its deposit
+/// instruction may use, besides the user, `user_token0` and `user_stake`.
+pub fn set_schedule_stake(
+  admin: &Pubkey,
+  vault: &Pubkey,
+  schedule: &Pubkey,
+  stake_program: &Pubkey,
+  stake_accounts: Vec<Pubkey>,
+  program_id: &Pubkey,
+) -> Instruction {
+  let (schedule_stake, _) = find_schedule_stake_address(schedule, program_id);
+  let (vault_destinations, _) = find_vault_destinations_address(vault, program_id);
+  let data = vault::instruction::SetScheduleStake {
+    stake_program: *stake_program,
+    stake_accounts,
+  };
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new(*admin, true),
+      AccountMeta::new_readonly(*vault, false),
+      AccountMeta::new_readonly(*schedule, false),
+      AccountMeta::new(schedule_stake, false),
+      AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+      AccountMeta::new_readonly(vault_destinations, false),
+    ],
+    data: data.data(),
+  }
+}
+
+/// Sets the lamports the vault signer keeps on top of its rent exempt minimum.
+pub fn set_sol_reserve(
+  owner: &Pubkey,
+  vault: &Pubkey,
+  sol_reserve: u64,
+  program_id: &Pubkey,
+) -> Instruction {
+  let (vault_reserve, _) = find_vault_reserve_address(vault, program_id);
+  let data = vault::instruction::SetSolReserve {
+    sol_reserve,
+  };
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new(*owner, true),
+      AccountMeta::new_readonly(*vault, false),
+      AccountMeta::new(vault_reserve, false),
+      AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ],
+    data: data.data(),
+  }
+}
+
+/// Sets the lamports still owed to users by SOL schedules of the vault. Only the owner can
+/// lower them.
+pub fn set_sol_liabilities(
+  admin: &Pubkey,
+  vault: &Pubkey,
+  sol_liabilities: u64,
+  program_id: &Pubkey,
+) -> Instruction {
+  let (vault_reserve, _) = find_vault_reserve_address(vault, program_id);
+  let data = vault::instruction::SetSolLiabilities {
+    sol_liabilities,
+  };
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new(*admin, true),
+      AccountMeta::new_readonly(*vault, false),
+      AccountMeta::new(vault_reserve, false),
+      AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ],
+    data: data.data(),
+  }
+}
+
+/// Sets the claim fee charged on every redemption of the vault. `fee_bps` is withheld from
+/// `receiving_amount` and `fee_lamports` is paid by the user, both go to `treasury`.
+pub fn set_claim_fee(
+  owner: &Pubkey,
+  vault: &Pubkey,
+  treasury: &Pubkey,
+  fee_lamports: u64,
+  fee_bps: u16,
+  program_id: &Pubkey,
+) -> Instruction {
+  let (vault_fee, _) = find_vault_fee_address(vault, program_id);
+  let data = vault::instruction::SetClaimFee {
+    treasury: *treasury,
+    fee_lamports,
+    fee_bps,
+  };
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new(*owner, true),
+      AccountMeta::new_readonly(*vault, false),
+      AccountMeta::new(vault_fee, false),
+      AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ],
+    data: data.data(),
+  }
+}
+
+/// Sets the DEX program `redeem_token_swap` routes through.
+pub fn set_swap_program(
+  owner: &Pubkey,
+  vault: &Pubkey,
+  swap_program: &Pubkey,
+  program_id: &Pubkey,
+) -> Instruction {
+  let (vault_swap, _) = find_vault_swap_address(vault, program_id);
+  let data = vault::instruction::SetSwapProgram {
+    swap_program: *swap_program,
+  };
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new(*owner, true),
+      AccountMeta::new_readonly(*vault, false),
+      AccountMeta::new(vault_swap, false),
+      AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ],
+    data: data.data(),
+  }
+}
+
+/// Restricts the mints the vault deposits, distributes and withdraws to `mints`, an empty
+/// list allows any mint.
+pub fn set_allowed_mints(
+  owner: &Pubkey,
+  vault: &Pubkey,
+  mints: Vec<Pubkey>,
+  program_id: &Pubkey,
+) -> Instruction {
+  let (vault_allowlist, _) = find_vault_allowlist_address(vault, program_id);
+  let data = vault::instruction::SetAllowedMints {
+    mints,
+  };
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new(*owner, true),
+      AccountMeta::new_readonly(*vault, false),
+      AccountMeta::new(vault_allowlist, false),
+      AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ],
+    data: data.data(),
+  }
+}
+
+/// Approves `destination` as a withdrawal recipient once `DESTINATION_TIMELOCK` has passed.
+/// Token withdrawals are checked against the owner of the recipient token account. Once a
+/// destination is added, schedules are created and set up by the owner only.
+pub fn add_withdrawal_destination(
+  owner: &Pubkey,
+  vault: &Pubkey,
+  destination: &Pubkey,
+  program_id: &Pubkey,
+) -> Instruction {
+  let (vault_destinations, _) = find_vault_destinations_address(vault, program_id);
+  let data = vault::instruction::AddWithdrawalDestination {
+    destination: *destination,
+  };
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new(*owner, true),
+      AccountMeta::new_readonly(*vault, false),
+      AccountMeta::new(vault_destinations, false),
+      AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ],
+    data: data.data(),
+  }
+}
+
+/// Removes `destination` immediately. Without destinations left, `withdraw_sol` and
+/// `withdraw_token` fail until one is added again.
+pub fn remove_withdrawal_destination(
+  owner: &Pubkey,
+  vault: &Pubkey,
+  destination: &Pubkey,
+  program_id: &Pubkey,
+) -> Instruction {
+  let (vault_destinations, _) = find_vault_destinations_address(vault, program_id);
+  let data = vault::instruction::RemoveWithdrawalDestination {
+    destination: *destination,
+  };
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new_readonly(*owner, true),
+      AccountMeta::new_readonly(*vault, false),
+      AccountMeta::new(vault_destinations, false),
+    ],
+    data: data.data(),
+  }
+}
+
+/// Simulate this instruction and decode its return data as a little endian `u64`.
+pub fn get_withdrawable_sol(
+  vault: &Pubkey,
+  program_id: &Pubkey,
+) -> Instruction {
+  let (vault_signer, _) = find_vault_signer_address(vault, program_id);
+  let (vault_reserve, _) = find_vault_reserve_address(vault, program_id);
+  let data = vault::instruction::GetWithdrawableSol {};
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new_readonly(*vault, false),
+      AccountMeta::new_readonly(vault_signer, false),
+      AccountMeta::new_readonly(vault_reserve, false),
+    ],
+    data: data.data(),
+  }
+}
+
+/// Wraps `amount` lamports from `payer` into the vault's wrapped SOL token account.
+pub fn deposit_sol(
+  payer: &Pubkey,
+  vault: &Pubkey,
+  vault_token: &Pubkey,
+  amount: u64,
+  program_id: &Pubkey,
+) -> Instruction {
+  let (vault_signer, _) = find_vault_signer_address(vault, program_id);
+  let (vault_allowlist, _) = find_vault_allowlist_address(vault, program_id);
+  let data = vault::instruction::DepositSol {
+    amount,
+  };
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new(*payer, true),
+      AccountMeta::new_readonly(*vault, false),
+      AccountMeta::new_readonly(vault_signer, false),
+      AccountMeta::new(*vault_token, false),
+      AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+      AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+      AccountMeta::new_readonly(vault_allowlist, false),
+    ],
+    data: data.data(),
+  }
+}
+
+pub fn withdraw_sol(
+  admin: &Pubkey,
+  vault: &Pubkey,
+  recipient: &Pubkey,
+  amount: u64,
+  program_id: &Pubkey,
+) -> Instruction {
+  let (vault_signer, _) = find_vault_signer_address(vault, program_id);
+  let (vault_reserve, _) = find_vault_reserve_address(vault, program_id);
+  let (vault_destinations, _) = find_vault_destinations_address(vault, program_id);
+  let data = vault::instruction::WithdrawSol {
+    amount,
+  };
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new(*admin, true),
+      AccountMeta::new_readonly(*vault, false),
+      AccountMeta::new(vault_signer, false),
+      AccountMeta::new(*recipient, false),
+      AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+      AccountMeta::new_readonly(vault_reserve, false),
+      AccountMeta::new_readonly(vault_destinations, false),
+    ],
+    data: data.data(),
+  }
+}
+
+pub fn withdraw_token(
+  admin: &Pubkey,
+  vault: &Pubkey,
+  sender: &Pubkey,
+  recipient: &Pubkey,
+  amount: u64,
+  program_id: &Pubkey,
+) -> Instruction {
+  let (vault_signer, _) = find_vault_signer_address(vault, program_id);
+  let (vault_allowlist, _) = find_vault_allowlist_address(vault, program_id);
+  let (vault_destinations, _) = find_vault_destinations_address(vault, program_id);
+  let data = vault::instruction::WithdrawToken {
+    amount,
+  };
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new(*admin, true),
+      AccountMeta::new_readonly(*vault, false),
+      AccountMeta::new_readonly(vault_signer, false),
+      AccountMeta::new(*sender, false),
+      AccountMeta::new(*recipient, false),
+      AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+      AccountMeta::new_readonly(vault_allowlist, false),
+      AccountMeta::new_readonly(vault_destinations, false),
+    ],
+    data: data.data(),
+  }
+}
+
+/// Accounts required to create `user_token0` as the user's associated token account
+/// during a redemption.
+pub struct CreateUserTokenAccount {
+  pub mint: Pubkey,
+  /// Pays the rent of the new account, either the user or a relayer. Must sign.
+  pub payer: Pubkey,
+}
+
+/// Treasury accounts receiving the claim fee of the vault, see `account::decode_vault_fee`.
+pub struct ClaimFeeAccounts {
+  /// `VaultFee.treasury`, required when the vault charges `fee_lamports` or pays out SOL.
+  pub treasury: Pubkey,
+  /// Token account of the treasury for the receiving mint, required when `fee_bps` is set.
+  pub treasury_token: Option<Pubkey>,
+}
+
+/// Optional accounts of `redeem_token`, `redeem_token_bitmap` and `redeem_token_multi`.
+/// `Default` fits a schedule without `sending_amount`, claim fee, mint-on-claim nor tranches,
+/// whose user already has `user_token0`.
+#[derive(Default)]
+pub struct RedeemOptions {
+  /// Required when the schedule charges a `sending_amount` in an SPL token.
+  pub vault_token1: Option<Pubkey>,
+  /// Required when the schedule charges a `sending_amount` in an SPL token.
+  pub user_token1: Option<Pubkey>,
+  /// Creates `user_token0`, which must then be the associated token account of the user.
+  pub create_user_token0: Option<CreateUserTokenAccount>,
+  /// Required when the vault has a claim fee.
+  pub claim_fee: Option<ClaimFeeAccounts>,
+  /// Must be set for schedules created with the receiving mint as `receiving_token_account`,
+  /// `vault_token0` is then the mint. `redeem_token` only.
+  pub mint_on_claim: bool,
+  /// Must be set to redeem an index of a tranche added with `append_schedule_tranche`.
+  /// Not supported by `redeem_token_bitmap`.
+  pub use_tranches: bool,
+}
+
+/// See `RedeemOptions` for the accounts required by the schedule and the vault.
+#[allow(clippy::too_many_arguments)]
+pub fn redeem_token(
+  vault: &Pubkey,
+  schedule: &Pubkey,
+  index: u16,
+  timestamp: i64,
+  proofs: Vec<[u8; 32]>,
+  receiving_amount: u64,
+  sending_amount: u64,
+  vault_token0: &Pubkey,
+  user: &Pubkey,
+  user_token0: &Pubkey,
+  options: &RedeemOptions,
+  program_id: &Pubkey,
+) -> Instruction {
+  let (vault_signer, _) = find_vault_signer_address(vault, program_id);
+  let data = vault::instruction::RedeemToken {
+    index,
+    timestamp,
+    proofs,
+    receiving_amount,
+    sending_amount,
+  };
+
+  let mut accounts = vec![
+    AccountMeta::new_readonly(*vault, false),
+    AccountMeta::new(*schedule, false),
+    AccountMeta::new(vault_signer, false),
+    AccountMeta::new(*vault_token0, false),
+    AccountMeta::new(*user, true),
+    AccountMeta::new(*user_token0, false),
+    AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+    AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+  ];
+  let schedule_accounts = vec![
+    options.mint_on_claim.then(|| AccountMeta::new(find_schedule_mint_address(schedule, program_id).0, false)),
+    schedule_tranche_account(schedule, options.use_tranches, program_id),
+  ];
+  accounts.extend(redeem_optional_accounts(vault, options, schedule_accounts, program_id));
+
+  Instruction {
+    program_id: *program_id,
+    accounts,
+    data: data.data(),
+  }
+}
+
+/// Redeems from a schedule created with `create_bitmap_schedule`. See `RedeemOptions`, whose
+/// `mint_on_claim` and `use_tranches` are ignored as bitmap schedules support neither.
+#[allow(clippy::too_many_arguments)]
+pub fn redeem_token_bitmap(
+  vault: &Pubkey,
+  schedule: &Pubkey,
+  index: u16,
+  timestamp: i64,
+  proofs: Vec<[u8; 32]>,
+  receiving_amount: u64,
+  sending_amount: u64,
+  vault_token0: &Pubkey,
+  user: &Pubkey,
+  user_token0: &Pubkey,
+  options: &RedeemOptions,
+  program_id: &Pubkey,
+) -> Instruction {
+  let (vault_signer, _) = find_vault_signer_address(vault, program_id);
+  let data = vault::instruction::RedeemTokenBitmap {
+    index,
+    timestamp,
+    proofs,
+    receiving_amount,
+    sending_amount,
+  };
+
+  let mut accounts = vec![
+    AccountMeta::new_readonly(*vault, false),
+    AccountMeta::new(*schedule, false),
+    AccountMeta::new(vault_signer, false),
+    AccountMeta::new(*vault_token0, false),
+    AccountMeta::new(*user, true),
+    AccountMeta::new(*user_token0, false),
+    AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+    AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+  ];
+  accounts.extend(redeem_optional_accounts(vault, options, Vec::new(), program_id));
+
+  Instruction {
+    program_id: *program_id,
+    accounts,
+    data: data.data(),
+  }
+}
+
+/// See `RedeemOptions` for the accounts required by the schedule and the vault.
+#[allow(clippy::too_many_arguments)]
+pub fn redeem_token_multi(
+  vault: &Pubkey,
+  schedule: &Pubkey,
+  index: u16,
+  timestamp: i64,
+  proofs: Vec<[u8; 32]>,
+  receiving_token_mint: &Pubkey,
+  receiving_amount: u64,
+  sending_amount: u64,
+  vault_token0: &Pubkey,
+  user: &Pubkey,
+  user_token0: &Pubkey,
+  options: &RedeemOptions,
+  program_id: &Pubkey,
+) -> Instruction {
+  let (vault_signer, _) = find_vault_signer_address(vault, program_id);
+  let data = vault::instruction::RedeemTokenMulti {
+    index,
+    timestamp,
+    proofs,
+    receiving_token_mint: *receiving_token_mint,
+    receiving_amount,
+    sending_amount,
+  };
+
+  let mut accounts = vec![
+    AccountMeta::new_readonly(*vault, false),
+    AccountMeta::new(*schedule, false),
+    AccountMeta::new(vault_signer, false),
+    AccountMeta::new(*vault_token0, false),
+    AccountMeta::new(*user, true),
+    AccountMeta::new(*user_token0, false),
+    AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+    AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+  ];
+  let (vault_allowlist, _) = find_vault_allowlist_address(vault, program_id);
+  let schedule_accounts = vec![
+    schedule_tranche_account(schedule, options.use_tranches, program_id),
+    Some(AccountMeta::new_readonly(vault_allowlist, false)),
+  ];
+  accounts.extend(redeem_optional_accounts(vault, options, schedule_accounts, program_id));
+
+  Instruction {
+    program_id: *program_id,
+    accounts,
+    data: data.data(),
+  }
+}
+
+/// Redeems into `user_token0` then runs `swap_instruction`, which must be an instruction of
+/// the vault's swap program signed by `user`, swapping out of `user_token0` into
+/// `user_token_out`. See `redeem_token` for the other optional accounts.
+#[allow(clippy::too_many_arguments)]
+pub fn redeem_token_swap(
+  vault: &Pubkey,
+  schedule: &Pubkey,
+  index: u16,
+  timestamp: i64,
+  proofs: Vec<[u8; 32]>,
+  receiving_amount: u64,
+  sending_amount: u64,
+  output_mint: &Pubkey,
+  minimum_amount_out: u64,
+  vault_token0: &Pubkey,
+  vault_token1: Option<&Pubkey>,
+  user: &Pubkey,
+  user_token0: &Pubkey,
+  user_token1: Option<&Pubkey>,
+  user_token_out: &Pubkey,
+  claim_fee: Option<&ClaimFeeAccounts>,
+  use_tranches: bool,
+  swap_instruction: &Instruction,
+  program_id: &Pubkey,
+) -> Instruction {
+  let (vault_signer, _) = find_vault_signer_address(vault, program_id);
+  let (vault_swap, _) = find_vault_swap_address(vault, program_id);
+  let (vault_fee, _) = find_vault_fee_address(vault, program_id);
+  let data = vault::instruction::RedeemTokenSwap {
+    index,
+    timestamp,
+    proofs,
+    receiving_amount,
+    sending_amount,
+    output_mint: *output_mint,
+    minimum_amount_out,
+    swap_data: swap_instruction.data.clone(),
+  };
+
+  let mut accounts = vec![
+    AccountMeta::new_readonly(*vault, false),
+    AccountMeta::new(*schedule, false),
+    AccountMeta::new_readonly(vault_signer, false),
+    AccountMeta::new(*vault_token0, false),
+    AccountMeta::new(*user, true),
+    AccountMeta::new(*user_token0, false),
+    AccountMeta::new(*user_token_out, false),
+    AccountMeta::new_readonly(vault_swap, false),
+    AccountMeta::new_readonly(swap_instruction.program_id, false),
+    AccountMeta::new_readonly(vault_fee, false),
+    AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+    AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+  ];
+  accounts.extend(passthrough_optional_accounts(schedule, vault_token1, user_token1, claim_fee, use_tranches, program_id));
+  accounts.extend(swap_instruction.accounts.iter().cloned());
+
+  Instruction {
+    program_id: *program_id,
+    accounts,
+    data: data.data(),
+  }
+}
+
+/// Redeems into `user_token0` then runs `stake_instruction`, which must be an instruction of
+/// the schedule's staking program signed by `user`, depositing the tokens of `user_token0`
+/// into `user_stake`. See `redeem_token` for the other optional accounts.
+#[allow(clippy::too_many_arguments)]
+pub fn redeem_token_stake(
+  vault: &Pubkey,
+  schedule: &Pubkey,
+  index: u16,
+  timestamp: i64,
+  proofs: Vec<[u8; 32]>,
+  receiving_amount: u64,
+  sending_amount: u64,
+  vault_token0: &Pubkey,
+  vault_token1: Option<&Pubkey>,
+  user: &Pubkey,
+  user_token0: &Pubkey,
+  user_token1: Option<&Pubkey>,
+  user_stake: &Pubkey,
+  claim_fee: Option<&ClaimFeeAccounts>,
+  use_tranches: bool,
+  stake_instruction: &Instruction,
+  program_id: &Pubkey,
+) -> Instruction {
+  let (vault_signer, _) = find_vault_signer_address(vault, program_id);
+  let (schedule_stake, _) = find_schedule_stake_address(schedule, program_id);
+  let (vault_fee, _) = find_vault_fee_address(vault, program_id);
+  let data = vault::instruction::RedeemTokenStake {
+    index,
+    timestamp,
+    proofs,
+    receiving_amount,
+    sending_amount,
+    stake_data: stake_instruction.data.clone(),
+  };
+
+  let mut accounts = vec![
+    AccountMeta::new_readonly(*vault, false),
+    AccountMeta::new(*schedule, false),
+    AccountMeta::new_readonly(vault_signer, false),
+    AccountMeta::new(*vault_token0, false),
+    AccountMeta::new(*user, true),
+    AccountMeta::new(*user_token0, false),
+    AccountMeta::new(*user_stake, false),
+    AccountMeta::new_readonly(schedule_stake, false),
+    AccountMeta::new_readonly(stake_instruction.program_id, false),
+    AccountMeta::new_readonly(vault_fee, false),
+    AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+    AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+  ];
+  accounts.extend(passthrough_optional_accounts(schedule, vault_token1, user_token1, claim_fee, use_tranches, program_id));
+  accounts.extend(stake_instruction.accounts.iter().cloned());
+
+  Instruction {
+    program_id: *program_id,
+    accounts,
+    data: data.data(),
+  }
+}
+
+/// Transfers the NFT `asset` held by `vault_token0` to `user_token0`. `treasury` is required
+/// when the vault charges a flat claim fee.
+#[allow(clippy::too_many_arguments)]
+pub fn redeem_nft(
+  vault: &Pubkey,
+  schedule: &Pubkey,
+  index: u16,
+  timestamp: i64,
+  proofs: Vec<[u8; 32]>,
+  asset: &Pubkey,
+  vault_token0: &Pubkey,
+  user: &Pubkey,
+  user_token0: &Pubkey,
+  create_user_token0: Option<&CreateUserTokenAccount>,
+  treasury: Option<&Pubkey>,
+  program_id: &Pubkey,
+) -> Instruction {
+  let (vault_signer, _) = find_vault_signer_address(vault, program_id);
+  let (vault_fee, _) = find_vault_fee_address(vault, program_id);
+  let data = vault::instruction::RedeemNft {
+    index,
+    timestamp,
+    proofs,
+    asset: *asset,
+  };
+
+  let mut accounts = vec![
+    AccountMeta::new_readonly(*vault, false),
+    AccountMeta::new(*schedule, false),
+    AccountMeta::new_readonly(vault_signer, false),
+    AccountMeta::new(*vault_token0, false),
+    AccountMeta::new(*user, true),
+    AccountMeta::new(*user_token0, false),
+    AccountMeta::new_readonly(vault_fee, false),
+    AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+    AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+  ];
+  let mut optional_accounts = match create_user_token0 {
+    Some(create_user_token0) => vec![
+      Some(AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false)),
+      Some(AccountMeta::new_readonly(create_user_token0.mint, false)),
+      Some(AccountMeta::new(create_user_token0.payer, true)),
+    ],
+    None => vec![None, None, None],
+  };
+  optional_accounts.push(treasury.map(|pubkey| AccountMeta::new(*pubkey, false)));
+  while let Some(None) = optional_accounts.last() {
+    optional_accounts.pop();
+  }
+  accounts.extend(
+    optional_accounts.into_iter()
+      .map(|meta| meta.unwrap_or_else(|| AccountMeta::new_readonly(*program_id, false)))
+  );
+
+  Instruction {
+    program_id: *program_id,
+    accounts,
+    data: data.data(),
+  }
+}
+
+/// Accounts of the edition printed by `redeem_nft_edition`, see the Token Metadata
+/// `MintNewEditionFromMasterEditionViaToken` instruction.
+pub struct NewEditionAccounts {
+  /// Mint with a supply of one whose mint authority is the user.
+  pub new_mint: Pubkey,
+  pub new_metadata: Pubkey,
+  pub new_edition: Pubkey,
+  pub edition_mark: Pubkey,
+  /// Update authority of the master edition metadata.
+  pub new_metadata_update_authority: Pubkey,
+}
+
+/// Prints `edition` of the master edition `asset`, whose token is held by `vault_token0`.
+#[allow(clippy::too_many_arguments)]
+pub fn redeem_nft_edition(
+  vault: &Pubkey,
+  schedule: &Pubkey,
+  index: u16,
+  timestamp: i64,
+  proofs: Vec<[u8; 32]>,
+  asset: &Pubkey,
+  edition: u64,
+  vault_token0: &Pubkey,
+  user: &Pubkey,
+  new_edition: &NewEditionAccounts,
+  treasury: Option<&Pubkey>,
+  program_id: &Pubkey,
+) -> Instruction {
+  let (vault_signer, _) = find_vault_signer_address(vault, program_id);
+  let (vault_fee, _) = find_vault_fee_address(vault, program_id);
+  let data = vault::instruction::RedeemNftEdition {
+    index,
+    timestamp,
+    proofs,
+    asset: *asset,
+    edition,
+  };
+
+  let mut accounts = vec![
+    AccountMeta::new_readonly(*vault, false),
+    AccountMeta::new(*schedule, false),
+    AccountMeta::new_readonly(vault_signer, false),
+    AccountMeta::new_readonly(*vault_token0, false),
+    AccountMeta::new(*user, true),
+    AccountMeta::new(new_edition.new_metadata, false),
+    AccountMeta::new(new_edition.new_edition, false),
+    AccountMeta::new(get_master_edition_address(asset), false),
+    AccountMeta::new(new_edition.new_mint, false),
+    AccountMeta::new(new_edition.edition_mark, false),
+    AccountMeta::new_readonly(new_edition.new_metadata_update_authority, false),
+    AccountMeta::new_readonly(get_metadata_address(asset), false),
+    AccountMeta::new_readonly(vault_fee, false),
+    AccountMeta::new_readonly(TOKEN_METADATA_PROGRAM_ID, false),
+    AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+    AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+  ];
+  if let Some(treasury) = treasury {
+    accounts.push(AccountMeta::new(*treasury, false));
+  }
+
+  Instruction {
+    program_id: *program_id,
+    accounts,
+    data: data.data(),
+  }
+}
+
+/// Bubblegum leaf of the compressed NFT held by the vault signer, as returned by the
+/// DAS `getAssetProof` and `getAsset` methods.
+pub struct CompressedNftLeaf {
+  pub merkle_tree: Pubkey,
+  pub tree_authority: Pubkey,
+  /// The vault signer when the asset has no delegate.
+  pub leaf_delegate: Pubkey,
+  pub root: [u8; 32],
+  pub data_hash: [u8; 32],
+  pub creator_hash: [u8; 32],
+  pub nonce: u64,
+  pub leaf_index: u32,
+  /// Nodes of the proof in `merkle_tree`, without the canopy.
+  pub proof: Vec<Pubkey>,
+}
+
+/// Transfers a compressed NFT of the vault signer to `user`.
+#[allow(clippy::too_many_arguments)]
+pub fn redeem_compressed_nft(
+  vault: &Pubkey,
+  schedule: &Pubkey,
+  index: u16,
+  timestamp: i64,
+  proofs: Vec<[u8; 32]>,
+  user: &Pubkey,
+  leaf: &CompressedNftLeaf,
+  treasury: Option<&Pubkey>,
+  program_id: &Pubkey,
+) -> Instruction {
+  let (vault_signer, _) = find_vault_signer_address(vault, program_id);
+  let (vault_fee, _) = find_vault_fee_address(vault, program_id);
+  let data = vault::instruction::RedeemCompressedNft {
+    index,
+    timestamp,
+    proofs,
+    root: leaf.root,
+    data_hash: leaf.data_hash,
+    creator_hash: leaf.creator_hash,
+    nonce: leaf.nonce,
+    leaf_index: leaf.leaf_index,
+  };
+
+  let mut accounts = vec![
+    AccountMeta::new_readonly(*vault, false),
+    AccountMeta::new(*schedule, false),
+    AccountMeta::new_readonly(vault_signer, false),
+    AccountMeta::new(*user, true),
+    AccountMeta::new_readonly(leaf.leaf_delegate, false),
+    AccountMeta::new_readonly(leaf.tree_authority, false),
+    AccountMeta::new(leaf.merkle_tree, false),
+    AccountMeta::new_readonly(vault_fee, false),
+    AccountMeta::new_readonly(LOG_WRAPPER_ID, false),
+    AccountMeta::new_readonly(COMPRESSION_PROGRAM_ID, false),
+    AccountMeta::new_readonly(BUBBLEGUM_PROGRAM_ID, false),
+    AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    // always passed since the proof nodes follow it
+    match treasury {
+      Some(treasury) => AccountMeta::new(*treasury, false),
+      None => AccountMeta::new_readonly(*program_id, false),
+    },
+  ];
+  accounts.extend(leaf.proof.iter().map(|node| AccountMeta::new_readonly(*node, false)));
+
+  Instruction {
+    program_id: *program_id,
+    accounts,
+    data: data.data(),
+  }
+}
+
+/// Returns the message the voucher signer of `schedule` signs for `redeem_voucher`.
+pub fn voucher_message(
+  schedule: &Pubkey,
+  user: &Pubkey,
+  amount: u64,
+  nonce: u32,
+  expiry: i64,
+) -> Vec<u8> {
+  let voucher = Voucher {
+    schedule: *schedule,
+    user: *user,
+    amount,
+    nonce,
+    expiry,
+  };
+  voucher.try_to_vec().unwrap()
+}
+
+/// Ed25519 program instruction verifying `signature` of `message` by `signer`. Must be placed
+/// right before `redeem_voucher` in the transaction.
+pub fn ed25519_instruction(
+  signer: &Pubkey,
+  signature: &[u8; 64],
+  message: &[u8],
+) -> Instruction {
+  // header: signature count, padding and a single entry of signature offsets
+  let public_key_offset: u16 = 2 + 14;
+  let signature_offset = public_key_offset + 32;
+  let message_offset = signature_offset + 64;
+
+  let mut data = vec![1u8, 0u8];
+  for value in [
+    signature_offset,
+    u16::MAX,
+    public_key_offset,
+    u16::MAX,
+    message_offset,
+    message.len() as u16,
+    u16::MAX,
+  ] {
+    data.extend_from_slice(&value.to_le_bytes());
+  }
+  data.extend_from_slice(signer.as_ref());
+  data.extend_from_slice(signature);
+  data.extend_from_slice(message);
+
+  Instruction {
+    program_id: ED25519_PROGRAM_ID,
+    accounts: vec![],
+    data,
+  }
+}
+
+/// Pays out the voucher `voucher_message(schedule, user, amount, nonce, expiry)`. The
+/// transaction must verify its signature with `ed25519_instruction` right before this
+/// instruction. `claim_fee` is required when the vault has a claim fee.
+#[allow(clippy::too_many_arguments)]
+pub fn redeem_voucher(
+  vault: &Pubkey,
+  schedule: &Pubkey,
+  amount: u64,
+  nonce: u32,
+  expiry: i64,
+  vault_token0: &Pubkey,
+  user: &Pubkey,
+  user_token0: &Pubkey,
+  claim_fee: Option<&ClaimFeeAccounts>,
+  program_id: &Pubkey,
+) -> Instruction {
+  let (vault_signer, _) = find_vault_signer_address(vault, program_id);
+  let (schedule_voucher, _) = find_schedule_voucher_address(schedule, program_id);
+  let (vault_reserve, _) = find_vault_reserve_address(vault, program_id);
+  let (vault_fee, _) = find_vault_fee_address(vault, program_id);
+  let data = vault::instruction::RedeemVoucher {
+    amount,
+    nonce,
+    expiry,
+  };
+
+  let mut accounts = vec![
+    AccountMeta::new_readonly(*vault, false),
+    AccountMeta::new_readonly(*schedule, false),
+    AccountMeta::new(schedule_voucher, false),
+    AccountMeta::new(vault_signer, false),
+    AccountMeta::new(*vault_token0, false),
+    AccountMeta::new(*user, true),
+    AccountMeta::new(*user_token0, false),
+    AccountMeta::new_readonly(vault_fee, false),
+    AccountMeta::new_readonly(SYSVAR_INSTRUCTIONS_ID, false),
+    AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+    AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    AccountMeta::new(vault_reserve, false),
+  ];
+  if let Some(claim_fee) = claim_fee {
+    accounts.push(AccountMeta::new(claim_fee.treasury, false));
+    if let Some(treasury_token) = claim_fee.treasury_token {
+      accounts.push(AccountMeta::new(treasury_token, false));
+    }
+  }
+
+  Instruction {
+    program_id: *program_id,
+    accounts,
+    data: data.data(),
+  }
+}
+
+/// Pays out `cumulative_amount` minus what the user already claimed from the epoch schedule.
+/// `claim_fee` is required when the vault has a claim fee.
+#[allow(clippy::too_many_arguments)]
+pub fn redeem_epoch(
+  vault: &Pubkey,
+  schedule: &Pubkey,
+  proofs: Vec<[u8; 32]>,
+  cumulative_amount: u64,
+  vault_token0: &Pubkey,
+  user: &Pubkey,
+  user_token0: &Pubkey,
+  claim_fee: Option<&ClaimFeeAccounts>,
+  program_id: &Pubkey,
+) -> Instruction {
+  let (vault_signer, _) = find_vault_signer_address(vault, program_id);
+  let (schedule_epoch, _) = find_schedule_epoch_address(schedule, program_id);
+  let (epoch_claim, _) = find_epoch_claim_address(schedule, user, program_id);
+  let (vault_reserve, _) = find_vault_reserve_address(vault, program_id);
+  let (vault_fee, _) = find_vault_fee_address(vault, program_id);
+  let data = vault::instruction::RedeemEpoch {
+    proofs,
+    cumulative_amount,
+  };
+
+  let mut accounts = vec![
+    AccountMeta::new_readonly(*vault, false),
+    AccountMeta::new_readonly(*schedule, false),
+    AccountMeta::new_readonly(schedule_epoch, false),
+    AccountMeta::new(epoch_claim, false),
+    AccountMeta::new(vault_signer, false),
+    AccountMeta::new(*vault_token0, false),
+    AccountMeta::new(*user, true),
+    AccountMeta::new(*user_token0, false),
+    AccountMeta::new_readonly(vault_fee, false),
+    AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+    AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    AccountMeta::new(vault_reserve, false),
+  ];
+  if let Some(claim_fee) = claim_fee {
+    accounts.push(AccountMeta::new(claim_fee.treasury, false));
+    if let Some(treasury_token) = claim_fee.treasury_token {
+      accounts.push(AccountMeta::new(treasury_token, false));
+    }
+  }
+
+  Instruction {
+    program_id: *program_id,
+    accounts,
+    data: data.data(),
+  }
+}
+
+/// Simulate this instruction and decode its return data with
+/// `account::decode_claim_status`.
+pub fn get_claim_status(
+  schedule: &Pubkey,
+  index: u16,
+  program_id: &Pubkey,
+) -> Instruction {
+  let data = vault::instruction::GetClaimStatus {
+    index,
+  };
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new_readonly(*schedule, false),
+    ],
+    data: data.data(),
+  }
+}
+
+pub fn transfer_ownership(
+  owner: &Pubkey,
+  vault: &Pubkey,
+  new_owner: &Pubkey,
+  program_id: &Pubkey,
+) -> Instruction {
+  let data = vault::instruction::TransferOwnership {
+    new_owner: *new_owner,
+  };
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new_readonly(*owner, true),
+      AccountMeta::new(*vault, false),
+    ],
+    data: data.data(),
+  }
+}
+
+pub fn accept_ownership(
+  new_owner: &Pubkey,
+  vault: &Pubkey,
+  program_id: &Pubkey,
+) -> Instruction {
+  let data = vault::instruction::AcceptOwnership {};
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new_readonly(*new_owner, true),
+      AccountMeta::new(*vault, false),
+    ],
+    data: data.data(),
+  }
+}
+
+/// Moves a vault created before `Vault.version` to the current layout, the owner pays for the
+/// added space.
+pub fn migrate_vault(
+  owner: &Pubkey,
+  vault: &Pubkey,
+  program_id: &Pubkey,
+) -> Instruction {
+  let data = vault::instruction::MigrateVault {};
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new(*owner, true),
+      AccountMeta::new(*vault, false),
+      AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ],
+    data: data.data(),
+  }
+}
+
+/// Moves a schedule created before `Schedule.version` to the current layout, the admin pays
+/// for the added space.
+pub fn migrate_schedule(
+  admin: &Pubkey,
+  vault: &Pubkey,
+  schedule: &Pubkey,
+  program_id: &Pubkey,
+) -> Instruction {
+  let data = vault::instruction::MigrateSchedule {};
+
+  Instruction {
+    program_id: *program_id,
+    accounts: vec![
+      AccountMeta::new(*admin, true),
+      AccountMeta::new_readonly(*vault, false),
+      AccountMeta::new(*schedule, false),
+      AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ],
+    data: data.data(),
+  }
+}
+
+/// Optional accounts of the redeem instructions, omitted ones are replaced by the program id.
+/// The vault reserve and vault fee are always passed, the program requires them.
+/// `schedule_accounts` are the optional schedule PDAs following the treasury accounts.
+fn redeem_optional_accounts(
+  vault: &Pubkey,
+  options: &RedeemOptions,
+  schedule_accounts: Vec<Option<AccountMeta>>,
+  program_id: &Pubkey,
+) -> Vec<AccountMeta> {
+  let mut accounts = vec![
+    options.vault_token1.map(|pubkey| AccountMeta::new(pubkey, false)),
+    options.user_token1.map(|pubkey| AccountMeta::new(pubkey, false)),
+  ];
+  match &options.create_user_token0 {
+    Some(create_user_token0) => {
+      accounts.push(Some(AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false)));
+      accounts.push(Some(AccountMeta::new_readonly(create_user_token0.mint, false)));
+      accounts.push(Some(AccountMeta::new(create_user_token0.payer, true)));
+    },
+    None => accounts.extend([None, None, None]),
+  }
+  let (vault_reserve, _) = find_vault_reserve_address(vault, program_id);
+  accounts.push(Some(AccountMeta::new(vault_reserve, false)));
+  let (vault_fee, _) = find_vault_fee_address(vault, program_id);
+  accounts.push(Some(AccountMeta::new_readonly(vault_fee, false)));
+  let claim_fee = options.claim_fee.as_ref();
+  accounts.push(claim_fee.map(|claim_fee| AccountMeta::new(claim_fee.treasury, false)));
+  accounts.push(claim_fee.and_then(|claim_fee| claim_fee.treasury_token.map(|pubkey| AccountMeta::new(pubkey, false))));
+  accounts.extend(schedule_accounts);
+  while let Some(None) = accounts.last() {
+    accounts.pop();
+  }
+
+  accounts.into_iter()
+    .map(|meta| meta.unwrap_or_else(|| AccountMeta::new_readonly(*program_id, false)))
+    .collect()
+}
+
+fn schedule_tranche_account(schedule: &Pubkey, use_tranches: bool, program_id: &Pubkey) -> Option<AccountMeta> {
+  use_tranches.then(|| AccountMeta::new_readonly(find_schedule_tranche_address(schedule, program_id).0, false))
+}
+
+/// Optional accounts of `redeem_token_swap` and `redeem_token_stake`. They are all passed,
+/// omitted ones as the program id, since the accounts of the forwarded instruction follow them.
+fn passthrough_optional_accounts(
+  schedule: &Pubkey,
+  vault_token1: Option<&Pubkey>,
+  user_token1: Option<&Pubkey>,
+  claim_fee: Option<&ClaimFeeAccounts>,
+  use_tranches: bool,
+  program_id: &Pubkey,
+) -> Vec<AccountMeta> {
+  let mut accounts: Vec<AccountMeta> = vec![
+    vault_token1,
+    user_token1,
+    claim_fee.map(|claim_fee| &claim_fee.treasury),
+    claim_fee.and_then(|claim_fee| claim_fee.treasury_token.as_ref()),
+  ]
+    .into_iter()
+    .map(|pubkey| match pubkey {
+      Some(pubkey) => AccountMeta::new(*pubkey, false),
+      None => AccountMeta::new_readonly(*program_id, false),
+    })
+    .collect();
+  accounts.push(
+    schedule_tranche_account(schedule, use_tranches, program_id)
+      .unwrap_or_else(|| AccountMeta::new_readonly(*program_id, false))
+  );
+  accounts
+}
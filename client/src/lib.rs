@@ -0,0 +1,10 @@
+//! Rust counterpart of `services/vault_instruction.service.ts`: PDA helpers,
+//! instruction builders and account decoders for the `coin98_vault` program.
+
+pub mod account;
+pub mod instruction;
+pub mod lookup_table;
+pub mod merkle;
+pub mod pda;
+
+pub use vault::ID as PROGRAM_ID;
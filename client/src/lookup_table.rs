@@ -0,0 +1,128 @@
+use solana_program::{
+  address_lookup_table::{
+    instruction::{
+      create_lookup_table,
+      extend_lookup_table,
+    },
+    state::{
+      AddressLookupTable,
+    },
+    AddressLookupTableAccount,
+  },
+  clock::{
+    Slot,
+  },
+  hash::{
+    Hash,
+  },
+  instruction::{
+    Instruction,
+    InstructionError,
+  },
+  message::{
+    v0,
+    CompileError,
+    VersionedMessage,
+  },
+  pubkey::{
+    Pubkey,
+  },
+  system_program::{
+    ID as SYSTEM_PROGRAM_ID,
+  },
+  sysvar::{
+    instructions::ID as SYSVAR_INSTRUCTIONS_ID,
+  },
+};
+use vault::external::spl_associated_token::{
+  ID as ASSOCIATED_TOKEN_PROGRAM_ID,
+};
+use vault::external::spl_token::{
+  ID as TOKEN_PROGRAM_ID,
+};
+
+use crate::pda::{
+  find_vault_allowlist_address,
+  find_vault_fee_address,
+  find_vault_reserve_address,
+  find_vault_signer_address,
+};
+
+/// Addresses added by each extend instruction of `create_vault_lookup_table`, so that it fits
+/// in a transaction with the create instruction.
+pub const LOOKUP_TABLE_EXTEND_MAX: usize = 20;
+
+/// Returns the accounts shared by the redemptions of `vault`: the vault and its signer,
+/// reserve, fee and allowlist accounts, `schedules`, `vault_tokens`, and the programs and sysvars passed
+/// by the redeem builders. Signers and the invoked program are never loaded from a table.
+pub fn vault_lookup_table_addresses(
+  vault: &Pubkey,
+  schedules: &[Pubkey],
+  vault_tokens: &[Pubkey],
+  program_id: &Pubkey,
+) -> Vec<Pubkey> {
+  let mut addresses = vec![
+    *vault,
+    find_vault_signer_address(vault, program_id).0,
+    find_vault_reserve_address(vault, program_id).0,
+    find_vault_fee_address(vault, program_id).0,
+    find_vault_allowlist_address(vault, program_id).0,
+    TOKEN_PROGRAM_ID,
+    SYSTEM_PROGRAM_ID,
+    ASSOCIATED_TOKEN_PROGRAM_ID,
+    SYSVAR_INSTRUCTIONS_ID,
+  ];
+  for address in schedules.iter().chain(vault_tokens) {
+    if !addresses.contains(address) {
+      addresses.push(*address);
+    }
+  }
+  addresses
+}
+
+/// Returns the address of a new lookup table holding `addresses`, and the instructions
+/// creating it in batches that each fit in one transaction, to send in order. `recent_slot`
+/// must be a recent rooted slot, and the addresses can be looked up from the slot after the
+/// last batch.
+pub fn create_vault_lookup_table(
+  authority: &Pubkey,
+  payer: &Pubkey,
+  recent_slot: Slot,
+  addresses: &[Pubkey],
+) -> (Pubkey, Vec<Vec<Instruction>>) {
+  let (create_instruction, lookup_table) = create_lookup_table(*authority, *payer, recent_slot);
+
+  let mut batches = vec![vec![create_instruction]];
+  for (position, chunk) in addresses.chunks(LOOKUP_TABLE_EXTEND_MAX).enumerate() {
+    let extend_instruction = extend_lookup_table(lookup_table, *authority, Some(*payer), chunk.to_vec());
+    if position == 0 {
+      batches[0].push(extend_instruction);
+    } else {
+      batches.push(vec![extend_instruction]);
+    }
+  }
+
+  (lookup_table, batches)
+}
+
+/// Decodes the raw data of the lookup table account at `address`.
+pub fn decode_lookup_table(address: &Pubkey, data: &[u8]) -> Result<AddressLookupTableAccount, InstructionError> {
+  let lookup_table = AddressLookupTable::deserialize(data)?;
+  Ok(AddressLookupTableAccount {
+    key: *address,
+    addresses: lookup_table.addresses.to_vec(),
+  })
+}
+
+/// Compiles `instructions`, usually a redemption from `instruction`, into a v0 message paid by
+/// `payer` that loads the accounts found in `lookup_tables` from them. Sign it with
+/// `VersionedTransaction::try_new`.
+pub fn compile_message_v0(
+  payer: &Pubkey,
+  instructions: &[Instruction],
+  lookup_tables: &[AddressLookupTableAccount],
+  recent_blockhash: Hash,
+) -> Result<VersionedMessage, CompileError> {
+  let message = v0::Message::try_compile(payer, instructions, lookup_tables, recent_blockhash)?;
+  Ok(VersionedMessage::V0(message))
+}
@@ -0,0 +1,170 @@
+use anchor_lang::{
+  AnchorSerialize,
+};
+use solana_program::{
+  keccak::{
+    hash,
+    hashv,
+  },
+  pubkey::{
+    Pubkey,
+  },
+};
+use vault::state::{
+  RedemptionEpochParams,
+  RedemptionMultiParams,
+  RedemptionMultiParamsV2,
+  RedemptionNftParams,
+  RedemptionParams,
+  RedemptionParamsV2,
+};
+
+/// Merkle tree matching `shared::verify_proof` on-chain: every pair of nodes is
+/// sorted before hashing, and the last node of an odd-sized layer is promoted
+/// to the next layer unchanged.
+pub struct MerkleTree {
+  layers: Vec<Vec<[u8; 32]>>,
+}
+
+impl MerkleTree {
+  pub fn new(leaves: Vec<[u8; 32]>) -> Self {
+    assert!(!leaves.is_empty(), "Coin98Vault: Merkle tree requires at least one leaf");
+
+    let mut layers = vec![leaves];
+    while layers.last().unwrap().len() > 1 {
+      let layer = layers.last().unwrap();
+      let next = layer.chunks(2)
+        .map(|pair| match pair {
+          [left, right] => hash_pair(left, right),
+          [single] => *single,
+          _ => unreachable!(),
+        })
+        .collect();
+      layers.push(next);
+    }
+
+    MerkleTree {
+      layers,
+    }
+  }
+
+  pub fn root(&self) -> [u8; 32] {
+    self.layers.last().unwrap()[0]
+  }
+
+  pub fn leaf_count(&self) -> usize {
+    self.layers[0].len()
+  }
+
+  /// Returns the sibling hashes on the branch from the leaf at `index` to the root.
+  pub fn proof(&self, index: usize) -> Vec<[u8; 32]> {
+    let mut proofs = Vec::new();
+    let mut current_index = index;
+    for layer in &self.layers[..self.layers.len() - 1] {
+      let sibling_index = current_index ^ 1;
+      if sibling_index < layer.len() {
+        proofs.push(layer[sibling_index]);
+      }
+      current_index /= 2;
+    }
+    proofs
+  }
+}
+
+fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+  if left < right {
+    hashv(&[left, right]).to_bytes()
+  } else {
+    hashv(&[right, left]).to_bytes()
+  }
+}
+
+/// Hashes a `redeem_token` leaf. `timestamp` is `None` for schedules created with a
+/// non-zero unlock time, which use the leaf layout without timestamp.
+pub fn redemption_leaf(
+  index: u16,
+  timestamp: Option<i64>,
+  address: &Pubkey,
+  receiving_amount: u64,
+  sending_amount: u64,
+) -> [u8; 32] {
+  let data = match timestamp {
+    Some(timestamp) => RedemptionParamsV2 {
+      index,
+      timestamp,
+      address: *address,
+      receiving_amount,
+      sending_amount,
+    }.try_to_vec(),
+    None => RedemptionParams {
+      index,
+      address: *address,
+      receiving_amount,
+      sending_amount,
+    }.try_to_vec(),
+  };
+  hash(&data.unwrap()).to_bytes()
+}
+
+/// Hashes a `redeem_token_multi` leaf, see `redemption_leaf`.
+pub fn redemption_multi_leaf(
+  index: u16,
+  timestamp: Option<i64>,
+  address: &Pubkey,
+  receiving_token_mint: &Pubkey,
+  receiving_amount: u64,
+  sending_amount: u64,
+) -> [u8; 32] {
+  let data = match timestamp {
+    Some(timestamp) => RedemptionMultiParamsV2 {
+      index,
+      timestamp,
+      address: *address,
+      receiving_token_mint: *receiving_token_mint,
+      receiving_amount,
+      sending_amount,
+    }.try_to_vec(),
+    None => RedemptionMultiParams {
+      index,
+      address: *address,
+      receiving_token_mint: *receiving_token_mint,
+      receiving_amount,
+      sending_amount,
+    }.try_to_vec(),
+  };
+  hash(&data.unwrap()).to_bytes()
+}
+
+/// Hashes a leaf of an NFT schedule. `asset` is the NFT mint, the master edition mint when
+/// `edition` is not zero, or the asset id of a compressed NFT.
+pub fn redemption_nft_leaf(
+  index: u16,
+  timestamp: i64,
+  address: &Pubkey,
+  asset: &Pubkey,
+  edition: u64,
+) -> [u8; 32] {
+  let data = RedemptionNftParams {
+    index,
+    timestamp,
+    address: *address,
+    asset: *asset,
+    edition,
+  }.try_to_vec();
+  hash(&data.unwrap()).to_bytes()
+}
+
+/// Hashes a leaf of an epoch schedule. `cumulative_amount` is the total entitlement of
+/// `address` up to and including `epoch`.
+pub fn redemption_epoch_leaf(
+  epoch: u64,
+  address: &Pubkey,
+  cumulative_amount: u64,
+) -> [u8; 32] {
+  let data = RedemptionEpochParams {
+    epoch,
+    address: *address,
+    cumulative_amount,
+  }.try_to_vec();
+  hash(&data.unwrap()).to_bytes()
+}
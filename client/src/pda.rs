@@ -0,0 +1,192 @@
+use solana_program::{
+  hash::{
+    hash,
+  },
+  pubkey::{
+    Pubkey,
+  },
+};
+use vault::constant::{
+  ALLOWLIST_SEED_1,
+  CLAIM_SEED_1,
+  DESTINATION_SEED_1,
+  EPOCH_SEED_1,
+  FEE_SEED_1,
+  MINT_SEED_1,
+  RESERVE_SEED_1,
+  SCHEDULE_SEED_1,
+  SIGNER_SEED_1,
+  STAKE_SEED_1,
+  SWAP_SEED_1,
+  TRANCHE_SEED_1,
+  VAULT_SEED_1,
+  VOUCHER_SEED_1,
+};
+use vault::shared::{
+  derive_event_id,
+};
+
+pub use vault::external::mpl_bubblegum::{
+  get_asset_id,
+};
+pub use vault::external::mpl_token_metadata::{
+  get_master_edition_address,
+  get_metadata_address,
+};
+pub use vault::external::spl_associated_token::{
+  get_associated_token_address,
+};
+
+/// Returns the `vault_path` used by the TypeScript services for a vault name.
+pub fn find_vault_derivation_path(name: &str) -> Vec<u8> {
+  hash(name.as_bytes()).to_bytes().to_vec()
+}
+
+pub fn find_schedule_derivation_path(event_id: u64) -> [u8; 8] {
+  derive_event_id(event_id)
+}
+
+pub fn find_vault_address(vault_path: &[u8], program_id: &Pubkey) -> (Pubkey, u8) {
+  Pubkey::find_program_address(
+    &[
+      VAULT_SEED_1,
+      vault_path,
+    ],
+    program_id,
+  )
+}
+
+pub fn find_vault_signer_address(vault: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+  Pubkey::find_program_address(
+    &[
+      SIGNER_SEED_1,
+      vault.as_ref(),
+    ],
+    program_id,
+  )
+}
+
+pub fn find_schedule_address(event_id: u64, program_id: &Pubkey) -> (Pubkey, u8) {
+  Pubkey::find_program_address(
+    &[
+      SCHEDULE_SEED_1,
+      &find_schedule_derivation_path(event_id),
+    ],
+    program_id,
+  )
+}
+
+/// The reserve account may not exist yet, the vault then keeps no SOL reserve.
+pub fn find_vault_reserve_address(vault: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+  Pubkey::find_program_address(
+    &[
+      RESERVE_SEED_1,
+      vault.as_ref(),
+    ],
+    program_id,
+  )
+}
+
+/// The fee account may not exist yet, redemptions are then free of claim fees.
+pub fn find_vault_fee_address(vault: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+  Pubkey::find_program_address(
+    &[
+      FEE_SEED_1,
+      vault.as_ref(),
+    ],
+    program_id,
+  )
+}
+
+/// The allowlist account may not exist yet, the vault then accepts any mint.
+pub fn find_vault_allowlist_address(vault: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+  Pubkey::find_program_address(
+    &[
+      ALLOWLIST_SEED_1,
+      vault.as_ref(),
+    ],
+    program_id,
+  )
+}
+
+/// The destinations account may not exist yet, the vault then withdraws to any recipient.
+pub fn find_vault_destinations_address(vault: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+  Pubkey::find_program_address(
+    &[
+      DESTINATION_SEED_1,
+      vault.as_ref(),
+    ],
+    program_id,
+  )
+}
+
+pub fn find_vault_swap_address(vault: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+  Pubkey::find_program_address(
+    &[
+      SWAP_SEED_1,
+      vault.as_ref(),
+    ],
+    program_id,
+  )
+}
+
+pub fn find_schedule_stake_address(schedule: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+  Pubkey::find_program_address(
+    &[
+      STAKE_SEED_1,
+      schedule.as_ref(),
+    ],
+    program_id,
+  )
+}
+
+pub fn find_schedule_mint_address(schedule: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+  Pubkey::find_program_address(
+    &[
+      MINT_SEED_1,
+      schedule.as_ref(),
+    ],
+    program_id,
+  )
+}
+
+pub fn find_schedule_voucher_address(schedule: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+  Pubkey::find_program_address(
+    &[
+      VOUCHER_SEED_1,
+      schedule.as_ref(),
+    ],
+    program_id,
+  )
+}
+
+pub fn find_schedule_tranche_address(schedule: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+  Pubkey::find_program_address(
+    &[
+      TRANCHE_SEED_1,
+      schedule.as_ref(),
+    ],
+    program_id,
+  )
+}
+
+pub fn find_schedule_epoch_address(schedule: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+  Pubkey::find_program_address(
+    &[
+      EPOCH_SEED_1,
+      schedule.as_ref(),
+    ],
+    program_id,
+  )
+}
+
+pub fn find_epoch_claim_address(schedule: &Pubkey, user: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+  Pubkey::find_program_address(
+    &[
+      CLAIM_SEED_1,
+      schedule.as_ref(),
+      user.as_ref(),
+    ],
+    program_id,
+  )
+}
@@ -0,0 +1,108 @@
+//! Known vectors of `MerkleTree`, computed with an independent keccak256 implementation of
+//! the same construction as the TS `MerkleDistributionService`: sorted pair hashing and the
+//! last node of an odd-sized layer promoted unchanged.
+
+use solana_program::{
+  pubkey::{
+    Pubkey,
+  },
+};
+use vault::shared::{
+  verify_proof,
+};
+use vault_client::merkle::{
+  redemption_leaf,
+  MerkleTree,
+};
+
+fn node(value: &str) -> [u8; 32] {
+  let mut bytes = [0u8; 32];
+  hex::decode_to_slice(value, &mut bytes).unwrap();
+  bytes
+}
+
+/// Leaves `[1; 32]`, `[2; 32]`, ... up to `count`.
+fn leaves(count: u8) -> Vec<[u8; 32]> {
+  (1..=count).map(|value| [value; 32]).collect()
+}
+
+fn assert_proofs_verify(merkle_tree: &MerkleTree, leaves: &[[u8; 32]]) {
+  for (index, leaf) in leaves.iter().enumerate() {
+    assert!(verify_proof(merkle_tree.proof(index), merkle_tree.root(), *leaf), "leaf #{}", index);
+  }
+}
+
+#[test]
+fn redemption_leaf_vectors() {
+  let address = Pubkey::new_from_array([1; 32]);
+
+  assert_eq!(
+    redemption_leaf(0, None, &address, 100, 0),
+    node("c11a58ff7970acc879bf248febd675e342fe6fb3dfa0e79102982203830e0c31"),
+  );
+  assert_eq!(
+    redemption_leaf(1, Some(1_700_000_000), &address, 200, 5),
+    node("673a119e4c315927028024bebe264ebf3af6379f645612e07b4544909f12f4ce"),
+  );
+}
+
+#[test]
+fn single_leaf_is_root() {
+  let merkle_tree = MerkleTree::new(leaves(1));
+
+  assert_eq!(merkle_tree.root(), [1; 32]);
+  assert_eq!(merkle_tree.leaf_count(), 1);
+  assert!(merkle_tree.proof(0).is_empty());
+  assert!(verify_proof(Vec::new(), merkle_tree.root(), [1; 32]));
+}
+
+#[test]
+fn pairs_are_sorted_before_hashing() {
+  let root = node("346d8c96a2454213fcc0daff3c96ad0398148181b9fa6488f7ae2c0af5b20aa0");
+
+  let merkle_tree = MerkleTree::new(leaves(2));
+  assert_eq!(merkle_tree.root(), root);
+  assert_eq!(merkle_tree.proof(0), vec![[2; 32]]);
+  assert_eq!(merkle_tree.proof(1), vec![[1; 32]]);
+  assert_proofs_verify(&merkle_tree, &leaves(2));
+
+  let merkle_tree = MerkleTree::new(vec![[2; 32], [1; 32]]);
+  assert_eq!(merkle_tree.root(), root);
+}
+
+#[test]
+fn odd_node_is_promoted() {
+  let merkle_tree = MerkleTree::new(leaves(3));
+
+  assert_eq!(merkle_tree.root(), node("1d614fa3c8de62938b0948972494f9a3858575db69ce1d34c77926f30732c981"));
+  assert_eq!(merkle_tree.proof(0), vec![[2; 32], [3; 32]]);
+  assert_eq!(merkle_tree.proof(1), vec![[1; 32], [3; 32]]);
+  // the promoted leaf has no sibling on the first layer
+  assert_eq!(merkle_tree.proof(2), vec![node("346d8c96a2454213fcc0daff3c96ad0398148181b9fa6488f7ae2c0af5b20aa0")]);
+  assert_proofs_verify(&merkle_tree, &leaves(3));
+}
+
+#[test]
+fn odd_node_is_promoted_across_layers() {
+  let merkle_tree = MerkleTree::new(leaves(5));
+
+  assert_eq!(merkle_tree.root(), node("55d458ff264cc10cb4ef71d27a18db86f587999b2355cfa5eb5fccf8c973bced"));
+  assert_eq!(merkle_tree.proof(2), vec![
+    [4; 32],
+    node("346d8c96a2454213fcc0daff3c96ad0398148181b9fa6488f7ae2c0af5b20aa0"),
+    [5; 32],
+  ]);
+  // promoted twice, the last leaf is only paired at the root
+  assert_eq!(merkle_tree.proof(4), vec![node("0b242b9a6559f2d9f8563485a0697b746ec58ce879e0e5ac94d4c8a250723121")]);
+  assert_proofs_verify(&merkle_tree, &leaves(5));
+}
+
+#[test]
+fn tampered_proof_does_not_verify() {
+  let merkle_tree = MerkleTree::new(leaves(5));
+
+  let mut proofs = merkle_tree.proof(1);
+  proofs[1][0] ^= 1;
+  assert!(!verify_proof(proofs, merkle_tree.root(), [2; 32]));
+  assert!(!verify_proof(merkle_tree.proof(1), merkle_tree.root(), [3; 32]));
+}
@@ -0,0 +1,152 @@
+//! Verifies claims against trees built by `vault_client::merkle`: `verify_proof` accepts,
+//! for single and multi token leaves, exactly the untampered leaves of unredeemed indexes within
+//! the schedule, and returns an error for everything else.
+
+#![no_main]
+
+use arbitrary::{
+  Arbitrary,
+};
+use libfuzzer_sys::{
+  fuzz_target,
+};
+use solana_program::{
+  program_stubs::{
+    self,
+    SyscallStubs,
+  },
+  pubkey::{
+    Pubkey,
+  },
+};
+use std::{
+  sync::{
+    Once,
+  },
+};
+use vault::constant::{
+  SCHEDULE_VERSION,
+};
+use vault::state::{
+  ObjType,
+  Schedule,
+};
+use vault_client::merkle::{
+  redemption_leaf,
+  redemption_multi_leaf,
+  MerkleTree,
+};
+
+const MAX_LEAVES: usize = 64;
+
+#[derive(Arbitrary, Debug)]
+struct Leaf {
+  address: [u8; 32],
+  receiving_amount: u64,
+  sending_amount: u64,
+}
+
+#[derive(Arbitrary, Debug)]
+struct Claim {
+  /// Leaf whose address and proof are used
+  leaf: u8,
+  /// Index claimed, defaults to the index of the leaf
+  index: Option<u16>,
+  /// Amount claimed, defaults to the amount of the leaf
+  receiving_amount: Option<u64>,
+  /// Byte of the proof flipped with the given mask
+  tampered_proof: Option<(u16, u8)>,
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+  leaves: Vec<Leaf>,
+  timestamp: Option<i64>,
+  /// Verifies a multi token leaf with this mint when set
+  receiving_token_mint: Option<[u8; 32]>,
+  /// Length of `Schedule.redemptions`, which may be shorter than the tree
+  user_count: u8,
+  redemptions: Vec<bool>,
+  claim: Claim,
+}
+
+/// Drops the logs of `msg!`, which the default stubs print to stdout.
+struct SilentStubs;
+
+impl SyscallStubs for SilentStubs {
+  fn sol_log(&self, _message: &str) {}
+}
+
+fn leaf_hash(index: u16, input: &Input, leaf: &Leaf, receiving_amount: u64) -> [u8; 32] {
+  let address = Pubkey::new_from_array(leaf.address);
+  match input.receiving_token_mint {
+    Some(mint) => redemption_multi_leaf(index, input.timestamp, &address, &Pubkey::new_from_array(mint), receiving_amount, leaf.sending_amount),
+    None => redemption_leaf(index, input.timestamp, &address, receiving_amount, leaf.sending_amount),
+  }
+}
+
+fuzz_target!(|input: Input| {
+  static SILENCE_LOGS: Once = Once::new();
+  SILENCE_LOGS.call_once(|| {
+    program_stubs::set_syscall_stubs(Box::new(SilentStubs));
+  });
+
+  let leaf_count = input.leaves.len().min(MAX_LEAVES);
+  if leaf_count == 0 {
+    return;
+  }
+  let leaves = &input.leaves[..leaf_count];
+  let merkle_tree = MerkleTree::new(
+    leaves.iter()
+      .enumerate()
+      .map(|(index, leaf)| leaf_hash(index as u16, &input, leaf, leaf.receiving_amount))
+      .collect()
+  );
+
+  let redemptions: Vec<bool> = (0..usize::from(input.user_count))
+    .map(|index| input.redemptions.get(index).copied().unwrap_or(false))
+    .collect();
+  let schedule = Schedule {
+    obj_type: if input.receiving_token_mint.is_some() { ObjType::DistributionMulti } else { ObjType::Distribution },
+    nonce: 0,
+    event_id: 0,
+    vault_id: Pubkey::default(),
+    timestamp: 0,
+    merkle_root: merkle_tree.root().to_vec(),
+    receiving_token_mint: Pubkey::default(),
+    receiving_token_account: Pubkey::default(),
+    sending_token_mint: Pubkey::default(),
+    sending_token_account: Pubkey::default(),
+    is_active: true,
+    redemptions: redemptions.clone(),
+    version: SCHEDULE_VERSION,
+    verify_user_token_owner: false,
+    user_count: u16::from(input.user_count),
+  };
+
+  let claim = &input.claim;
+  let leaf_index = usize::from(claim.leaf) % leaf_count;
+  let leaf = &leaves[leaf_index];
+  let index = claim.index.unwrap_or(leaf_index as u16);
+  let receiving_amount = claim.receiving_amount.unwrap_or(leaf.receiving_amount);
+  let mut proofs = merkle_tree.proof(leaf_index);
+  let mut is_tampered = false;
+  if let Some((position, mask)) = claim.tampered_proof {
+    if !proofs.is_empty() && mask != 0 {
+      let position = usize::from(position) % (32 * proofs.len());
+      proofs[position / 32][position % 32] ^= mask;
+      is_tampered = true;
+    }
+  }
+
+  let user = Pubkey::new_from_array(leaf.address);
+  let redemption_data = match input.receiving_token_mint {
+    Some(mint) => vault::redemption_multi_data(index, input.timestamp, &user, Pubkey::new_from_array(mint), receiving_amount, leaf.sending_amount),
+    None => vault::redemption_data(index, input.timestamp, &user, receiving_amount, leaf.sending_amount),
+  };
+  let result = vault::verify_proof(index, &redemption_data, &proofs, &schedule, None);
+
+  let is_valid_leaf = !is_tampered && usize::from(index) == leaf_index && receiving_amount == leaf.receiving_amount;
+  let is_redeemable = redemptions.get(usize::from(index)) == Some(&false);
+  assert_eq!(result.is_ok(), is_valid_leaf && is_redeemable, "{:?}", result);
+});
@@ -0,0 +1,295 @@
+//! Runs sequences of `redeem_token` and `redeem_token_multi` against a local bank and checks
+//! them against a model of the schedules: only untampered leaves of unredeemed indexes are
+//! paid, no index is redeemed twice, and the vault balance decreases by exactly the paid
+//! amounts.
+//!
+//! The bank is shared by every input, each input creates its own pair of schedules. The panic
+//! hook of libfuzzer-sys aborts on a panic of the program too, even on the bank thread.
+
+#![no_main]
+
+#[path = "../../programs/vault/tests/common/mod.rs"]
+mod common;
+
+use arbitrary::{
+  Arbitrary,
+};
+use libfuzzer_sys::{
+  fuzz_target,
+};
+use solana_program::{
+  instruction::{
+    Instruction,
+  },
+  native_token::{
+    LAMPORTS_PER_SOL,
+  },
+  pubkey::{
+    Pubkey,
+  },
+  system_program,
+};
+use solana_program_test::{
+  BanksClientError,
+};
+use solana_sdk::{
+  compute_budget::{
+    ComputeBudgetInstruction,
+  },
+  signature::{
+    Keypair,
+    Signer,
+  },
+  signer::keypair::{
+    keypair_from_seed,
+  },
+};
+use std::{
+  cell::{
+    RefCell,
+  },
+};
+use tokio::runtime::{
+  Builder,
+  Runtime,
+};
+use vault_client::account::{
+  decode_schedule,
+};
+use vault_client::instruction::{
+  self as vault_instruction,
+  RedeemOptions,
+};
+use vault_client::merkle::{
+  redemption_leaf,
+  redemption_multi_leaf,
+  MerkleTree,
+};
+use vault_client::pda::{
+  find_schedule_address,
+};
+
+use common::{
+  VaultTest,
+  PROGRAM_ID,
+};
+
+const MAX_RECIPIENTS: usize = 16;
+const MAX_ACTIONS: usize = 32;
+
+#[derive(Arbitrary, Debug)]
+struct Action {
+  /// Redeems from the `DistributionMulti` schedule instead of the `Distribution` one
+  multi: bool,
+  /// Leaf whose user and proof are used
+  recipient: u8,
+  /// Index claimed, defaults to the index of the leaf
+  index: Option<u8>,
+  /// Amount claimed, defaults to the amount of the leaf
+  receiving_amount: Option<u32>,
+  /// Byte of the proof flipped with the given mask
+  tampered_proof: Option<(u8, u8)>,
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+  /// Receiving amount of each recipient, in leaf order
+  amounts: Vec<u32>,
+  /// Leaves left out of `Schedule.redemptions` when creating the schedules
+  missing_redemptions: u8,
+  actions: Vec<Action>,
+}
+
+/// Expected state of a schedule and of the vault account paying it.
+struct ScheduleModel {
+  schedule: Pubkey,
+  vault_token: Pubkey,
+  merkle_tree: MerkleTree,
+  redemptions: Vec<bool>,
+  balance: u64,
+}
+
+struct Harness {
+  test: VaultTest,
+  mint: Pubkey,
+  event_id: u64,
+  nonce: u32,
+}
+
+thread_local! {
+  static HARNESS: RefCell<Option<(Runtime, Harness)>> = const { RefCell::new(None) };
+}
+
+impl Harness {
+  fn new(runtime: &Runtime) -> Self {
+    let (test, mint) = runtime.block_on(async {
+      let mut test = VaultTest::new().await;
+      let mint = test.create_mint(&Pubkey::new_unique(), 6).await;
+      (test, mint)
+    });
+
+    Harness {
+      test,
+      mint,
+      event_id: 0,
+      nonce: 0,
+    }
+  }
+
+  /// Processes `instruction` behind a compute budget instruction unique to the harness, so
+  /// repeated claims are not deduplicated by the bank.
+  async fn process(&mut self, instruction: Instruction, signers: &[&Keypair]) -> Result<(), BanksClientError> {
+    self.nonce += 1;
+    let compute_budget = ComputeBudgetInstruction::set_compute_unit_limit(400_000 + self.nonce % 1_000_000);
+    self.test.process(&[compute_budget, instruction], signers).await
+  }
+
+  async fn create_schedule(&mut self, user_count: u16, use_multi_token: bool, merkle_tree: MerkleTree, balance: u64) -> ScheduleModel {
+    self.event_id += 1;
+    let admin = self.test.admin.insecure_clone();
+    let vault_signer = self.test.vault_signer;
+    let vault_token = self.test.create_token_account(&self.mint, &vault_signer, balance).await;
+    let instruction = vault_instruction::create_schedule(
+      &admin.pubkey(),
+      &self.test.vault,
+      user_count,
+      self.event_id,
+      1,
+      merkle_tree.root(),
+      use_multi_token,
+      &self.mint,
+      &vault_token,
+      &system_program::ID,
+      &system_program::ID,
+      &PROGRAM_ID,
+    );
+    self.process(instruction, &[&admin]).await.unwrap();
+
+    ScheduleModel {
+      schedule: find_schedule_address(self.event_id, &PROGRAM_ID).0,
+      vault_token,
+      merkle_tree,
+      redemptions: vec![false; usize::from(user_count)],
+      balance,
+    }
+  }
+
+  async fn run(&mut self, input: Input) {
+    let recipient_count = input.amounts.len().min(MAX_RECIPIENTS);
+    if recipient_count == 0 {
+      return;
+    }
+    let amounts: Vec<u64> = input.amounts[..recipient_count].iter().map(|amount| u64::from(*amount)).collect();
+    let user_count = recipient_count - usize::from(input.missing_redemptions) % recipient_count;
+
+    // the bank keeps producing blockhashes, stale ones expire during long runs
+    self.test.context.last_blockhash = self.test.context.banks_client.get_latest_blockhash().await.unwrap();
+    let admin = self.test.admin.pubkey();
+    self.test.airdrop(&admin, 10 * LAMPORTS_PER_SOL);
+
+    let users: Vec<Keypair> = (0..recipient_count)
+      .map(|index| keypair_from_seed(&[index as u8 + 1; 32]).unwrap())
+      .collect();
+    let mut user_tokens = Vec::new();
+    for user in users.iter() {
+      user_tokens.push(self.test.create_token_account(&self.mint, &user.pubkey(), 0).await);
+    }
+    let mut user_balances = vec![0u64; recipient_count];
+
+    let leaves = users.iter()
+      .zip(amounts.iter())
+      .enumerate()
+      .map(|(index, (user, amount))| redemption_leaf(index as u16, None, &user.pubkey(), *amount, 0))
+      .collect();
+    let multi_leaves = users.iter()
+      .zip(amounts.iter())
+      .enumerate()
+      .map(|(index, (user, amount))| redemption_multi_leaf(index as u16, None, &user.pubkey(), &self.mint, *amount, 0))
+      .collect();
+    let total: u64 = amounts.iter().sum();
+    let mut models = [
+      self.create_schedule(user_count as u16, false, MerkleTree::new(leaves), total).await,
+      self.create_schedule(user_count as u16, true, MerkleTree::new(multi_leaves), total).await,
+    ];
+
+    for action in input.actions.iter().take(MAX_ACTIONS) {
+      let recipient = usize::from(action.recipient) % recipient_count;
+      let index = action.index.map_or(recipient, usize::from);
+      let receiving_amount = action.receiving_amount.map_or(amounts[recipient], u64::from);
+      let model = &models[usize::from(action.multi)];
+
+      let mut proofs = model.merkle_tree.proof(recipient);
+      let mut is_tampered = false;
+      if let Some((position, mask)) = action.tampered_proof {
+        if !proofs.is_empty() && mask != 0 {
+          let position = usize::from(position) % (32 * proofs.len());
+          proofs[position / 32][position % 32] ^= mask;
+          is_tampered = true;
+        }
+      }
+
+      let user = &users[recipient];
+      let instruction = if action.multi {
+        vault_instruction::redeem_token_multi(
+          &self.test.vault,
+          &model.schedule,
+          index as u16,
+          0,
+          proofs,
+          &self.mint,
+          receiving_amount,
+          0,
+          &model.vault_token,
+          &user.pubkey(),
+          &user_tokens[recipient],
+          &RedeemOptions::default(),
+          &PROGRAM_ID,
+        )
+      } else {
+        vault_instruction::redeem_token(
+          &self.test.vault,
+          &model.schedule,
+          index as u16,
+          0,
+          proofs,
+          receiving_amount,
+          0,
+          &model.vault_token,
+          &user.pubkey(),
+          &user_tokens[recipient],
+          &RedeemOptions::default(),
+          &PROGRAM_ID,
+        )
+      };
+      let result = self.process(instruction, &[user]).await;
+
+      let is_valid_leaf = !is_tampered && index == recipient && receiving_amount == amounts[recipient];
+      let is_redeemable = model.redemptions.get(index) == Some(&false);
+      assert_eq!(result.is_ok(), is_valid_leaf && is_redeemable, "{:?} {:?}", action, result);
+
+      let model = &mut models[usize::from(action.multi)];
+      if result.is_ok() {
+        model.redemptions[index] = true;
+        model.balance -= receiving_amount;
+        user_balances[recipient] += receiving_amount;
+      }
+
+      assert_eq!(self.test.token_balance(&model.vault_token).await, model.balance);
+      assert_eq!(self.test.token_balance(&user_tokens[recipient]).await, user_balances[recipient]);
+      let schedule = self.test.decode(&model.schedule, decode_schedule).await;
+      assert_eq!(schedule.redemptions, model.redemptions);
+    }
+  }
+}
+
+fuzz_target!(|input: Input| {
+  HARNESS.with(|harness| {
+    let mut harness = harness.borrow_mut();
+    let (runtime, harness) = harness.get_or_insert_with(|| {
+      let runtime = Builder::new_current_thread().enable_all().build().unwrap();
+      let harness = Harness::new(&runtime);
+      (runtime, harness)
+    });
+    runtime.block_on(harness.run(input));
+  });
+});
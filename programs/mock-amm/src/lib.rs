@@ -0,0 +1,216 @@
+//! Fixed rate AMM used to test `redeem_token_swap`. Not meant to be deployed.
+
+use anchor_lang::prelude::*;
+use solana_program::{
+  instruction::{
+    Instruction,
+  },
+  program::{
+    invoke,
+    invoke_signed,
+  },
+};
+use std::{
+  convert::{
+    TryFrom,
+  },
+};
+
+declare_id!("774baQWvabQKYS5aQsmaThN1L47apDrKNCo75mxb6w7d");
+
+pub const POOL_SEED: &[u8] = b"pool";
+pub const AUTHORITY_SEED: &[u8] = b"authority";
+pub const TOKEN_PROGRAM_ID: Pubkey = solana_program::pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+#[program]
+pub mod mock_amm {
+  use super::*;
+
+  pub fn initialize_pool(
+    ctx: Context<InitializePoolContext>,
+    rate_numerator: u64,
+    rate_denominator: u64,
+  ) -> Result<()> {
+    require!(rate_denominator > 0, ErrorCode::InvalidRate);
+
+    let pool = &mut ctx.accounts.pool;
+    pool.pool_source = ctx.accounts.pool_source.key();
+    pool.pool_destination = ctx.accounts.pool_destination.key();
+    pool.rate_numerator = rate_numerator;
+    pool.rate_denominator = rate_denominator;
+    pool.authority_nonce = ctx.bumps.pool_authority;
+
+    Ok(())
+  }
+
+  /// Takes `amount_in` from `user_source` and pays `amount_in * rate` from the pool
+  pub fn swap(
+    ctx: Context<SwapContext>,
+    amount_in: u64,
+    minimum_amount_out: u64,
+  ) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    let amount_out = u128::from(amount_in) * u128::from(pool.rate_numerator) / u128::from(pool.rate_denominator);
+    let amount_out = u64::try_from(amount_out).map_err(|_| ErrorCode::InvalidRate)?;
+    require!(amount_out >= minimum_amount_out, ErrorCode::SlippageExceeded);
+
+    transfer_token(
+      &ctx.accounts.user.to_account_info(),
+      &ctx.accounts.user_source,
+      &ctx.accounts.pool_source,
+      amount_in,
+      &[],
+    )?;
+
+    let pool_key = pool.key();
+    let seeds: &[&[_]] = &[
+      AUTHORITY_SEED,
+      pool_key.as_ref(),
+      &[pool.authority_nonce],
+    ];
+    transfer_token(
+      &ctx.accounts.pool_authority,
+      &ctx.accounts.pool_destination,
+      &ctx.accounts.user_destination,
+      amount_out,
+      &[seeds],
+    )?;
+
+    Ok(())
+  }
+}
+
+#[derive(Accounts)]
+pub struct InitializePoolContext<'info> {
+
+  #[account(mut)]
+  pub payer: Signer<'info>,
+
+  #[account(
+    init,
+    seeds = [
+      POOL_SEED,
+      pool_source.key().as_ref(),
+      pool_destination.key().as_ref(),
+    ],
+    bump,
+    payer = payer,
+    space = 8 + Pool::size(),
+  )]
+  pub pool: Account<'info, Pool>,
+
+  /// CHECK: PDA owning the pool token accounts
+  #[account(
+    seeds = [
+      AUTHORITY_SEED,
+      pool.key().as_ref(),
+    ],
+    bump,
+  )]
+  pub pool_authority: AccountInfo<'info>,
+
+  /// CHECK: Pool token account receiving the input token
+  pub pool_source: AccountInfo<'info>,
+
+  /// CHECK: Pool token account paying the output token
+  pub pool_destination: AccountInfo<'info>,
+
+  pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SwapContext<'info> {
+
+  pub pool: Account<'info, Pool>,
+
+  /// CHECK: PDA owning the pool token accounts
+  #[account(
+    seeds = [
+      AUTHORITY_SEED,
+      pool.key().as_ref(),
+    ],
+    bump = pool.authority_nonce,
+  )]
+  pub pool_authority: AccountInfo<'info>,
+
+  pub user: Signer<'info>,
+
+  /// CHECK: Token account of the user, checked by the token program
+  #[account(mut)]
+  pub user_source: AccountInfo<'info>,
+
+  /// CHECK: Token account of the user, checked by the token program
+  #[account(mut)]
+  pub user_destination: AccountInfo<'info>,
+
+  /// CHECK: Pool token account
+  #[account(
+    mut,
+    address = pool.pool_source,
+  )]
+  pub pool_source: AccountInfo<'info>,
+
+  /// CHECK: Pool token account
+  #[account(
+    mut,
+    address = pool.pool_destination,
+  )]
+  pub pool_destination: AccountInfo<'info>,
+
+  /// CHECK: Solana native Token Program
+  #[account(address = TOKEN_PROGRAM_ID)]
+  pub token_program: AccountInfo<'info>,
+}
+
+#[account]
+pub struct Pool {
+  pub pool_source: Pubkey,
+  pub pool_destination: Pubkey,
+  pub rate_numerator: u64,
+  pub rate_denominator: u64,
+  pub authority_nonce: u8,
+}
+
+impl Pool {
+  pub fn size() -> usize {
+    32 + 32 + 8 + 8 + 1
+  }
+}
+
+#[error_code]
+pub enum ErrorCode {
+
+  #[msg("MockAmm: Invalid rate.")]
+  InvalidRate,
+
+  #[msg("MockAmm: Slippage exceeded.")]
+  SlippageExceeded,
+}
+
+fn transfer_token<'a>(
+  owner: &AccountInfo<'a>,
+  from: &AccountInfo<'a>,
+  to: &AccountInfo<'a>,
+  amount: u64,
+  signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+  let mut data = vec![3u8];
+  data.extend_from_slice(&amount.to_le_bytes());
+  let instruction = Instruction {
+    program_id: TOKEN_PROGRAM_ID,
+    accounts: vec![
+      AccountMeta::new(*from.key, false),
+      AccountMeta::new(*to.key, false),
+      AccountMeta::new_readonly(*owner.key, true),
+    ],
+    data,
+  };
+  let accounts = [from.clone(), to.clone(), owner.clone()];
+  if signer_seeds.is_empty() {
+    invoke(&instruction, &accounts)?;
+  } else {
+    invoke_signed(&instruction, &accounts, signer_seeds)?;
+  }
+
+  Ok(())
+}
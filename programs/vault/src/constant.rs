@@ -15,6 +15,30 @@ pub const ROOT_KEYS: &[&str] = &[
   "3BiVpSVqGw9VX9Dp1SdBvKaGwBtWEhpG8eWkfLPZyMhK",
 ];
 
+pub const ALLOWED_MINTS_MAX: usize = 32;
+pub const BPS_DENOMINATOR: u16 = 10_000;
+pub const DESTINATIONS_MAX: usize = 16;
+pub const STAKE_ACCOUNTS_MAX: usize = 16;
+pub const TRANCHES_MAX: usize = 32;
+
+// Current layout versions of Vault and Schedule, see Vault::try_deserialize_versioned
+pub const SCHEDULE_VERSION: u8 = 1;
+pub const VAULT_VERSION: u8 = 1;
+
+// Seconds before a withdrawal destination added by the owner can receive funds
+pub const DESTINATION_TIMELOCK: i64 = 172_800;
+
+pub const ALLOWLIST_SEED_1: &[u8] = &[78, 195, 14, 157, 133, 114, 93, 65];
+pub const CLAIM_SEED_1: &[u8] = &[76, 164, 29, 176, 40, 37, 55, 0];
+pub const DESTINATION_SEED_1: &[u8] = &[41, 61, 64, 74, 80, 15, 95, 154];
+pub const EPOCH_SEED_1: &[u8] = &[255, 247, 162, 215, 214, 34, 52, 15];
+pub const FEE_SEED_1: &[u8] = &[248, 66, 195, 225, 133, 141, 248, 200];
+pub const MINT_SEED_1: &[u8] = &[206, 217, 124, 196, 163, 119, 181, 180];
+pub const RESERVE_SEED_1: &[u8] = &[90, 230, 243, 12, 41, 123, 70, 29];
 pub const SCHEDULE_SEED_1: &[u8] = &[244, 131, 10, 29, 174, 41, 128, 68];
 pub const SIGNER_SEED_1: &[u8] = &[2, 151, 229, 53, 244, 77, 229, 7];
+pub const STAKE_SEED_1: &[u8] = &[137, 57, 174, 168, 65, 155, 206, 141];
+pub const SWAP_SEED_1: &[u8] = &[110, 194, 130, 212, 10, 138, 113, 254];
+pub const TRANCHE_SEED_1: &[u8] = &[48, 183, 109, 8, 101, 141, 187, 111];
 pub const VAULT_SEED_1: &[u8] = &[93, 85, 196, 21, 227, 86, 221, 123];
+pub const VOUCHER_SEED_1: &[u8] = &[72, 102, 36, 70, 168, 182, 246, 133];
@@ -1,20 +1,58 @@
 use anchor_lang::prelude::*;
+use solana_program::{
+  system_program::ID as SYSTEM_PROGRAM_ID,
+  sysvar::{
+    instructions as sysvar_instructions,
+  },
+};
 
 use crate::constant::{
+  ALLOWLIST_SEED_1,
+  CLAIM_SEED_1,
+  DESTINATION_SEED_1,
+  EPOCH_SEED_1,
+  FEE_SEED_1,
+  MINT_SEED_1,
+  RESERVE_SEED_1,
   SCHEDULE_SEED_1,
   SIGNER_SEED_1,
+  STAKE_SEED_1,
+  SWAP_SEED_1,
+  TRANCHE_SEED_1,
   VAULT_SEED_1,
+  VOUCHER_SEED_1,
 };
 use crate::error::{
   ErrorCode,
 };
 use crate::state::{
-  ObjType,
+  BitmapSchedule,
+  EpochClaim,
   Schedule,
+  ScheduleEpoch,
+  ScheduleMint,
+  ScheduleStake,
+  ScheduleTranche,
+  ScheduleVoucher,
   Vault,
+  VaultAllowlist,
+  VaultDestinations,
+  VaultFee,
+  VaultReserve,
+  VaultSwap,
 };
 use crate::shared;
+use crate::external::mpl_bubblegum::{
+  is_bubblegum_program,
+  spl_account_compression,
+  spl_noop,
+};
+use crate::external::mpl_token_metadata::{
+  is_token_metadata_program,
+};
 use crate::external::spl_token::{
+  is_mint,
+  is_token_account,
   is_token_program,
 };
 
@@ -53,7 +91,17 @@ pub struct SetVaultContext<'info> {
 }
 
 #[derive(Accounts)]
-#[instruction(user_count: u16, event_id: u64)]
+#[instruction(
+  user_count: u16,
+  event_id: u64,
+  timestamp: i64,
+  merkle_root: [u8; 32],
+  use_multi_token: bool,
+  receiving_token_mint: Pubkey,
+  receiving_token_account: Pubkey,
+  sending_token_mint: Pubkey,
+  sending_token_account: Pubkey,
+)]
 pub struct CreateScheduleContext<'info> {
 
   /// CHECK: vault admin, verified using #access_control
@@ -75,10 +123,62 @@ pub struct CreateScheduleContext<'info> {
   pub schedule: Account<'info, Schedule>,
 
   pub system_program: Program<'info, System>,
+
+  /// CHECK: PDA holding the mint allowlist of the vault, may be uninitialized
+  #[account(
+    seeds = [
+      &ALLOWLIST_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump,
+  )]
+  pub vault_allowlist: AccountInfo<'info>,
+
+  /// CHECK: PDA to hold vault's assets
+  #[account(
+    seeds = [
+      &SIGNER_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump = vault.signer_nonce
+  )]
+  pub vault_signer: AccountInfo<'info>,
+
+  /// CHECK: Vault's TokenAccount of receiving_token_mint, the mint itself for mint-on-claim
+  /// schedules. Any account for SOL schedules
+  #[account(
+    address = receiving_token_account @ErrorCode::InvalidReceivingTokenAccount,
+    constraint = receiving_token_mint == SYSTEM_PROGRAM_ID
+      || is_token_account(&vault_token0, &receiving_token_mint, vault_signer.key)
+      || (receiving_token_account == receiving_token_mint && is_mint(&vault_token0, vault_signer.key))
+      @ErrorCode::InvalidReceivingTokenAccount,
+  )]
+  pub vault_token0: AccountInfo<'info>,
+
+  /// CHECK: Vault's TokenAccount of sending_token_mint, any account when the schedule charges
+  /// no sending token
+  #[account(
+    address = sending_token_account @ErrorCode::InvalidSendingTokenAccount,
+    constraint = sending_token_mint == SYSTEM_PROGRAM_ID
+      || is_token_account(&vault_token1, &sending_token_mint, vault_signer.key)
+      @ErrorCode::InvalidSendingTokenAccount,
+  )]
+  pub vault_token1: AccountInfo<'info>,
+
+  /// CHECK: PDA holding the withdrawal destinations of the vault, may be uninitialized
+  #[account(
+    seeds = [
+      &DESTINATION_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump,
+  )]
+  pub vault_destinations: AccountInfo<'info>,
 }
 
 #[derive(Accounts)]
-pub struct SetScheduleContext<'info> {
+#[instruction(user_count: u16, event_id: u64)]
+pub struct CreateNftScheduleContext<'info> {
 
   /// CHECK: vault admin, verified using #access_control
   #[account(signer, mut)]
@@ -87,19 +187,40 @@ pub struct SetScheduleContext<'info> {
   pub vault: Account<'info, Vault>,
 
   #[account(
-    mut,
+    init,
     seeds = [
       &SCHEDULE_SEED_1,
-      &shared::derive_event_id(schedule.event_id).as_ref(),
+      &shared::derive_event_id(event_id).as_ref(),
     ],
-    bump = schedule.nonce,
-    constraint = schedule.vault_id == vault.key() @ErrorCode::InvalidAccount
+    bump,
+    payer = admin,
+    space = 16 + Schedule::size(user_count),
   )]
   pub schedule: Account<'info, Schedule>,
+
+  pub system_program: Program<'info, System>,
+
+  /// CHECK: PDA holding the withdrawal destinations of the vault, may be uninitialized
+  #[account(
+    seeds = [
+      &DESTINATION_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump,
+  )]
+  pub vault_destinations: AccountInfo<'info>,
 }
 
 #[derive(Accounts)]
-pub struct WithdrawSolContext<'info> {
+#[instruction(
+  event_id: u64,
+  nonce_count: u32,
+  timestamp: i64,
+  voucher_signer: Pubkey,
+  receiving_token_mint: Pubkey,
+  receiving_token_account: Pubkey,
+)]
+pub struct CreateVoucherScheduleContext<'info> {
 
   /// CHECK: vault admin, verified using #access_control
   #[account(signer, mut)]
@@ -107,9 +228,44 @@ pub struct WithdrawSolContext<'info> {
 
   pub vault: Account<'info, Vault>,
 
+  #[account(
+    init,
+    seeds = [
+      &SCHEDULE_SEED_1,
+      &shared::derive_event_id(event_id).as_ref(),
+    ],
+    bump,
+    payer = admin,
+    space = 16 + Schedule::size(0),
+  )]
+  pub schedule: Account<'info, Schedule>,
+
+  #[account(
+    init,
+    seeds = [
+      &VOUCHER_SEED_1,
+      schedule.to_account_info().key.as_ref(),
+    ],
+    bump,
+    payer = admin,
+    space = 16 + ScheduleVoucher::size(nonce_count),
+  )]
+  pub schedule_voucher: Account<'info, ScheduleVoucher>,
+
+  pub system_program: Program<'info, System>,
+
+  /// CHECK: PDA holding the mint allowlist of the vault, may be uninitialized
+  #[account(
+    seeds = [
+      &ALLOWLIST_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump,
+  )]
+  pub vault_allowlist: AccountInfo<'info>,
+
   /// CHECK: PDA to hold vault's assets
   #[account(
-    mut,
     seeds = [
       &SIGNER_SEED_1,
       vault.to_account_info().key.as_ref(),
@@ -118,15 +274,33 @@ pub struct WithdrawSolContext<'info> {
   )]
   pub vault_signer: AccountInfo<'info>,
 
-  /// CHECK: Destination SOL account
-  #[account(mut)]
-  pub recipient: AccountInfo<'info>,
+  /// CHECK: Vault's TokenAccount of receiving_token_mint, any account for SOL schedules
+  #[account(
+    address = receiving_token_account @ErrorCode::InvalidReceivingTokenAccount,
+    constraint = receiving_token_mint == SYSTEM_PROGRAM_ID
+      || is_token_account(&vault_token0, &receiving_token_mint, vault_signer.key)
+      @ErrorCode::InvalidReceivingTokenAccount,
+  )]
+  pub vault_token0: AccountInfo<'info>,
 
-  pub system_program: Program<'info, System>,
+  /// CHECK: PDA holding the withdrawal destinations of the vault, may be uninitialized
+  #[account(
+    seeds = [
+      &DESTINATION_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump,
+  )]
+  pub vault_destinations: AccountInfo<'info>,
 }
 
 #[derive(Accounts)]
-pub struct WithdrawTokenContext<'info> {
+#[instruction(
+  event_id: u64,
+  receiving_token_mint: Pubkey,
+  receiving_token_account: Pubkey,
+)]
+pub struct CreateEpochScheduleContext<'info> {
 
   /// CHECK: vault admin, verified using #access_control
   #[account(signer, mut)]
@@ -134,6 +308,42 @@ pub struct WithdrawTokenContext<'info> {
 
   pub vault: Account<'info, Vault>,
 
+  #[account(
+    init,
+    seeds = [
+      &SCHEDULE_SEED_1,
+      &shared::derive_event_id(event_id).as_ref(),
+    ],
+    bump,
+    payer = admin,
+    space = 16 + Schedule::size(0),
+  )]
+  pub schedule: Account<'info, Schedule>,
+
+  #[account(
+    init,
+    seeds = [
+      &EPOCH_SEED_1,
+      schedule.to_account_info().key.as_ref(),
+    ],
+    bump,
+    payer = admin,
+    space = 16 + ScheduleEpoch::size(),
+  )]
+  pub schedule_epoch: Account<'info, ScheduleEpoch>,
+
+  pub system_program: Program<'info, System>,
+
+  /// CHECK: PDA holding the mint allowlist of the vault, may be uninitialized
+  #[account(
+    seeds = [
+      &ALLOWLIST_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump,
+  )]
+  pub vault_allowlist: AccountInfo<'info>,
+
   /// CHECK: PDA to hold vault's assets
   #[account(
     seeds = [
@@ -144,23 +354,32 @@ pub struct WithdrawTokenContext<'info> {
   )]
   pub vault_signer: AccountInfo<'info>,
 
-  /// CHECK: Vault's TokenAccount for distribution
-  #[account(mut)]
-  pub sender: AccountInfo<'info>,
-
-  /// CHECK: Destination token account
-  #[account(mut)]
-  pub recipient: AccountInfo<'info>,
+  /// CHECK: Vault's TokenAccount of receiving_token_mint, any account for SOL schedules
+  #[account(
+    address = receiving_token_account @ErrorCode::InvalidReceivingTokenAccount,
+    constraint = receiving_token_mint == SYSTEM_PROGRAM_ID
+      || is_token_account(&vault_token0, &receiving_token_mint, vault_signer.key)
+      @ErrorCode::InvalidReceivingTokenAccount,
+  )]
+  pub vault_token0: AccountInfo<'info>,
 
-  /// CHECK: Solana native Token Program
+  /// CHECK: PDA holding the withdrawal destinations of the vault, may be uninitialized
   #[account(
-    constraint = is_token_program(&token_program) @ErrorCode::InvalidAccount,
+    seeds = [
+      &DESTINATION_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump,
   )]
-  pub token_program: AccountInfo<'info>,
+  pub vault_destinations: AccountInfo<'info>,
 }
 
 #[derive(Accounts)]
-pub struct RedeemTokenContext<'info> {
+pub struct SetScheduleEpochContext<'info> {
+
+  /// CHECK: vault admin, verified using #access_control
+  #[account(signer, mut)]
+  pub admin: AccountInfo<'info>,
 
   pub vault: Account<'info, Vault>,
 
@@ -171,59 +390,94 @@ pub struct RedeemTokenContext<'info> {
       &shared::derive_event_id(schedule.event_id).as_ref(),
     ],
     bump = schedule.nonce,
-    constraint = schedule.vault_id == vault.key() @ErrorCode::InvalidAccount,
+    constraint = schedule.vault_id == vault.key() @ErrorCode::InvalidAccount
   )]
   pub schedule: Account<'info, Schedule>,
 
-  /// CHECK: PDA to hold vault's assets
   #[account(
+    mut,
     seeds = [
-      &SIGNER_SEED_1,
-      vault.to_account_info().key.as_ref(),
+      &EPOCH_SEED_1,
+      schedule.to_account_info().key.as_ref(),
     ],
-    bump = vault.signer_nonce
+    bump = schedule_epoch.nonce,
   )]
-  pub vault_signer: AccountInfo<'info>,
+  pub schedule_epoch: Account<'info, ScheduleEpoch>,
 
-  /// CHECK: Program's TokenAccount for distribution
+  /// CHECK: PDA holding the withdrawal destinations of the vault, may be uninitialized
   #[account(
-    mut,
-    constraint = *vault_token0.key == schedule.receiving_token_account @ErrorCode::InvalidAccount
+    seeds = [
+      &DESTINATION_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump,
   )]
-  pub vault_token0: AccountInfo<'info>,
+  pub vault_destinations: AccountInfo<'info>,
+}
 
-  /// CHECK: User account eligible to redeem token. Must sign to provide proof of redemption
-  #[account(signer)]
-  pub user: AccountInfo<'info>,
+#[derive(Accounts)]
+pub struct SetScheduleContext<'info> {
 
-  /// CHECK: User account to receive token
-  #[account(mut)]
-  pub user_token0: AccountInfo<'info>,
+  /// CHECK: vault admin, verified using #access_control
+  #[account(signer, mut)]
+  pub admin: AccountInfo<'info>,
+
+  pub vault: Account<'info, Vault>,
 
-  /// CHECK: Solana native Token Program
   #[account(
-    constraint = is_token_program(&token_program) @ErrorCode::InvalidAccount
+    mut,
+    seeds = [
+      &SCHEDULE_SEED_1,
+      &shared::derive_event_id(schedule.event_id).as_ref(),
+    ],
+    bump = schedule.nonce,
+    constraint = schedule.vault_id == vault.key() @ErrorCode::InvalidAccount
   )]
-  pub token_program: AccountInfo<'info>,
-
-  pub system_program: Program<'info, System>,
+  pub schedule: Account<'info, Schedule>,
 }
 
 #[derive(Accounts)]
-pub struct RedeemTokenMultiContext<'info> {
+#[instruction(
+  user_count: u16,
+  event_id: u64,
+  timestamp: i64,
+  merkle_root: [u8; 32],
+  receiving_token_mint: Pubkey,
+  receiving_token_account: Pubkey,
+  sending_token_mint: Pubkey,
+  sending_token_account: Pubkey,
+)]
+pub struct CreateBitmapScheduleContext<'info> {
+
+  /// CHECK: vault admin, verified using #access_control
+  #[account(signer, mut)]
+  pub admin: AccountInfo<'info>,
 
   pub vault: Account<'info, Vault>,
 
   #[account(
-    mut,
+    init,
     seeds = [
       &SCHEDULE_SEED_1,
-      &shared::derive_event_id(schedule.event_id).as_ref(),
+      &shared::derive_event_id(event_id).as_ref(),
     ],
-    bump = schedule.nonce,
-    constraint = schedule.vault_id == vault.key() @ErrorCode::InvalidAccount,
+    bump,
+    payer = admin,
+    space = 16 + BitmapSchedule::size(user_count),
   )]
-  pub schedule: Account<'info, Schedule>,
+  pub schedule: AccountLoader<'info, BitmapSchedule>,
+
+  pub system_program: Program<'info, System>,
+
+  /// CHECK: PDA holding the mint allowlist of the vault, may be uninitialized
+  #[account(
+    seeds = [
+      &ALLOWLIST_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump,
+  )]
+  pub vault_allowlist: AccountInfo<'info>,
 
   /// CHECK: PDA to hold vault's assets
   #[account(
@@ -235,45 +489,1444 @@ pub struct RedeemTokenMultiContext<'info> {
   )]
   pub vault_signer: AccountInfo<'info>,
 
-  /// CHECK: Program's TokenAccount for distribution
-  #[account(mut)]
+  /// CHECK: Vault's TokenAccount of receiving_token_mint, any account for SOL schedules. Never
+  /// the mint itself, bitmap schedules do not mint on claim
+  #[account(
+    constraint = receiving_token_mint == SYSTEM_PROGRAM_ID
+      || receiving_token_account != receiving_token_mint
+      @ErrorCode::InvalidInput,
+    address = receiving_token_account @ErrorCode::InvalidReceivingTokenAccount,
+    constraint = receiving_token_mint == SYSTEM_PROGRAM_ID
+      || is_token_account(&vault_token0, &receiving_token_mint, vault_signer.key)
+      @ErrorCode::InvalidReceivingTokenAccount,
+  )]
   pub vault_token0: AccountInfo<'info>,
 
-  /// CHECK: User account eligible to redeem token. Must sign to provide proof of redemption
-  #[account(signer)]
-  pub user: AccountInfo<'info>,
-
-  /// CHECK: User account to receive token
-  #[account(mut)]
-  pub user_token0: AccountInfo<'info>,
-
-  /// CHECK: Solana native Token Program
+  /// CHECK: Vault's TokenAccount of sending_token_mint, any account when the schedule charges
+  /// no sending token
   #[account(
-    constraint = is_token_program(&token_program) @ErrorCode::InvalidAccount
+    address = sending_token_account @ErrorCode::InvalidSendingTokenAccount,
+    constraint = sending_token_mint == SYSTEM_PROGRAM_ID
+      || is_token_account(&vault_token1, &sending_token_mint, vault_signer.key)
+      @ErrorCode::InvalidSendingTokenAccount,
   )]
-  pub token_program: AccountInfo<'info>,
+  pub vault_token1: AccountInfo<'info>,
 
-  pub system_program: Program<'info, System>,
+  /// CHECK: PDA holding the withdrawal destinations of the vault, may be uninitialized
+  #[account(
+    seeds = [
+      &DESTINATION_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump,
+  )]
+  pub vault_destinations: AccountInfo<'info>,
 }
 
 #[derive(Accounts)]
-pub struct TransferOwnershipContext<'info> {
+pub struct SetBitmapScheduleContext<'info> {
 
-  /// CHECK: vault owner, verified using #access_control
-    #[account(signer)]
-    pub owner: AccountInfo<'info>,
+  /// CHECK: vault admin, verified using #access_control
+  #[account(signer, mut)]
+  pub admin: AccountInfo<'info>,
 
-    #[account(mut)]
-    pub vault: Account<'info, Vault>,
+  pub vault: Account<'info, Vault>,
+
+  #[account(
+    mut,
+    seeds = [
+      &SCHEDULE_SEED_1,
+      &shared::derive_event_id(schedule.load()?.event_id).as_ref(),
+    ],
+    bump = schedule.load()?.nonce,
+    constraint = schedule.load()?.vault_id == vault.key() @ErrorCode::InvalidAccount
+  )]
+  pub schedule: AccountLoader<'info, BitmapSchedule>,
 }
 
 #[derive(Accounts)]
-pub struct AcceptOwnershipContext<'info> {
+#[instruction(user_count: u16)]
+pub struct AppendScheduleTrancheContext<'info> {
 
-  /// CHECK: new vault owner, verified using #access_control
-    #[account(signer)]
-    pub new_owner: AccountInfo<'info>,
+  /// CHECK: vault admin, verified using #access_control
+  #[account(signer, mut)]
+  pub admin: AccountInfo<'info>,
+
+  pub vault: Account<'info, Vault>,
+
+  #[account(
+    mut,
+    seeds = [
+      &SCHEDULE_SEED_1,
+      &shared::derive_event_id(schedule.event_id).as_ref(),
+    ],
+    bump = schedule.nonce,
+    constraint = schedule.vault_id == vault.key() @ErrorCode::InvalidAccount,
+    realloc = 16 + Schedule::size(0) + schedule.redemptions.len() + usize::from(user_count),
+    realloc::payer = admin,
+    realloc::zero = false,
+  )]
+  pub schedule: Account<'info, Schedule>,
+
+  #[account(
+    init_if_needed,
+    seeds = [
+      &TRANCHE_SEED_1,
+      schedule.to_account_info().key.as_ref(),
+    ],
+    bump,
+    payer = admin,
+    space = 16 + ScheduleTranche::size(),
+  )]
+  pub schedule_tranche: Account<'info, ScheduleTranche>,
+
+  pub system_program: Program<'info, System>,
+
+  /// CHECK: PDA holding the withdrawal destinations of the vault, may be uninitialized
+  #[account(
+    seeds = [
+      &DESTINATION_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump,
+  )]
+  pub vault_destinations: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetScheduleMintContext<'info> {
+
+  /// CHECK: vault admin, verified using #access_control
+  #[account(signer, mut)]
+  pub admin: AccountInfo<'info>,
+
+  pub vault: Account<'info, Vault>,
+
+  #[account(
+    seeds = [
+      &SCHEDULE_SEED_1,
+      &shared::derive_event_id(schedule.event_id).as_ref(),
+    ],
+    bump = schedule.nonce,
+    constraint = schedule.vault_id == vault.key() @ErrorCode::InvalidAccount
+  )]
+  pub schedule: Account<'info, Schedule>,
+
+  #[account(
+    init_if_needed,
+    seeds = [
+      &MINT_SEED_1,
+      schedule.to_account_info().key.as_ref(),
+    ],
+    bump,
+    payer = admin,
+    space = 16 + ScheduleMint::size(),
+  )]
+  pub schedule_mint: Account<'info, ScheduleMint>,
+
+  pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetScheduleStakeContext<'info> {
+
+  /// CHECK: vault admin, verified using #access_control
+  #[account(signer, mut)]
+  pub admin: AccountInfo<'info>,
+
+  pub vault: Account<'info, Vault>,
+
+  #[account(
+    seeds = [
+      &SCHEDULE_SEED_1,
+      &shared::derive_event_id(schedule.event_id).as_ref(),
+    ],
+    bump = schedule.nonce,
+    constraint = schedule.vault_id == vault.key() @ErrorCode::InvalidAccount
+  )]
+  pub schedule: Account<'info, Schedule>,
+
+  #[account(
+    init_if_needed,
+    seeds = [
+      &STAKE_SEED_1,
+      schedule.to_account_info().key.as_ref(),
+    ],
+    bump,
+    payer = admin,
+    space = 16 + ScheduleStake::size(),
+  )]
+  pub schedule_stake: Account<'info, ScheduleStake>,
+
+  pub system_program: Program<'info, System>,
+
+  /// CHECK: PDA holding the withdrawal destinations of the vault, may be uninitialized
+  #[account(
+    seeds = [
+      &DESTINATION_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump,
+  )]
+  pub vault_destinations: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetSolReserveContext<'info> {
+
+  /// CHECK: vault owner, verified using #access_control
+  #[account(signer, mut)]
+  pub owner: AccountInfo<'info>,
+
+  pub vault: Account<'info, Vault>,
+
+  #[account(
+    init_if_needed,
+    seeds = [
+      &RESERVE_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump,
+    payer = owner,
+    space = 16 + VaultReserve::size(),
+  )]
+  pub vault_reserve: Account<'info, VaultReserve>,
+
+  pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetSolLiabilitiesContext<'info> {
+
+  /// CHECK: vault admin, verified using #access_control
+  #[account(signer, mut)]
+  pub admin: AccountInfo<'info>,
+
+  pub vault: Account<'info, Vault>,
+
+  #[account(
+    init_if_needed,
+    seeds = [
+      &RESERVE_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump,
+    payer = admin,
+    space = 16 + VaultReserve::size(),
+  )]
+  pub vault_reserve: Account<'info, VaultReserve>,
+
+  pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetClaimFeeContext<'info> {
+
+  /// CHECK: vault owner, verified using #access_control
+  #[account(signer, mut)]
+  pub owner: AccountInfo<'info>,
+
+  pub vault: Account<'info, Vault>,
+
+  #[account(
+    init_if_needed,
+    seeds = [
+      &FEE_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump,
+    payer = owner,
+    space = 16 + VaultFee::size(),
+  )]
+  pub vault_fee: Account<'info, VaultFee>,
+
+  pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetSwapProgramContext<'info> {
+
+  /// CHECK: vault owner, verified using #access_control
+  #[account(signer, mut)]
+  pub owner: AccountInfo<'info>,
+
+  pub vault: Account<'info, Vault>,
+
+  #[account(
+    init_if_needed,
+    seeds = [
+      &SWAP_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump,
+    payer = owner,
+    space = 16 + VaultSwap::size(),
+  )]
+  pub vault_swap: Account<'info, VaultSwap>,
+
+  pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetAllowedMintsContext<'info> {
+
+  /// CHECK: vault owner, verified using #access_control
+  #[account(signer, mut)]
+  pub owner: AccountInfo<'info>,
+
+  pub vault: Account<'info, Vault>,
+
+  #[account(
+    init_if_needed,
+    seeds = [
+      &ALLOWLIST_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump,
+    payer = owner,
+    space = 16 + VaultAllowlist::size(),
+  )]
+  pub vault_allowlist: Account<'info, VaultAllowlist>,
+
+  pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AddWithdrawalDestinationContext<'info> {
+
+  /// CHECK: vault owner, verified using #access_control
+  #[account(signer, mut)]
+  pub owner: AccountInfo<'info>,
+
+  pub vault: Account<'info, Vault>,
+
+  #[account(
+    init_if_needed,
+    seeds = [
+      &DESTINATION_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump,
+    payer = owner,
+    space = 16 + VaultDestinations::size(),
+  )]
+  pub vault_destinations: Account<'info, VaultDestinations>,
+
+  pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveWithdrawalDestinationContext<'info> {
+
+  /// CHECK: vault owner, verified using #access_control
+  #[account(signer)]
+  pub owner: AccountInfo<'info>,
+
+  pub vault: Account<'info, Vault>,
+
+  #[account(
+    mut,
+    seeds = [
+      &DESTINATION_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump = vault_destinations.nonce,
+  )]
+  pub vault_destinations: Account<'info, VaultDestinations>,
+}
+
+#[derive(Accounts)]
+pub struct GetWithdrawableSolContext<'info> {
+
+  pub vault: Account<'info, Vault>,
+
+  /// CHECK: PDA to hold vault's assets
+  #[account(
+    seeds = [
+      &SIGNER_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump = vault.signer_nonce
+  )]
+  pub vault_signer: AccountInfo<'info>,
+
+  /// CHECK: PDA holding the SOL reserve of the vault, may be uninitialized
+  #[account(
+    seeds = [
+      &RESERVE_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump,
+  )]
+  pub vault_reserve: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DepositSolContext<'info> {
+
+  /// CHECK: Account funding the vault
+  #[account(signer, mut)]
+  pub payer: AccountInfo<'info>,
+
+  pub vault: Account<'info, Vault>,
+
+  /// CHECK: PDA to hold vault's assets
+  #[account(
+    seeds = [
+      &SIGNER_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump = vault.signer_nonce
+  )]
+  pub vault_signer: AccountInfo<'info>,
+
+  /// CHECK: Vault's wrapped SOL TokenAccount, verified in instruction
+  #[account(mut)]
+  pub vault_token: AccountInfo<'info>,
+
+  /// CHECK: Solana native Token Program
+  #[account(
+    constraint = is_token_program(&token_program) @ErrorCode::InvalidAccount,
+  )]
+  pub token_program: AccountInfo<'info>,
+
+  pub system_program: Program<'info, System>,
+
+  /// CHECK: PDA holding the mint allowlist of the vault, may be uninitialized
+  #[account(
+    seeds = [
+      &ALLOWLIST_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump,
+  )]
+  pub vault_allowlist: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawSolContext<'info> {
+
+  /// CHECK: vault admin, verified using #access_control
+  #[account(signer, mut)]
+  pub admin: AccountInfo<'info>,
+
+  pub vault: Account<'info, Vault>,
+
+  /// CHECK: PDA to hold vault's assets
+  #[account(
+    mut,
+    seeds = [
+      &SIGNER_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump = vault.signer_nonce
+  )]
+  pub vault_signer: AccountInfo<'info>,
+
+  /// CHECK: Destination SOL account
+  #[account(mut)]
+  pub recipient: AccountInfo<'info>,
+
+  pub system_program: Program<'info, System>,
+
+  /// CHECK: PDA holding the SOL reserve of the vault, may be uninitialized
+  #[account(
+    seeds = [
+      &RESERVE_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump,
+  )]
+  pub vault_reserve: AccountInfo<'info>,
+
+  /// CHECK: PDA holding the withdrawal destinations of the vault, may be uninitialized
+  #[account(
+    seeds = [
+      &DESTINATION_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump,
+  )]
+  pub vault_destinations: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTokenContext<'info> {
+
+  /// CHECK: vault admin, verified using #access_control
+  #[account(signer, mut)]
+  pub admin: AccountInfo<'info>,
+
+  pub vault: Account<'info, Vault>,
+
+  /// CHECK: PDA to hold vault's assets
+  #[account(
+    seeds = [
+      &SIGNER_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump = vault.signer_nonce
+  )]
+  pub vault_signer: AccountInfo<'info>,
+
+  /// CHECK: Vault's TokenAccount for distribution
+  #[account(mut)]
+  pub sender: AccountInfo<'info>,
+
+  /// CHECK: Destination token account
+  #[account(mut)]
+  pub recipient: AccountInfo<'info>,
+
+  /// CHECK: Solana native Token Program
+  #[account(
+    constraint = is_token_program(&token_program) @ErrorCode::InvalidAccount,
+  )]
+  pub token_program: AccountInfo<'info>,
+
+  /// CHECK: PDA holding the mint allowlist of the vault, may be uninitialized
+  #[account(
+    seeds = [
+      &ALLOWLIST_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump,
+  )]
+  pub vault_allowlist: AccountInfo<'info>,
+
+  /// CHECK: PDA holding the withdrawal destinations of the vault, may be uninitialized
+  #[account(
+    seeds = [
+      &DESTINATION_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump,
+  )]
+  pub vault_destinations: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemTokenContext<'info> {
+
+  pub vault: Account<'info, Vault>,
+
+  #[account(
+    mut,
+    seeds = [
+      &SCHEDULE_SEED_1,
+      &shared::derive_event_id(schedule.event_id).as_ref(),
+    ],
+    bump = schedule.nonce,
+    constraint = schedule.vault_id == vault.key() @ErrorCode::InvalidAccount,
+  )]
+  pub schedule: Account<'info, Schedule>,
+
+  /// CHECK: PDA to hold vault's assets
+  #[account(
+    seeds = [
+      &SIGNER_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump = vault.signer_nonce
+  )]
+  pub vault_signer: AccountInfo<'info>,
+
+  /// CHECK: Program's TokenAccount for distribution, or the mint of a mint-on-claim schedule
+  #[account(
+    mut,
+    constraint = *vault_token0.key == schedule.receiving_token_account @ErrorCode::InvalidAccount
+  )]
+  pub vault_token0: AccountInfo<'info>,
+
+  /// CHECK: User account eligible to redeem token. Must sign to provide proof of redemption
+  #[account(signer)]
+  pub user: AccountInfo<'info>,
+
+  /// CHECK: User account to receive token, mint and owner verified in instruction
+  #[account(mut)]
+  pub user_token0: AccountInfo<'info>,
+
+  /// CHECK: Solana native Token Program
+  #[account(
+    constraint = is_token_program(&token_program) @ErrorCode::InvalidAccount
+  )]
+  pub token_program: AccountInfo<'info>,
+
+  pub system_program: Program<'info, System>,
+
+  /// CHECK: Program's TokenAccount to receive sending_amount, verified in instruction
+  #[account(mut)]
+  pub vault_token1: Option<AccountInfo<'info>>,
+
+  /// CHECK: User account to pay sending_amount, verified in instruction
+  #[account(mut)]
+  pub user_token1: Option<AccountInfo<'info>>,
+
+  /// CHECK: Solana Associated Token Program, required to create user_token0
+  pub associated_token_program: Option<AccountInfo<'info>>,
+
+  /// CHECK: Mint of user_token0, verified in instruction
+  pub user_token0_mint: Option<AccountInfo<'info>>,
+
+  /// CHECK: Account paying for the creation of user_token0, can be the user or a relayer
+  #[account(signer, mut)]
+  pub payer: Option<AccountInfo<'info>>,
+
+  /// CHECK: PDA holding the SOL reserve of the vault, may be uninitialized. Verified in instruction
+  #[account(mut)]
+  pub vault_reserve: AccountInfo<'info>,
+
+  /// CHECK: PDA holding the claim fee of the vault, may be uninitialized. Verified in instruction
+  pub vault_fee: AccountInfo<'info>,
+
+  /// CHECK: Treasury receiving the SOL claim fees, verified in instruction
+  #[account(mut)]
+  pub treasury: Option<AccountInfo<'info>>,
+
+  /// CHECK: Treasury token account receiving the token claim fees, verified in instruction
+  #[account(mut)]
+  pub treasury_token: Option<AccountInfo<'info>>,
+
+  /// Mint cap of a mint-on-claim schedule, required to redeem from it
+  #[account(
+    mut,
+    seeds = [
+      &MINT_SEED_1,
+      schedule.to_account_info().key.as_ref(),
+    ],
+    bump = schedule_mint.nonce,
+  )]
+  pub schedule_mint: Option<Account<'info, ScheduleMint>>,
+
+  /// Tranches appended to the schedule, required to redeem from them
+  #[account(
+    seeds = [
+      &TRANCHE_SEED_1,
+      schedule.to_account_info().key.as_ref(),
+    ],
+    bump = schedule_tranche.nonce,
+  )]
+  pub schedule_tranche: Option<Account<'info, ScheduleTranche>>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemTokenBitmapContext<'info> {
+
+  pub vault: Account<'info, Vault>,
+
+  #[account(
+    mut,
+    seeds = [
+      &SCHEDULE_SEED_1,
+      &shared::derive_event_id(schedule.load()?.event_id).as_ref(),
+    ],
+    bump = schedule.load()?.nonce,
+    constraint = schedule.load()?.vault_id == vault.key() @ErrorCode::InvalidAccount,
+  )]
+  pub schedule: AccountLoader<'info, BitmapSchedule>,
+
+  /// CHECK: PDA to hold vault's assets
+  #[account(
+    seeds = [
+      &SIGNER_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump = vault.signer_nonce
+  )]
+  pub vault_signer: AccountInfo<'info>,
+
+  /// CHECK: Program's TokenAccount for distribution
+  #[account(
+    mut,
+    constraint = *vault_token0.key == schedule.load()?.receiving_token_account @ErrorCode::InvalidAccount
+  )]
+  pub vault_token0: AccountInfo<'info>,
+
+  /// CHECK: User account eligible to redeem token. Must sign to provide proof of redemption
+  #[account(signer)]
+  pub user: AccountInfo<'info>,
+
+  /// CHECK: User account to receive token, mint and owner verified in instruction
+  #[account(mut)]
+  pub user_token0: AccountInfo<'info>,
+
+  /// CHECK: Solana native Token Program
+  #[account(
+    constraint = is_token_program(&token_program) @ErrorCode::InvalidAccount
+  )]
+  pub token_program: AccountInfo<'info>,
+
+  pub system_program: Program<'info, System>,
+
+  /// CHECK: Program's TokenAccount to receive sending_amount, verified in instruction
+  #[account(mut)]
+  pub vault_token1: Option<AccountInfo<'info>>,
+
+  /// CHECK: User account to pay sending_amount, verified in instruction
+  #[account(mut)]
+  pub user_token1: Option<AccountInfo<'info>>,
+
+  /// CHECK: Solana Associated Token Program, required to create user_token0
+  pub associated_token_program: Option<AccountInfo<'info>>,
+
+  /// CHECK: Mint of user_token0, verified in instruction
+  pub user_token0_mint: Option<AccountInfo<'info>>,
+
+  /// CHECK: Account paying for the creation of user_token0, can be the user or a relayer
+  #[account(signer, mut)]
+  pub payer: Option<AccountInfo<'info>>,
+
+  /// CHECK: PDA holding the SOL reserve of the vault, may be uninitialized. Verified in instruction
+  #[account(mut)]
+  pub vault_reserve: AccountInfo<'info>,
+
+  /// CHECK: PDA holding the claim fee of the vault, may be uninitialized. Verified in instruction
+  pub vault_fee: AccountInfo<'info>,
+
+  /// CHECK: Treasury receiving the SOL claim fees, verified in instruction
+  #[account(mut)]
+  pub treasury: Option<AccountInfo<'info>>,
+
+  /// CHECK: Treasury token account receiving the token claim fees, verified in instruction
+  #[account(mut)]
+  pub treasury_token: Option<AccountInfo<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemTokenMultiContext<'info> {
+
+  pub vault: Account<'info, Vault>,
+
+  #[account(
+    mut,
+    seeds = [
+      &SCHEDULE_SEED_1,
+      &shared::derive_event_id(schedule.event_id).as_ref(),
+    ],
+    bump = schedule.nonce,
+    constraint = schedule.vault_id == vault.key() @ErrorCode::InvalidAccount,
+  )]
+  pub schedule: Account<'info, Schedule>,
+
+  /// CHECK: PDA to hold vault's assets
+  #[account(
+    seeds = [
+      &SIGNER_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump = vault.signer_nonce
+  )]
+  pub vault_signer: AccountInfo<'info>,
+
+  /// CHECK: Program's TokenAccount for distribution
+  #[account(mut)]
+  pub vault_token0: AccountInfo<'info>,
+
+  /// CHECK: User account eligible to redeem token. Must sign to provide proof of redemption
+  #[account(signer)]
+  pub user: AccountInfo<'info>,
+
+  /// CHECK: User account to receive token, mint and owner verified in instruction
+  #[account(mut)]
+  pub user_token0: AccountInfo<'info>,
+
+  /// CHECK: Solana native Token Program
+  #[account(
+    constraint = is_token_program(&token_program) @ErrorCode::InvalidAccount
+  )]
+  pub token_program: AccountInfo<'info>,
+
+  pub system_program: Program<'info, System>,
+
+  /// CHECK: Program's TokenAccount to receive sending_amount, verified in instruction
+  #[account(mut)]
+  pub vault_token1: Option<AccountInfo<'info>>,
+
+  /// CHECK: User account to pay sending_amount, verified in instruction
+  #[account(mut)]
+  pub user_token1: Option<AccountInfo<'info>>,
+
+  /// CHECK: Solana Associated Token Program, required to create user_token0
+  pub associated_token_program: Option<AccountInfo<'info>>,
+
+  /// CHECK: Mint of user_token0, verified in instruction
+  pub user_token0_mint: Option<AccountInfo<'info>>,
+
+  /// CHECK: Account paying for the creation of user_token0, can be the user or a relayer
+  #[account(signer, mut)]
+  pub payer: Option<AccountInfo<'info>>,
+
+  /// CHECK: PDA holding the SOL reserve of the vault, may be uninitialized. Verified in instruction
+  #[account(mut)]
+  pub vault_reserve: AccountInfo<'info>,
+
+  /// CHECK: PDA holding the claim fee of the vault, may be uninitialized. Verified in instruction
+  pub vault_fee: AccountInfo<'info>,
+
+  /// CHECK: Treasury receiving the SOL claim fees, verified in instruction
+  #[account(mut)]
+  pub treasury: Option<AccountInfo<'info>>,
+
+  /// CHECK: Treasury token account receiving the token claim fees, verified in instruction
+  #[account(mut)]
+  pub treasury_token: Option<AccountInfo<'info>>,
+
+  /// Tranches appended to the schedule, required to redeem from them
+  #[account(
+    seeds = [
+      &TRANCHE_SEED_1,
+      schedule.to_account_info().key.as_ref(),
+    ],
+    bump = schedule_tranche.nonce,
+  )]
+  pub schedule_tranche: Option<Account<'info, ScheduleTranche>>,
+
+  /// CHECK: PDA holding the mint allowlist of the vault, may be uninitialized. Verified in instruction
+  pub vault_allowlist: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemTokenSwapContext<'info> {
+
+  pub vault: Account<'info, Vault>,
+
+  #[account(
+    mut,
+    seeds = [
+      &SCHEDULE_SEED_1,
+      &shared::derive_event_id(schedule.event_id).as_ref(),
+    ],
+    bump = schedule.nonce,
+    constraint = schedule.vault_id == vault.key() @ErrorCode::InvalidAccount,
+  )]
+  pub schedule: Account<'info, Schedule>,
+
+  /// CHECK: PDA to hold vault's assets
+  #[account(
+    seeds = [
+      &SIGNER_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump = vault.signer_nonce
+  )]
+  pub vault_signer: AccountInfo<'info>,
+
+  /// CHECK: Program's TokenAccount for distribution
+  #[account(
+    mut,
+    constraint = *vault_token0.key == schedule.receiving_token_account @ErrorCode::InvalidAccount
+  )]
+  pub vault_token0: AccountInfo<'info>,
+
+  /// CHECK: User account eligible to redeem token. Must sign to provide proof of redemption and to authorize the swap
+  #[account(signer, mut)]
+  pub user: AccountInfo<'info>,
+
+  /// CHECK: User account receiving the distributed token as swap input, mint and owner verified in instruction
+  #[account(mut)]
+  pub user_token0: AccountInfo<'info>,
+
+  /// CHECK: User account receiving the swap output, mint and owner verified in instruction
+  #[account(mut)]
+  pub user_token_out: AccountInfo<'info>,
+
+  #[account(
+    seeds = [
+      &SWAP_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump = vault_swap.nonce,
+  )]
+  pub vault_swap: Account<'info, VaultSwap>,
+
+  /// CHECK: DEX program configured for the vault
+  #[account(
+    executable,
+    constraint = *swap_program.key == vault_swap.swap_program @ErrorCode::InvalidAccount,
+  )]
+  pub swap_program: AccountInfo<'info>,
+
+  /// CHECK: PDA holding the claim fee of the vault, may be uninitialized. Verified in instruction
+  pub vault_fee: AccountInfo<'info>,
+
+  /// CHECK: Solana native Token Program
+  #[account(
+    constraint = is_token_program(&token_program) @ErrorCode::InvalidAccount
+  )]
+  pub token_program: AccountInfo<'info>,
+
+  pub system_program: Program<'info, System>,
+
+  /// CHECK: Program's TokenAccount to receive sending_amount, verified in instruction
+  #[account(mut)]
+  pub vault_token1: Option<AccountInfo<'info>>,
+
+  /// CHECK: User account to pay sending_amount, verified in instruction
+  #[account(mut)]
+  pub user_token1: Option<AccountInfo<'info>>,
+
+  /// CHECK: Treasury receiving the SOL claim fees, verified in instruction
+  #[account(mut)]
+  pub treasury: Option<AccountInfo<'info>>,
+
+  /// CHECK: Treasury token account receiving the token claim fees, verified in instruction
+  #[account(mut)]
+  pub treasury_token: Option<AccountInfo<'info>>,
+
+  /// Tranches appended to the schedule, required to redeem from them
+  #[account(
+    seeds = [
+      &TRANCHE_SEED_1,
+      schedule.to_account_info().key.as_ref(),
+    ],
+    bump = schedule_tranche.nonce,
+  )]
+  pub schedule_tranche: Option<Account<'info, ScheduleTranche>>,
+
+  // remaining accounts: accounts of the swap instruction, in order
+}
+
+#[derive(Accounts)]
+pub struct RedeemTokenStakeContext<'info> {
+
+  pub vault: Account<'info, Vault>,
+
+  #[account(
+    mut,
+    seeds = [
+      &SCHEDULE_SEED_1,
+      &shared::derive_event_id(schedule.event_id).as_ref(),
+    ],
+    bump = schedule.nonce,
+    constraint = schedule.vault_id == vault.key() @ErrorCode::InvalidAccount,
+  )]
+  pub schedule: Account<'info, Schedule>,
+
+  /// CHECK: PDA to hold vault's assets
+  #[account(
+    seeds = [
+      &SIGNER_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump = vault.signer_nonce
+  )]
+  pub vault_signer: AccountInfo<'info>,
+
+  /// CHECK: Program's TokenAccount for distribution
+  #[account(
+    mut,
+    constraint = *vault_token0.key == schedule.receiving_token_account @ErrorCode::InvalidAccount
+  )]
+  pub vault_token0: AccountInfo<'info>,
+
+  /// CHECK: User account eligible to redeem token. Must sign to provide proof of redemption and to authorize the stake
+  #[account(signer, mut)]
+  pub user: AccountInfo<'info>,
+
+  /// CHECK: User account receiving the distributed token before it is staked, mint and owner verified in instruction
+  #[account(mut)]
+  pub user_token0: AccountInfo<'info>,
+
+  /// CHECK: Stake position of the user, verified by the staking program
+  #[account(mut)]
+  pub user_stake: AccountInfo<'info>,
+
+  #[account(
+    seeds = [
+      &STAKE_SEED_1,
+      schedule.to_account_info().key.as_ref(),
+    ],
+    bump = schedule_stake.nonce,
+  )]
+  pub schedule_stake: Account<'info, ScheduleStake>,
+
+  /// CHECK: Staking program whitelisted for the schedule
+  #[account(
+    executable,
+    constraint = *stake_program.key == schedule_stake.stake_program @ErrorCode::InvalidAccount,
+  )]
+  pub stake_program: AccountInfo<'info>,
+
+  /// CHECK: PDA holding the claim fee of the vault, may be uninitialized. Verified in instruction
+  pub vault_fee: AccountInfo<'info>,
+
+  /// CHECK: Solana native Token Program
+  #[account(
+    constraint = is_token_program(&token_program) @ErrorCode::InvalidAccount
+  )]
+  pub token_program: AccountInfo<'info>,
+
+  pub system_program: Program<'info, System>,
+
+  /// CHECK: Program's TokenAccount to receive sending_amount, verified in instruction
+  #[account(mut)]
+  pub vault_token1: Option<AccountInfo<'info>>,
+
+  /// CHECK: User account to pay sending_amount, verified in instruction
+  #[account(mut)]
+  pub user_token1: Option<AccountInfo<'info>>,
+
+  /// CHECK: Treasury receiving the SOL claim fees, verified in instruction
+  #[account(mut)]
+  pub treasury: Option<AccountInfo<'info>>,
+
+  /// CHECK: Treasury token account receiving the token claim fees, verified in instruction
+  #[account(mut)]
+  pub treasury_token: Option<AccountInfo<'info>>,
+
+  /// Tranches appended to the schedule, required to redeem from them
+  #[account(
+    seeds = [
+      &TRANCHE_SEED_1,
+      schedule.to_account_info().key.as_ref(),
+    ],
+    bump = schedule_tranche.nonce,
+  )]
+  pub schedule_tranche: Option<Account<'info, ScheduleTranche>>,
+
+  // remaining accounts: accounts of the stake instruction, in order
+}
+
+#[derive(Accounts)]
+pub struct RedeemNftContext<'info> {
+
+  pub vault: Account<'info, Vault>,
+
+  #[account(
+    mut,
+    seeds = [
+      &SCHEDULE_SEED_1,
+      &shared::derive_event_id(schedule.event_id).as_ref(),
+    ],
+    bump = schedule.nonce,
+    constraint = schedule.vault_id == vault.key() @ErrorCode::InvalidAccount,
+  )]
+  pub schedule: Account<'info, Schedule>,
+
+  /// CHECK: PDA to hold vault's assets
+  #[account(
+    seeds = [
+      &SIGNER_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump = vault.signer_nonce
+  )]
+  pub vault_signer: AccountInfo<'info>,
+
+  /// CHECK: Vault signer's TokenAccount holding the NFT, mint and owner verified in instruction
+  #[account(mut)]
+  pub vault_token0: AccountInfo<'info>,
+
+  /// CHECK: User account eligible to redeem the NFT. Must sign to provide proof of redemption
+  #[account(signer, mut)]
+  pub user: AccountInfo<'info>,
+
+  /// CHECK: User account to receive the NFT, mint and owner verified in instruction
+  #[account(mut)]
+  pub user_token0: AccountInfo<'info>,
+
+  /// CHECK: PDA holding the claim fee of the vault, may be uninitialized. Verified in instruction
+  pub vault_fee: AccountInfo<'info>,
+
+  /// CHECK: Solana native Token Program
+  #[account(
+    constraint = is_token_program(&token_program) @ErrorCode::InvalidAccount
+  )]
+  pub token_program: AccountInfo<'info>,
+
+  pub system_program: Program<'info, System>,
+
+  /// CHECK: Solana Associated Token Program, required to create user_token0
+  pub associated_token_program: Option<AccountInfo<'info>>,
+
+  /// CHECK: Mint of user_token0, verified in instruction
+  pub user_token0_mint: Option<AccountInfo<'info>>,
+
+  /// CHECK: Account paying for the creation of user_token0, can be the user or a relayer
+  #[account(signer, mut)]
+  pub payer: Option<AccountInfo<'info>>,
+
+  /// CHECK: Treasury receiving the SOL claim fees, verified in instruction
+  #[account(mut)]
+  pub treasury: Option<AccountInfo<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemNftEditionContext<'info> {
+
+  pub vault: Account<'info, Vault>,
+
+  #[account(
+    mut,
+    seeds = [
+      &SCHEDULE_SEED_1,
+      &shared::derive_event_id(schedule.event_id).as_ref(),
+    ],
+    bump = schedule.nonce,
+    constraint = schedule.vault_id == vault.key() @ErrorCode::InvalidAccount,
+  )]
+  pub schedule: Account<'info, Schedule>,
+
+  /// CHECK: PDA to hold vault's assets
+  #[account(
+    seeds = [
+      &SIGNER_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump = vault.signer_nonce
+  )]
+  pub vault_signer: AccountInfo<'info>,
+
+  /// CHECK: Vault signer's TokenAccount holding the master edition token, mint and owner verified in instruction
+  pub vault_token0: AccountInfo<'info>,
+
+  /// CHECK: User account eligible to redeem the edition. Must sign as the authority of new_mint and pays for the new accounts
+  #[account(signer, mut)]
+  pub user: AccountInfo<'info>,
+
+  /// CHECK: Metadata of the new edition, verified by the Token Metadata Program
+  #[account(mut)]
+  pub new_metadata: AccountInfo<'info>,
+
+  /// CHECK: Edition account of the new edition, verified by the Token Metadata Program
+  #[account(mut)]
+  pub new_edition: AccountInfo<'info>,
+
+  /// CHECK: Master edition of the asset, verified in instruction
+  #[account(mut)]
+  pub master_edition: AccountInfo<'info>,
+
+  /// CHECK: Mint of the new edition with a supply of one, verified by the Token Metadata Program
+  #[account(mut)]
+  pub new_mint: AccountInfo<'info>,
+
+  /// CHECK: Edition marker of the edition number, verified by the Token Metadata Program
+  #[account(mut)]
+  pub edition_mark: AccountInfo<'info>,
+
+  /// CHECK: Update authority of the master metadata, verified by the Token Metadata Program
+  pub new_metadata_update_authority: AccountInfo<'info>,
+
+  /// CHECK: Metadata of the asset, verified in instruction
+  pub metadata: AccountInfo<'info>,
+
+  /// CHECK: PDA holding the claim fee of the vault, may be uninitialized. Verified in instruction
+  pub vault_fee: AccountInfo<'info>,
+
+  /// CHECK: Metaplex Token Metadata Program
+  #[account(
+    constraint = is_token_metadata_program(&token_metadata_program) @ErrorCode::InvalidAccount
+  )]
+  pub token_metadata_program: AccountInfo<'info>,
+
+  /// CHECK: Solana native Token Program
+  #[account(
+    constraint = is_token_program(&token_program) @ErrorCode::InvalidAccount
+  )]
+  pub token_program: AccountInfo<'info>,
+
+  pub system_program: Program<'info, System>,
+
+  /// CHECK: Treasury receiving the SOL claim fees, verified in instruction
+  #[account(mut)]
+  pub treasury: Option<AccountInfo<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemCompressedNftContext<'info> {
+
+  pub vault: Account<'info, Vault>,
+
+  #[account(
+    mut,
+    seeds = [
+      &SCHEDULE_SEED_1,
+      &shared::derive_event_id(schedule.event_id).as_ref(),
+    ],
+    bump = schedule.nonce,
+    constraint = schedule.vault_id == vault.key() @ErrorCode::InvalidAccount,
+  )]
+  pub schedule: Account<'info, Schedule>,
+
+  /// CHECK: PDA to hold vault's assets
+  #[account(
+    seeds = [
+      &SIGNER_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump = vault.signer_nonce
+  )]
+  pub vault_signer: AccountInfo<'info>,
+
+  /// CHECK: User account eligible to redeem the NFT. Must sign to provide proof of redemption
+  #[account(signer, mut)]
+  pub user: AccountInfo<'info>,
+
+  /// CHECK: Delegate of the compressed NFT, the vault signer if none. Verified by the Bubblegum Program
+  pub leaf_delegate: AccountInfo<'info>,
+
+  /// CHECK: Tree config of merkle_tree, verified by the Bubblegum Program
+  pub tree_authority: AccountInfo<'info>,
+
+  /// CHECK: Concurrent Merkle tree holding the compressed NFT, verified by the Bubblegum Program
+  #[account(mut)]
+  pub merkle_tree: AccountInfo<'info>,
+
+  /// CHECK: PDA holding the claim fee of the vault, may be uninitialized. Verified in instruction
+  pub vault_fee: AccountInfo<'info>,
+
+  /// CHECK: SPL Noop Program
+  #[account(
+    constraint = *log_wrapper.key == spl_noop::ID @ErrorCode::InvalidAccount
+  )]
+  pub log_wrapper: AccountInfo<'info>,
+
+  /// CHECK: SPL Account Compression Program
+  #[account(
+    constraint = *compression_program.key == spl_account_compression::ID @ErrorCode::InvalidAccount
+  )]
+  pub compression_program: AccountInfo<'info>,
+
+  /// CHECK: Metaplex Bubblegum Program
+  #[account(
+    constraint = is_bubblegum_program(&bubblegum_program) @ErrorCode::InvalidAccount
+  )]
+  pub bubblegum_program: AccountInfo<'info>,
+
+  pub system_program: Program<'info, System>,
+
+  /// CHECK: Treasury receiving the SOL claim fees, verified in instruction
+  #[account(mut)]
+  pub treasury: Option<AccountInfo<'info>>,
+
+  // remaining accounts: nodes of the proof of the compressed NFT in merkle_tree
+}
+
+#[derive(Accounts)]
+pub struct RedeemVoucherContext<'info> {
+
+  pub vault: Account<'info, Vault>,
+
+  #[account(
+    seeds = [
+      &SCHEDULE_SEED_1,
+      &shared::derive_event_id(schedule.event_id).as_ref(),
+    ],
+    bump = schedule.nonce,
+    constraint = schedule.vault_id == vault.key() @ErrorCode::InvalidAccount,
+  )]
+  pub schedule: Account<'info, Schedule>,
+
+  #[account(
+    mut,
+    seeds = [
+      &VOUCHER_SEED_1,
+      schedule.to_account_info().key.as_ref(),
+    ],
+    bump = schedule_voucher.nonce,
+  )]
+  pub schedule_voucher: Account<'info, ScheduleVoucher>,
+
+  /// CHECK: PDA to hold vault's assets
+  #[account(
+    seeds = [
+      &SIGNER_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump = vault.signer_nonce
+  )]
+  pub vault_signer: AccountInfo<'info>,
+
+  /// CHECK: Program's TokenAccount for distribution
+  #[account(
+    mut,
+    constraint = *vault_token0.key == schedule.receiving_token_account @ErrorCode::InvalidAccount
+  )]
+  pub vault_token0: AccountInfo<'info>,
+
+  /// CHECK: User account named in the voucher. Must sign to redeem it
+  #[account(signer, mut)]
+  pub user: AccountInfo<'info>,
+
+  /// CHECK: User account to receive token, mint and owner verified in instruction
+  #[account(mut)]
+  pub user_token0: AccountInfo<'info>,
+
+  /// CHECK: PDA holding the claim fee of the vault, may be uninitialized. Verified in instruction
+  pub vault_fee: AccountInfo<'info>,
+
+  /// CHECK: Instructions sysvar, holding the ed25519 instruction verifying the voucher
+  #[account(address = sysvar_instructions::ID @ErrorCode::InvalidAccount)]
+  pub instructions: AccountInfo<'info>,
+
+  /// CHECK: Solana native Token Program
+  #[account(
+    constraint = is_token_program(&token_program) @ErrorCode::InvalidAccount
+  )]
+  pub token_program: AccountInfo<'info>,
+
+  pub system_program: Program<'info, System>,
+
+  /// CHECK: PDA holding the SOL reserve of the vault, may be uninitialized. Verified in instruction
+  #[account(mut)]
+  pub vault_reserve: AccountInfo<'info>,
+
+  /// CHECK: Treasury receiving the SOL claim fees, verified in instruction
+  #[account(mut)]
+  pub treasury: Option<AccountInfo<'info>>,
+
+  /// CHECK: Treasury token account receiving the token claim fees, verified in instruction
+  #[account(mut)]
+  pub treasury_token: Option<AccountInfo<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemEpochContext<'info> {
+
+  pub vault: Account<'info, Vault>,
+
+  #[account(
+    seeds = [
+      &SCHEDULE_SEED_1,
+      &shared::derive_event_id(schedule.event_id).as_ref(),
+    ],
+    bump = schedule.nonce,
+    constraint = schedule.vault_id == vault.key() @ErrorCode::InvalidAccount,
+  )]
+  pub schedule: Account<'info, Schedule>,
+
+  #[account(
+    seeds = [
+      &EPOCH_SEED_1,
+      schedule.to_account_info().key.as_ref(),
+    ],
+    bump = schedule_epoch.nonce,
+  )]
+  pub schedule_epoch: Account<'info, ScheduleEpoch>,
+
+  #[account(
+    init_if_needed,
+    seeds = [
+      &CLAIM_SEED_1,
+      schedule.to_account_info().key.as_ref(),
+      user.key.as_ref(),
+    ],
+    bump,
+    payer = user,
+    space = 16 + EpochClaim::size(),
+  )]
+  pub epoch_claim: Account<'info, EpochClaim>,
+
+  /// CHECK: PDA to hold vault's assets
+  #[account(
+    seeds = [
+      &SIGNER_SEED_1,
+      vault.to_account_info().key.as_ref(),
+    ],
+    bump = vault.signer_nonce
+  )]
+  pub vault_signer: AccountInfo<'info>,
+
+  /// CHECK: Program's TokenAccount for distribution
+  #[account(
+    mut,
+    constraint = *vault_token0.key == schedule.receiving_token_account @ErrorCode::InvalidAccount
+  )]
+  pub vault_token0: AccountInfo<'info>,
+
+  /// CHECK: User account eligible to redeem token. Must sign to provide proof of redemption
+  #[account(signer, mut)]
+  pub user: AccountInfo<'info>,
+
+  /// CHECK: User account to receive token, mint and owner verified in instruction
+  #[account(mut)]
+  pub user_token0: AccountInfo<'info>,
+
+  /// CHECK: PDA holding the claim fee of the vault, may be uninitialized. Verified in instruction
+  pub vault_fee: AccountInfo<'info>,
+
+  /// CHECK: Solana native Token Program
+  #[account(
+    constraint = is_token_program(&token_program) @ErrorCode::InvalidAccount
+  )]
+  pub token_program: AccountInfo<'info>,
+
+  pub system_program: Program<'info, System>,
+
+  /// CHECK: PDA holding the SOL reserve of the vault, may be uninitialized. Verified in instruction
+  #[account(mut)]
+  pub vault_reserve: AccountInfo<'info>,
+
+  /// CHECK: Treasury receiving the SOL claim fees, verified in instruction
+  #[account(mut)]
+  pub treasury: Option<AccountInfo<'info>>,
+
+  /// CHECK: Treasury token account receiving the token claim fees, verified in instruction
+  #[account(mut)]
+  pub treasury_token: Option<AccountInfo<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct GetClaimStatusContext<'info> {
+
+  #[account(
+    seeds = [
+      &SCHEDULE_SEED_1,
+      &shared::derive_event_id(schedule.event_id).as_ref(),
+    ],
+    bump = schedule.nonce,
+  )]
+  pub schedule: Account<'info, Schedule>,
+}
+
+#[derive(Accounts)]
+pub struct TransferOwnershipContext<'info> {
+
+  /// CHECK: vault owner, verified using #access_control
+    #[account(signer)]
+    pub owner: AccountInfo<'info>,
 
     #[account(mut)]
     pub vault: Account<'info, Vault>,
 }
+
+#[derive(Accounts)]
+pub struct AcceptOwnershipContext<'info> {
+
+  /// CHECK: new vault owner, verified using #access_control
+    #[account(signer)]
+    pub new_owner: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateVaultContext<'info> {
+
+  /// CHECK: vault owner, verified in instruction
+  #[account(signer, mut)]
+  pub owner: AccountInfo<'info>,
+
+  /// CHECK: vault in the current or previous layout, verified in instruction
+  #[account(mut)]
+  pub vault: AccountInfo<'info>,
+
+  pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateScheduleContext<'info> {
+
+  /// CHECK: vault admin, verified in instruction
+  #[account(signer, mut)]
+  pub admin: AccountInfo<'info>,
+
+  /// CHECK: vault in the current or previous layout, verified in instruction
+  pub vault: AccountInfo<'info>,
+
+  /// CHECK: schedule in the current or previous layout, verified in instruction
+  #[account(mut)]
+  pub schedule: AccountInfo<'info>,
+
+  pub system_program: Program<'info, System>,
+}
@@ -20,5 +20,50 @@ pub enum ErrorCode {
 
   #[msg("Coin98Vault: Unauthorized.")]
   Unauthorized,
+
+  #[msg("Coin98Vault: Invalid token account.")]
+  InvalidTokenAccount,
+
+  #[msg("Coin98Vault: Invalid token mint.")]
+  InvalidTokenMint,
+
+  #[msg("Coin98Vault: Invalid token owner.")]
+  InvalidTokenOwner,
+
+  #[msg("Coin98Vault: Insufficient SOL reserve.")]
+  InsufficientReserve,
+
+  #[msg("Coin98Vault: Invalid fee.")]
+  InvalidFee,
+
+  #[msg("Coin98Vault: Slippage exceeded.")]
+  SlippageExceeded,
+
+  #[msg("Coin98Vault: Redeemed tokens not staked.")]
+  StakeIncomplete,
+
+  #[msg("Coin98Vault: Mint cap exceeded.")]
+  MintCapExceeded,
+
+  #[msg("Coin98Vault: Invalid voucher signature.")]
+  InvalidSignature,
+
+  #[msg("Coin98Vault: Voucher expired.")]
+  VoucherExpired,
+
+  #[msg("Coin98Vault: Index out of range.")]
+  IndexOutOfRange,
+
+  #[msg("Coin98Vault: Mint not allowed.")]
+  MintNotAllowed,
+
+  #[msg("Coin98Vault: Destination not allowed.")]
+  DestinationNotAllowed,
+
+  #[msg("Coin98Vault: Invalid receiving token account.")]
+  InvalidReceivingTokenAccount,
+
+  #[msg("Coin98Vault: Invalid sending token account.")]
+  InvalidSendingTokenAccount,
 }
 
@@ -22,14 +22,14 @@ pub fn transfer_lamport<'a>(
 ) -> std::result::Result<(), ProgramError> {
 
   let instruction = transfer(
-    &owner.key,
-    &recipient.key,
+    owner.key,
+    recipient.key,
     amount,
   );
-  if signer_seeds.len() == 0 {
+  if signer_seeds.is_empty() {
     invoke(&instruction, &[owner.clone(), recipient.clone()])
   }
   else {
-    invoke_signed(&instruction, &[owner.clone(), recipient.clone()], &signer_seeds)
+    invoke_signed(&instruction, &[owner.clone(), recipient.clone()], signer_seeds)
   }
 }
@@ -76,10 +76,74 @@ pub fn transfer_token<'a>(
     ],
     data: data.try_to_vec().unwrap(),
   };
-  if signer_seeds.len() == 0 {
+  if signer_seeds.is_empty() {
     invoke(&instruction, &[from_pubkey.clone(), to_pubkey.clone(), owner.clone()])
   }
   else {
-    invoke_signed(&instruction, &[from_pubkey.clone(), to_pubkey.clone(), owner.clone()], &signer_seeds)
+    invoke_signed(&instruction, &[from_pubkey.clone(), to_pubkey.clone(), owner.clone()], signer_seeds)
   }
 }
+
+pub fn close_account<'a>(
+  owner: &AccountInfo<'a>,
+  account: &AccountInfo<'a>,
+  destination: &AccountInfo<'a>,
+  signer_seeds: &[&[&[u8]]],
+) -> std::result::Result<(), ProgramError> {
+  let instruction = Instruction {
+    program_id: TOKEN_PROGRAM_ID,
+    accounts: vec![
+      AccountMeta::new(*account.key, false),
+      AccountMeta::new(*destination.key, false),
+      AccountMeta::new_readonly(*owner.key, true),
+    ],
+    data: vec![9],
+  };
+  if signer_seeds.is_empty() {
+    invoke(&instruction, &[account.clone(), destination.clone(), owner.clone()])
+  }
+  else {
+    invoke_signed(&instruction, &[account.clone(), destination.clone(), owner.clone()], signer_seeds)
+  }
+}
+
+pub fn mint_to<'a>(
+  mint_authority: &AccountInfo<'a>,
+  mint: &AccountInfo<'a>,
+  account: &AccountInfo<'a>,
+  amount: u64,
+  signer_seeds: &[&[&[u8]]],
+) -> std::result::Result<(), ProgramError> {
+  let data = TransferTokenParams {
+    instruction: 7,
+    amount,
+  };
+  let instruction = Instruction {
+    program_id: TOKEN_PROGRAM_ID,
+    accounts: vec![
+      AccountMeta::new(*mint.key, false),
+      AccountMeta::new(*account.key, false),
+      AccountMeta::new_readonly(*mint_authority.key, true),
+    ],
+    data: data.try_to_vec().unwrap(),
+  };
+  if signer_seeds.is_empty() {
+    invoke(&instruction, &[mint.clone(), account.clone(), mint_authority.clone()])
+  }
+  else {
+    invoke_signed(&instruction, &[mint.clone(), account.clone(), mint_authority.clone()], signer_seeds)
+  }
+}
+
+pub fn sync_native<'a>(
+  account: &AccountInfo<'a>,
+) -> std::result::Result<(), ProgramError> {
+  let instruction = Instruction {
+    program_id: TOKEN_PROGRAM_ID,
+    accounts: vec![
+      AccountMeta::new(*account.key, false),
+    ],
+    data: vec![17],
+  };
+  invoke(&instruction, std::slice::from_ref(account))
+}
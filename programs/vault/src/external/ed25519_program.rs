@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use solana_program::{
+  ed25519_program::{
+    ID,
+  },
+  instruction::{
+    Instruction,
+  },
+};
+
+const PUBKEY_SIZE: usize = 32;
+const SIGNATURE_SIZE: usize = 64;
+const SIGNATURE_OFFSETS_START: usize = 2;
+const SIGNATURE_OFFSETS_SIZE: usize = 14;
+// instruction index meaning the data is in the ed25519 instruction itself
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+  let bytes = data.get(offset..offset + 2)?;
+  Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+/// Returns true if `instruction` is an ed25519 program instruction verifying exactly one
+/// signature of `message` by `signer`, with the signature, public key and message all
+/// stored in the instruction data
+pub fn is_ed25519_signature(instruction: &Instruction, signer: &Pubkey, message: &[u8]) -> bool {
+  if instruction.program_id != ID || !instruction.accounts.is_empty() {
+    return false;
+  }
+  let data = &instruction.data;
+  if data.len() < SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_SIZE || data[0] != 1 {
+    return false;
+  }
+
+  let offsets = (0..7)
+    .map(|i| read_u16(data, SIGNATURE_OFFSETS_START + 2 * i))
+    .collect::<Option<Vec<u16>>>();
+  let (signature_offset, public_key_offset, message_offset, message_size) = match offsets.as_deref() {
+    Some(&[signature_offset, signature_index, public_key_offset, public_key_index, message_offset, message_size, message_index])
+      if signature_index == CURRENT_INSTRUCTION
+        && public_key_index == CURRENT_INSTRUCTION
+        && message_index == CURRENT_INSTRUCTION => {
+      (usize::from(signature_offset), usize::from(public_key_offset), usize::from(message_offset), usize::from(message_size))
+    },
+    _ => return false,
+  };
+
+  let has_signature = data.get(signature_offset..signature_offset + SIGNATURE_SIZE).is_some();
+  let public_key = data.get(public_key_offset..public_key_offset + PUBKEY_SIZE);
+  let signed_message = data.get(message_offset..message_offset + message_size);
+  has_signature && public_key == Some(signer.as_ref()) && signed_message == Some(message)
+}
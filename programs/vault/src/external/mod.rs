@@ -1,3 +1,8 @@
 pub mod anchor_spl_system;
 pub mod anchor_spl_token;
+pub mod ed25519_program;
+pub mod mpl_bubblegum;
+pub mod mpl_token_metadata;
+pub mod passthrough;
+pub mod spl_associated_token;
 pub mod spl_token;
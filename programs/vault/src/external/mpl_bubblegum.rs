@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+use solana_program::{
+  instruction::{
+    Instruction,
+  },
+  program::{
+    invoke_signed,
+  },
+};
+
+declare_id!("BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY");
+
+pub mod spl_account_compression {
+  solana_program::declare_id!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+}
+
+pub mod spl_noop {
+  solana_program::declare_id!("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+}
+
+const TRANSFER_DISCRIMINATOR: [u8; 8] = [163, 52, 200, 231, 140, 3, 69, 186];
+
+pub fn is_bubblegum_program<'a>(account: &AccountInfo<'a>) -> bool {
+  *account.key == ID
+}
+
+/// Returns the asset id of the compressed NFT minted into `merkle_tree` with `nonce`
+pub fn get_asset_id(merkle_tree: &Pubkey, nonce: u64) -> Pubkey {
+  Pubkey::find_program_address(
+    &[
+      b"asset",
+      merkle_tree.as_ref(),
+      &nonce.to_le_bytes(),
+    ],
+    &ID,
+  ).0
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Default)]
+pub struct TransferParams {
+  pub root: [u8; 32],
+  pub data_hash: [u8; 32],
+  pub creator_hash: [u8; 32],
+  pub nonce: u64,
+  pub index: u32,
+}
+
+/// Transfers a compressed NFT owned by `leaf_owner`. `proofs` are the nodes of the
+/// concurrent Merkle tree proof of the leaf.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_compressed_nft<'a>(
+  tree_authority: &AccountInfo<'a>,
+  leaf_owner: &AccountInfo<'a>,
+  leaf_delegate: &AccountInfo<'a>,
+  new_leaf_owner: &AccountInfo<'a>,
+  merkle_tree: &AccountInfo<'a>,
+  log_wrapper: &AccountInfo<'a>,
+  compression_program: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>,
+  proofs: &[AccountInfo<'a>],
+  params: TransferParams,
+  signer_seeds: &[&[&[u8]]],
+) -> std::result::Result<(), ProgramError> {
+  let mut data = TRANSFER_DISCRIMINATOR.to_vec();
+  data.extend(params.try_to_vec().unwrap());
+
+  let mut accounts = vec![
+    AccountMeta::new_readonly(*tree_authority.key, false),
+    AccountMeta::new_readonly(*leaf_owner.key, true),
+    AccountMeta::new_readonly(*leaf_delegate.key, false),
+    AccountMeta::new_readonly(*new_leaf_owner.key, false),
+    AccountMeta::new(*merkle_tree.key, false),
+    AccountMeta::new_readonly(*log_wrapper.key, false),
+    AccountMeta::new_readonly(*compression_program.key, false),
+    AccountMeta::new_readonly(*system_program.key, false),
+  ];
+  accounts.extend(proofs.iter().map(|proof| AccountMeta::new_readonly(*proof.key, false)));
+  let instruction = Instruction {
+    program_id: ID,
+    accounts,
+    data,
+  };
+
+  let mut account_infos = vec![
+    tree_authority.clone(),
+    leaf_owner.clone(),
+    leaf_delegate.clone(),
+    new_leaf_owner.clone(),
+    merkle_tree.clone(),
+    log_wrapper.clone(),
+    compression_program.clone(),
+    system_program.clone(),
+  ];
+  account_infos.extend_from_slice(proofs);
+  invoke_signed(&instruction, &account_infos, signer_seeds)
+}
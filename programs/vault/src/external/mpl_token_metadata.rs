@@ -0,0 +1,106 @@
+use anchor_lang::prelude::*;
+use solana_program::{
+  instruction::{
+    Instruction,
+  },
+  program::{
+    invoke_signed,
+  },
+};
+
+declare_id!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+const MINT_NEW_EDITION_FROM_MASTER_EDITION_VIA_TOKEN: u8 = 11;
+
+pub fn is_token_metadata_program<'a>(account: &AccountInfo<'a>) -> bool {
+  *account.key == ID
+}
+
+pub fn get_metadata_address(mint: &Pubkey) -> Pubkey {
+  Pubkey::find_program_address(
+    &[
+      b"metadata",
+      ID.as_ref(),
+      mint.as_ref(),
+    ],
+    &ID,
+  ).0
+}
+
+pub fn get_master_edition_address(mint: &Pubkey) -> Pubkey {
+  Pubkey::find_program_address(
+    &[
+      b"metadata",
+      ID.as_ref(),
+      mint.as_ref(),
+      b"edition",
+    ],
+    &ID,
+  ).0
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Default)]
+pub struct MintNewEditionParams {
+  pub instruction: u8,
+  pub edition: u64,
+}
+
+/// Prints `edition` of the master edition held in `token_account` into `new_mint`, which
+/// must have a supply of one minted by `new_mint_authority`
+#[allow(clippy::too_many_arguments)]
+pub fn mint_new_edition_from_master_edition_via_token<'a>(
+  new_metadata: &AccountInfo<'a>,
+  new_edition: &AccountInfo<'a>,
+  master_edition: &AccountInfo<'a>,
+  new_mint: &AccountInfo<'a>,
+  edition_mark: &AccountInfo<'a>,
+  new_mint_authority: &AccountInfo<'a>,
+  payer: &AccountInfo<'a>,
+  token_account_owner: &AccountInfo<'a>,
+  token_account: &AccountInfo<'a>,
+  new_metadata_update_authority: &AccountInfo<'a>,
+  metadata: &AccountInfo<'a>,
+  token_program: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>,
+  edition: u64,
+  signer_seeds: &[&[&[u8]]],
+) -> std::result::Result<(), ProgramError> {
+  let data = MintNewEditionParams {
+    instruction: MINT_NEW_EDITION_FROM_MASTER_EDITION_VIA_TOKEN,
+    edition,
+  };
+  let instruction = Instruction {
+    program_id: ID,
+    accounts: vec![
+      AccountMeta::new(*new_metadata.key, false),
+      AccountMeta::new(*new_edition.key, false),
+      AccountMeta::new(*master_edition.key, false),
+      AccountMeta::new(*new_mint.key, false),
+      AccountMeta::new(*edition_mark.key, false),
+      AccountMeta::new_readonly(*new_mint_authority.key, true),
+      AccountMeta::new(*payer.key, true),
+      AccountMeta::new_readonly(*token_account_owner.key, true),
+      AccountMeta::new_readonly(*token_account.key, false),
+      AccountMeta::new_readonly(*new_metadata_update_authority.key, false),
+      AccountMeta::new_readonly(*metadata.key, false),
+      AccountMeta::new_readonly(*token_program.key, false),
+      AccountMeta::new_readonly(*system_program.key, false),
+    ],
+    data: data.try_to_vec().unwrap(),
+  };
+  invoke_signed(&instruction, &[
+    new_metadata.clone(),
+    new_edition.clone(),
+    master_edition.clone(),
+    new_mint.clone(),
+    edition_mark.clone(),
+    new_mint_authority.clone(),
+    payer.clone(),
+    token_account_owner.clone(),
+    token_account.clone(),
+    new_metadata_update_authority.clone(),
+    metadata.clone(),
+    token_program.clone(),
+    system_program.clone(),
+  ], signer_seeds)
+}
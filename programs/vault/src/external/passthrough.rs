@@ -0,0 +1,39 @@
+use solana_program::{
+  account_info::{
+    AccountInfo
+  },
+  instruction::{
+    AccountMeta,
+    Instruction,
+  },
+  program::{
+    invoke,
+  },
+  program_error::{
+    ProgramError,
+  },
+};
+
+/// Forwards `data` to `program` with `accounts` in the same order and with the same
+/// signer and writable flags as they were passed to the vault.
+pub fn invoke_passthrough<'a>(
+  program: &AccountInfo<'a>,
+  accounts: &[AccountInfo<'a>],
+  data: Vec<u8>,
+) -> std::result::Result<(), ProgramError> {
+
+  let instruction = Instruction {
+    program_id: *program.key,
+    accounts: accounts.iter()
+      .map(|account| AccountMeta {
+        pubkey: *account.key,
+        is_signer: account.is_signer,
+        is_writable: account.is_writable,
+      })
+      .collect(),
+    data,
+  };
+  let mut account_infos = accounts.to_vec();
+  account_infos.push(program.clone());
+  invoke(&instruction, &account_infos)
+}
@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use solana_program::{
+  instruction::{
+    Instruction,
+  },
+  program::{
+    invoke,
+  },
+};
+use crate::external::spl_token::{
+  ID as TOKEN_PROGRAM_ID,
+};
+
+declare_id!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+pub fn is_associated_token_program<'a>(account: &AccountInfo<'a>) -> bool {
+  *account.key == ID
+}
+
+pub fn get_associated_token_address(wallet: &Pubkey, mint: &Pubkey) -> Pubkey {
+  Pubkey::find_program_address(
+    &[
+      wallet.as_ref(),
+      TOKEN_PROGRAM_ID.as_ref(),
+      mint.as_ref(),
+    ],
+    &ID,
+  ).0
+}
+
+/// Creates the associated token account of `wallet` for `mint`, does nothing if it
+/// already exists
+pub fn create_associated_token_account_idempotent<'a>(
+  payer: &AccountInfo<'a>,
+  associated_token: &AccountInfo<'a>,
+  wallet: &AccountInfo<'a>,
+  mint: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>,
+  token_program: &AccountInfo<'a>,
+) -> std::result::Result<(), ProgramError> {
+  let instruction = Instruction {
+    program_id: ID,
+    accounts: vec![
+      AccountMeta::new(*payer.key, true),
+      AccountMeta::new(*associated_token.key, false),
+      AccountMeta::new_readonly(*wallet.key, false),
+      AccountMeta::new_readonly(*mint.key, false),
+      AccountMeta::new_readonly(*system_program.key, false),
+      AccountMeta::new_readonly(*token_program.key, false),
+    ],
+    data: vec![1],
+  };
+  invoke(&instruction, &[
+    payer.clone(),
+    associated_token.clone(),
+    wallet.clone(),
+    mint.clone(),
+    system_program.clone(),
+    token_program.clone(),
+  ])
+}
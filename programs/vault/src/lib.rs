@@ -14,33 +14,89 @@ use solana_program::{
     Pack,
   },
   system_program::ID as SYSTEM_PROGRAM_ID,
+  sysvar::{
+    instructions::{
+      load_current_index_checked,
+      load_instruction_at_checked,
+    },
+  },
 };
 use std::{
   convert::{
+    TryFrom,
     TryInto,
   },
 };
 
 use crate::{constant::{
+  ALLOWED_MINTS_MAX,
+  ALLOWLIST_SEED_1,
+  BPS_DENOMINATOR,
+  DESTINATIONS_MAX,
+  DESTINATION_SEED_1,
+  DESTINATION_TIMELOCK,
+  FEE_SEED_1,
+  RESERVE_SEED_1,
+  SCHEDULE_VERSION,
   SIGNER_SEED_1,
-}, state::{RedemptionMultiParamsV2, RedemptionParams, RedemptionParamsV2}};
+  STAKE_ACCOUNTS_MAX,
+  TRANCHES_MAX,
+  VAULT_VERSION,
+}, state::{RedemptionEpochParams, RedemptionMultiParamsV2, RedemptionNftParams, RedemptionParams, RedemptionParamsV2}};
 use crate::context::*;
 use crate::error::{
   ErrorCode,
 };
 use crate::state::{
+  BitmapSchedule,
+  ClaimStatus,
   ObjType,
   RedemptionMultiParams,
   Schedule,
+  ScheduleStake,
+  ScheduleTranche,
+  ScheduleVoucher,
+  Tranche,
   Vault,
+  VaultAllowlist,
+  VaultDestinations,
+  VaultFee,
+  VaultReserve,
+  Voucher,
+  WithdrawalDestination,
 };
 use crate::external::anchor_spl_system::{
   transfer_lamport,
 };
 use crate::external::anchor_spl_token::{
+  close_account,
+  mint_to,
+  sync_native,
   transfer_token,
 };
+use crate::external::ed25519_program::{
+  is_ed25519_signature,
+};
+use crate::external::mpl_bubblegum::{
+  get_asset_id,
+  transfer_compressed_nft,
+  TransferParams as BubblegumTransferParams,
+};
+use crate::external::mpl_token_metadata::{
+  get_master_edition_address,
+  get_metadata_address,
+  mint_new_edition_from_master_edition_via_token,
+};
+use crate::external::passthrough::{
+  invoke_passthrough,
+};
+use crate::external::spl_associated_token::{
+  create_associated_token_account_idempotent,
+  is_associated_token_program,
+};
 use crate::external::spl_token::{
+  ID as TOKEN_PROGRAM_ID,
+  native_mint::ID as NATIVE_MINT_ID,
   TokenAccount,
 };
 
@@ -77,6 +133,7 @@ mod coin98_vault {
     vault.signer_nonce = signer_nonce;
     vault.owner = *owner.key;
     vault.new_owner = anchor_lang::system_program::ID; // Set to empty
+    vault.version = VAULT_VERSION;
 
     Ok(())
   }
@@ -94,6 +151,7 @@ mod coin98_vault {
     Ok(())
   }
 
+  #[allow(clippy::too_many_arguments)]
   #[access_control(is_admin(&ctx.accounts.admin.key, &ctx.accounts.vault))]
   pub fn create_schedule(
     ctx: Context<CreateScheduleContext>,
@@ -108,7 +166,12 @@ mod coin98_vault {
     sending_token_account: Pubkey,
   ) -> Result<()> {
 
+    verify_payout_authority(ctx.accounts.admin.key, &ctx.accounts.vault, &ctx.accounts.vault_destinations, ctx.program_id)?;
+
     let vault = &ctx.accounts.vault;
+    let vault_allowlist = load_vault_allowlist(&ctx.accounts.vault_allowlist, &vault.key(), ctx.program_id)?;
+    verify_allowed_mint(&receiving_token_mint, &vault_allowlist)?;
+    verify_allowed_mint(&sending_token_mint, &vault_allowlist)?;
 
     let schedule = &mut ctx.accounts.schedule;
 
@@ -124,361 +187,2135 @@ mod coin98_vault {
     schedule.sending_token_account = sending_token_account;
     schedule.is_active = true;
     schedule.redemptions = vec![false; user_count.into()];
+    schedule.version = SCHEDULE_VERSION;
+    schedule.verify_user_token_owner = false;
+    schedule.user_count = user_count;
 
     Ok(())
   }
 
+  /// Creates a schedule of NFT redemptions. Its assets are only named by the merkle leaves, so
+  /// neither the mint allowlist nor the vault token accounts are checked at creation
   #[access_control(is_admin(&ctx.accounts.admin.key, &ctx.accounts.vault))]
-  pub fn set_schedule_status(
-    ctx: Context<SetScheduleContext>,
-    is_active: bool,
+  pub fn create_nft_schedule(
+    ctx: Context<CreateNftScheduleContext>,
+    user_count: u16,
+    event_id: u64,
+    timestamp: i64,
+    merkle_root: [u8; 32],
   ) -> Result<()> {
 
+    verify_payout_authority(ctx.accounts.admin.key, &ctx.accounts.vault, &ctx.accounts.vault_destinations, ctx.program_id)?;
+
+    let vault = &ctx.accounts.vault;
+
     let schedule = &mut ctx.accounts.schedule;
 
-    schedule.is_active = is_active;
+    schedule.obj_type = ObjType::DistributionNft;
+    schedule.nonce = ctx.bumps.schedule;
+    schedule.event_id = event_id;
+    schedule.vault_id = vault.key();
+    schedule.timestamp = timestamp;
+    schedule.merkle_root = merkle_root.try_to_vec().unwrap();
+    schedule.receiving_token_mint = SYSTEM_PROGRAM_ID;
+    schedule.receiving_token_account = SYSTEM_PROGRAM_ID;
+    schedule.sending_token_mint = SYSTEM_PROGRAM_ID;
+    schedule.sending_token_account = SYSTEM_PROGRAM_ID;
+    schedule.is_active = true;
+    schedule.redemptions = vec![false; user_count.into()];
+    schedule.version = SCHEDULE_VERSION;
+    schedule.verify_user_token_owner = false;
+    schedule.user_count = user_count;
 
     Ok(())
   }
 
   #[access_control(is_admin(&ctx.accounts.admin.key, &ctx.accounts.vault))]
-  pub fn withdraw_sol(
-    ctx: Context<WithdrawSolContext>,
-    amount: u64,
+  pub fn create_epoch_schedule(
+    ctx: Context<CreateEpochScheduleContext>,
+    event_id: u64,
+    receiving_token_mint: Pubkey,
+    receiving_token_account: Pubkey,
   ) -> Result<()> {
 
+    verify_payout_authority(ctx.accounts.admin.key, &ctx.accounts.vault, &ctx.accounts.vault_destinations, ctx.program_id)?;
+
     let vault = &ctx.accounts.vault;
-    let vault_signer = &ctx.accounts.vault_signer;
-    let recipient = &ctx.accounts.recipient;
+    let vault_allowlist = load_vault_allowlist(&ctx.accounts.vault_allowlist, &vault.key(), ctx.program_id)?;
+    verify_allowed_mint(&receiving_token_mint, &vault_allowlist)?;
 
-    let seeds: &[&[_]] = &[
-      &SIGNER_SEED_1,
-      vault.to_account_info().key.as_ref(),
-      &[vault.signer_nonce],
-    ];
-    transfer_lamport(
-        &vault_signer,
-        &recipient,
-        amount,
-        &[&seeds]
-      )
-      .expect("Coin98Vault: CPI failed.");
+    let schedule = &mut ctx.accounts.schedule;
+
+    schedule.obj_type = ObjType::DistributionEpoch;
+    schedule.nonce = ctx.bumps.schedule;
+    schedule.event_id = event_id;
+    schedule.vault_id = vault.key();
+    schedule.timestamp = 0;
+    schedule.merkle_root = [0u8; 32].try_to_vec().unwrap();
+    schedule.receiving_token_mint = receiving_token_mint;
+    schedule.receiving_token_account = receiving_token_account;
+    schedule.sending_token_mint = SYSTEM_PROGRAM_ID;
+    schedule.sending_token_account = SYSTEM_PROGRAM_ID;
+    schedule.is_active = true;
+    schedule.redemptions = Vec::new();
+    schedule.version = SCHEDULE_VERSION;
+    schedule.verify_user_token_owner = false;
+    schedule.user_count = 0;
+
+    let schedule_epoch = &mut ctx.accounts.schedule_epoch;
+
+    schedule_epoch.obj_type = ObjType::ScheduleEpoch;
+    schedule_epoch.nonce = ctx.bumps.schedule_epoch;
+    schedule_epoch.schedule_id = schedule.key();
+    schedule_epoch.epoch = 0;
 
     Ok(())
   }
 
+  /// Posts the merkle root of cumulative amounts for `epoch`, claimable from `timestamp`.
+  /// Replaces the root of the previous epoch
   #[access_control(is_admin(&ctx.accounts.admin.key, &ctx.accounts.vault))]
-  pub fn withdraw_token(
-    ctx: Context<WithdrawTokenContext>,
-    amount: u64,
+  pub fn set_schedule_epoch(
+    ctx: Context<SetScheduleEpochContext>,
+    epoch: u64,
+    timestamp: i64,
+    merkle_root: [u8; 32],
   ) -> Result<()> {
-    msg!("Coin98Vault: Instruction_WithdrawToken");
 
-    let vault = &ctx.accounts.vault;
-    let vault_signer = &ctx.accounts.vault_signer;
-    let sender = &ctx.accounts.sender;
-    let recipient = &ctx.accounts.recipient;
+    verify_payout_authority(ctx.accounts.admin.key, &ctx.accounts.vault, &ctx.accounts.vault_destinations, ctx.program_id)?;
 
-    let seeds: &[&[_]] = &[
-      &SIGNER_SEED_1,
-      vault.to_account_info().key.as_ref(),
-      &[vault.signer_nonce],
-    ];
-    transfer_token(
-        &vault_signer,
-        &sender,
-        &recipient,
-        amount,
-        &[&seeds]
-      )
-      .expect("Coin98Vault: CPI failed.");
+    let schedule = &mut ctx.accounts.schedule;
+    require!(schedule.obj_type == ObjType::DistributionEpoch, ErrorCode::InvalidInput);
+
+    let schedule_epoch = &mut ctx.accounts.schedule_epoch;
+    require!(epoch > schedule_epoch.epoch, ErrorCode::InvalidInput);
+
+    schedule_epoch.epoch = epoch;
+    schedule.timestamp = timestamp;
+    schedule.merkle_root = merkle_root.try_to_vec().unwrap();
 
     Ok(())
   }
 
-  #[access_control(verify_schedule(&ctx.accounts.schedule, ObjType::Distribution))]
-  pub fn redeem_token<'a>(
-    ctx: Context<'_, '_, '_, 'a, RedeemTokenContext<'a>>,
-    index: u16,
+  #[access_control(is_admin(&ctx.accounts.admin.key, &ctx.accounts.vault))]
+  pub fn create_voucher_schedule(
+    ctx: Context<CreateVoucherScheduleContext>,
+    event_id: u64,
+    nonce_count: u32,
     timestamp: i64,
-    proofs: Vec<[u8; 32]>,
-    receiving_amount: u64,
-    sending_amount: u64,
+    voucher_signer: Pubkey,
+    receiving_token_mint: Pubkey,
+    receiving_token_account: Pubkey,
   ) -> Result<()> {
-    msg!("Coin98Vault: Instruction_RedeemToken");
+    verify_payout_authority(ctx.accounts.admin.key, &ctx.accounts.vault, &ctx.accounts.vault_destinations, ctx.program_id)?;
+    require!(nonce_count > 0, ErrorCode::InvalidInput);
 
     let vault = &ctx.accounts.vault;
-    let vault_signer = &ctx.accounts.vault_signer;
-    let vault_token0 = &ctx.accounts.vault_token0;
-    let user_token0 = &ctx.accounts.user_token0;
-    let accounts = &ctx.remaining_accounts;
-    let user = &ctx.accounts.user;
-    let clock = Clock::get().unwrap();
+    let vault_allowlist = load_vault_allowlist(&ctx.accounts.vault_allowlist, &vault.key(), ctx.program_id)?;
+    verify_allowed_mint(&receiving_token_mint, &vault_allowlist)?;
 
     let schedule = &mut ctx.accounts.schedule;
-    if schedule.timestamp > 0 {
-      // older version of merkle node
-      require!(clock.unix_timestamp >= schedule.timestamp, ErrorCode::ScheduleLocked);
-      verify_proof(index, None, &ctx.accounts.user.key, receiving_amount, sending_amount, &proofs, &schedule)?;
-    } else {
-      // version 2 of merkle node
-      require!(clock.unix_timestamp >= timestamp, ErrorCode::ScheduleLocked);
-      verify_proof(index, Some(timestamp), &ctx.accounts.user.key, receiving_amount, sending_amount, &proofs, &schedule)?;
-    }
 
-    let user_index: usize = index.into();
-    schedule.redemptions[user_index] = true;
-
-    if schedule.sending_token_mint != solana_program::system_program::ID && sending_amount > 0 {
-      let vault_token1 = &accounts[0];
-      require_keys_eq!(*vault_token1.key, schedule.sending_token_account, ErrorCode::InvalidAccount);
-      let user_token1 = &accounts[1];
-      transfer_token(
-          &user,
-          &user_token1,
-          &vault_token1,
-          sending_amount,
-          &[]
-        )
-        .expect("Coin98Vault: CPI failed.");
-    }
+    schedule.obj_type = ObjType::DistributionVoucher;
+    schedule.nonce = ctx.bumps.schedule;
+    schedule.event_id = event_id;
+    schedule.vault_id = vault.key();
+    schedule.timestamp = timestamp;
+    schedule.merkle_root = [0u8; 32].try_to_vec().unwrap();
+    schedule.receiving_token_mint = receiving_token_mint;
+    schedule.receiving_token_account = receiving_token_account;
+    schedule.sending_token_mint = SYSTEM_PROGRAM_ID;
+    schedule.sending_token_account = SYSTEM_PROGRAM_ID;
+    schedule.is_active = true;
+    schedule.redemptions = Vec::new();
+    schedule.version = SCHEDULE_VERSION;
+    schedule.verify_user_token_owner = false;
+    schedule.user_count = 0;
 
-    let seeds: &[&[_]] = &[
-      &SIGNER_SEED_1,
-      vault.to_account_info().key.as_ref(),
-      &[vault.signer_nonce],
-    ];
+    let schedule_voucher = &mut ctx.accounts.schedule_voucher;
 
-    if schedule.receiving_token_mint == SYSTEM_PROGRAM_ID {
-      transfer_lamport(
-        &vault_signer,
-        &user,
-        receiving_amount,
-        &[&seeds]
-      ).expect("Coin98Vault: CPI failed.");
-    } else {
-      transfer_token(
-        &vault_signer,
-        &vault_token0,
-        &user_token0,
-        receiving_amount,
-        &[&seeds]
-      ).expect("Coin98Vault: CPI failed.");
-    }
+    schedule_voucher.obj_type = ObjType::ScheduleVoucher;
+    schedule_voucher.nonce = ctx.bumps.schedule_voucher;
+    schedule_voucher.schedule_id = schedule.key();
+    schedule_voucher.voucher_signer = voucher_signer;
+    schedule_voucher.nonces = vec![0; ScheduleVoucher::bitmap_len(nonce_count)];
 
     Ok(())
   }
 
-  #[access_control(verify_schedule(&ctx.accounts.schedule, ObjType::DistributionMulti))]
-  pub fn redeem_token_multi<'a>(
-    ctx: Context<'_, '_, '_, 'a, RedeemTokenMultiContext<'a>>,
-    index: u16,
-    timestamp: i64,
-    proofs: Vec<[u8; 32]>,
-    receiving_token_mint: Pubkey,
-    receiving_amount: u64,
-    sending_amount: u64,
+  #[access_control(is_admin(&ctx.accounts.admin.key, &ctx.accounts.vault))]
+  pub fn set_schedule_status(
+    ctx: Context<SetScheduleContext>,
+    is_active: bool,
   ) -> Result<()> {
-    let clock = Clock::get().unwrap();
-
-    let vault = &ctx.accounts.vault;
-    let vault_signer = &ctx.accounts.vault_signer;
-    let accounts = &ctx.remaining_accounts;
-    let user = &ctx.accounts.user;
 
     let schedule = &mut ctx.accounts.schedule;
 
-    if schedule.timestamp > 0 {
-      require!(clock.unix_timestamp >= schedule.timestamp, ErrorCode::ScheduleLocked);
-      verify_proof_multi(index, None, &ctx.accounts.user.key, receiving_token_mint, receiving_amount, sending_amount, &proofs, schedule)?;
-    } else {
-      require!(clock.unix_timestamp >= timestamp, ErrorCode::ScheduleLocked);
-      verify_proof_multi(index, Some(timestamp), &ctx.accounts.user.key, receiving_token_mint, receiving_amount, sending_amount, &proofs, schedule)?;
-    }
+    schedule.is_active = is_active;
 
-    let user_index: usize = index.into();
-    schedule.redemptions[user_index] = true;
+    Ok(())
+  }
 
-    if schedule.sending_token_mint != solana_program::system_program::ID && sending_amount > 0 {
-      let vault_token1 = &accounts[0];
-      require_keys_eq!(*vault_token1.key, schedule.sending_token_account, ErrorCode::InvalidAccount);
-      let user_token1 = &accounts[1];
-      transfer_token(
-          &user,
-          &user_token1,
-          &vault_token1,
-          sending_amount,
-          &[]
-        )
-        .expect("Coin98Vault: CPI failed.");
-    }
+  /// Sets whether `redeem_token` and `redeem_token_multi` require `user_token0` to be owned
+  /// by the claiming user. Schedules created before `version` must be migrated first
+  #[access_control(is_admin(&ctx.accounts.admin.key, &ctx.accounts.vault))]
+  pub fn set_schedule_owner_check(
+    ctx: Context<SetScheduleContext>,
+    verify_user_token_owner: bool,
+  ) -> Result<()> {
 
-    let seeds: &[&[_]] = &[
-      &SIGNER_SEED_1,
-      vault.to_account_info().key.as_ref(),
-      &[vault.signer_nonce],
-    ];
+    let schedule = &mut ctx.accounts.schedule;
+    require!(schedule.version == SCHEDULE_VERSION, ErrorCode::InvalidInput);
 
-    if receiving_token_mint == SYSTEM_PROGRAM_ID {
-      transfer_lamport(
-        &vault_signer,
-        &user,
-        receiving_amount,
-        &[&seeds]
-      ).expect("Coin98Vault: CPI failed.");
-    } else {
-      let vault_token0 = &ctx.accounts.vault_token0;
-      let vault_token0_account = TokenAccount::unpack_from_slice(&vault_token0.try_borrow_data().unwrap()).unwrap();
-      let user_token0 = &ctx.accounts.user_token0;
-      let user_token0_account = TokenAccount::unpack_from_slice(&user_token0.try_borrow_data().unwrap()).unwrap();
+    schedule.verify_user_token_owner = verify_user_token_owner;
 
-      require_keys_eq!(vault_token0_account.mint, receiving_token_mint, ErrorCode::InvalidAccount);
-      require_keys_eq!(user_token0_account.mint, receiving_token_mint, ErrorCode::InvalidAccount);
+    Ok(())
+  }
 
-      transfer_token(
-        &vault_signer,
-        &vault_token0,
-        &user_token0,
-        receiving_amount,
-        &[&seeds]
-      ).expect("Coin98Vault: CPI failed.");
-    }
+  /// Creates a token distribution whose redemptions are kept in a bitmap after a fixed header,
+  /// so redeeming touches a single byte however large `user_count` is. Mint-on-claim schedules
+  /// and tranches are not supported
+  #[allow(clippy::too_many_arguments)]
+  #[access_control(is_admin(&ctx.accounts.admin.key, &ctx.accounts.vault))]
+  pub fn create_bitmap_schedule(
+    ctx: Context<CreateBitmapScheduleContext>,
+    user_count: u16,
+    event_id: u64,
+    timestamp: i64,
+    merkle_root: [u8; 32],
+    receiving_token_mint: Pubkey,
+    receiving_token_account: Pubkey,
+    sending_token_mint: Pubkey,
+    sending_token_account: Pubkey,
+  ) -> Result<()> {
+    verify_payout_authority(ctx.accounts.admin.key, &ctx.accounts.vault, &ctx.accounts.vault_destinations, ctx.program_id)?;
+    require!(user_count > 0, ErrorCode::InvalidInput);
+
+    let vault = &ctx.accounts.vault;
+    let vault_allowlist = load_vault_allowlist(&ctx.accounts.vault_allowlist, &vault.key(), ctx.program_id)?;
+    verify_allowed_mint(&receiving_token_mint, &vault_allowlist)?;
+    verify_allowed_mint(&sending_token_mint, &vault_allowlist)?;
+
+    let mut schedule = ctx.accounts.schedule.load_init()?;
+
+    schedule.obj_type = ObjType::DistributionBitmap as u8;
+    schedule.nonce = ctx.bumps.schedule;
+    schedule.event_id = event_id;
+    schedule.vault_id = vault.key();
+    schedule.timestamp = timestamp;
+    schedule.merkle_root = merkle_root;
+    schedule.receiving_token_mint = receiving_token_mint;
+    schedule.receiving_token_account = receiving_token_account;
+    schedule.sending_token_mint = sending_token_mint;
+    schedule.sending_token_account = sending_token_account;
+    schedule.user_count = user_count;
+    schedule.is_active = 1;
+    schedule.version = SCHEDULE_VERSION;
+    schedule.verify_user_token_owner = 0;
 
     Ok(())
   }
 
-  #[access_control(is_owner(&ctx.accounts.owner.key, &ctx.accounts.vault))]
-  pub fn transfer_ownership(
-    ctx: Context<TransferOwnershipContext>,
-    new_owner: Pubkey,
+  #[access_control(is_admin(&ctx.accounts.admin.key, &ctx.accounts.vault))]
+  pub fn set_bitmap_schedule_status(
+    ctx: Context<SetBitmapScheduleContext>,
+    is_active: bool,
   ) -> Result<()> {
 
-    let vault = &mut ctx.accounts.vault;
+    let mut schedule = ctx.accounts.schedule.load_mut()?;
 
-    vault.new_owner = new_owner;
+    schedule.is_active = u8::from(is_active);
 
     Ok(())
   }
 
-  #[access_control(verify_new_owner(&ctx.accounts.new_owner.key, &ctx.accounts.vault))]
-  pub fn accept_ownership(
-    ctx: Context<AcceptOwnershipContext>,
+  /// Sets whether `redeem_token_bitmap` requires `user_token0` to be owned by the claiming user
+  #[access_control(is_admin(&ctx.accounts.admin.key, &ctx.accounts.vault))]
+  pub fn set_bitmap_schedule_owner_check(
+    ctx: Context<SetBitmapScheduleContext>,
+    verify_user_token_owner: bool,
   ) -> Result<()> {
 
-    let vault = &mut ctx.accounts.vault;
+    let mut schedule = ctx.accounts.schedule.load_mut()?;
 
-    vault.owner = vault.new_owner;
-    vault.new_owner = anchor_lang::system_program::ID; // Set to empty
+    schedule.verify_user_token_owner = u8::from(verify_user_token_owner);
 
     Ok(())
   }
-}
 
-/// Returns true if the user has root priviledge of the vault
-pub fn is_owner(user: &Pubkey, vault: &Vault) -> Result<()> {
+  /// Appends `user_count` redemptions to the schedule, claimable with proofs of
+  /// `merkle_root` from `timestamp`
+  #[access_control(is_admin(&ctx.accounts.admin.key, &ctx.accounts.vault))]
+  pub fn append_schedule_tranche(
+    ctx: Context<AppendScheduleTrancheContext>,
+    user_count: u16,
+    timestamp: i64,
+    merkle_root: [u8; 32],
+  ) -> Result<()> {
+    verify_payout_authority(ctx.accounts.admin.key, &ctx.accounts.vault, &ctx.accounts.vault_destinations, ctx.program_id)?;
+    require!(user_count > 0, ErrorCode::InvalidInput);
 
-  require_keys_eq!(*user, vault.owner, ErrorCode::Unauthorized);
+    let schedule = &mut ctx.accounts.schedule;
+    require!(
+      schedule.obj_type == ObjType::Distribution || schedule.obj_type == ObjType::DistributionMulti,
+      ErrorCode::InvalidInput
+    );
+    // tranches are told apart from the schedule's own redemptions by user_count
+    require!(schedule.version == SCHEDULE_VERSION, ErrorCode::InvalidInput);
 
-  Ok(())
-}
+    let index_start = schedule.redemptions.len();
+    let index_end = index_start + usize::from(user_count);
+    let index_start = u16::try_from(index_start).map_err(|_| ErrorCode::InvalidInput)?;
+    let index_end = u16::try_from(index_end).map_err(|_| ErrorCode::InvalidInput)?;
 
-/// Returns true if the user is the newly apppointed owner of the vault
-pub fn verify_new_owner(user: &Pubkey, vault: &Vault) -> Result<()> {
+    let schedule_tranche = &mut ctx.accounts.schedule_tranche;
+    require!(schedule_tranche.tranches.len() < TRANCHES_MAX, ErrorCode::InvalidInput);
 
-  require_keys_eq!(*user, vault.new_owner, ErrorCode::Unauthorized);
+    schedule_tranche.obj_type = ObjType::ScheduleTranche;
+    schedule_tranche.nonce = ctx.bumps.schedule_tranche;
+    schedule_tranche.schedule_id = schedule.key();
+    schedule_tranche.tranches.push(Tranche {
+      merkle_root,
+      index_start,
+      index_end,
+      timestamp,
+    });
 
-  Ok(())
-}
+    schedule.redemptions.resize(usize::from(index_end), false);
 
-/// Returns true if the user is an admin of a specified vault
-pub fn is_admin(user: &Pubkey, vault: &Vault) -> Result<()> {
-  if *user == vault.owner {
-   return Ok(());
+    Ok(())
   }
 
-  let result = vault.admins.iter().position(|&key| key == *user);
-  if result == None {
-    return Err(ErrorCode::Unauthorized.into());
-  }
+  #[access_control(is_admin(&ctx.accounts.admin.key, &ctx.accounts.vault))]
+  pub fn set_schedule_mint(
+    ctx: Context<SetScheduleMintContext>,
+    mint_cap: u64,
+  ) -> Result<()> {
 
-  Ok(())
-}
+    let schedule = &ctx.accounts.schedule;
+    require!(schedule.receiving_token_account == schedule.receiving_token_mint, ErrorCode::InvalidInput);
 
-pub fn verify_schedule(schedule: &Schedule, expected_type: ObjType) -> Result<()> {
+    let schedule_mint = &mut ctx.accounts.schedule_mint;
+    require!(mint_cap >= schedule_mint.minted_amount, ErrorCode::InvalidInput);
+
+    schedule_mint.obj_type = ObjType::ScheduleMint;
+    schedule_mint.nonce = ctx.bumps.schedule_mint;
+    schedule_mint.schedule_id = schedule.key();
+    schedule_mint.mint_cap = mint_cap;
+
+    Ok(())
+  }
+
+  #[access_control(is_admin(&ctx.accounts.admin.key, &ctx.accounts.vault))]
+  pub fn set_schedule_stake(
+    ctx: Context<SetScheduleStakeContext>,
+    stake_program: Pubkey,
+    stake_accounts: Vec<Pubkey>,
+  ) -> Result<()> {
+    verify_payout_authority(ctx.accounts.admin.key, &ctx.accounts.vault, &ctx.accounts.vault_destinations, ctx.program_id)?;
+    require!(stake_accounts.len() <= STAKE_ACCOUNTS_MAX, ErrorCode::InvalidInput);
+
+    let schedule = &ctx.accounts.schedule;
+
+    let schedule_stake = &mut ctx.accounts.schedule_stake;
+
+    schedule_stake.obj_type = ObjType::ScheduleStake;
+    schedule_stake.nonce = ctx.bumps.schedule_stake;
+    schedule_stake.schedule_id = schedule.key();
+    schedule_stake.stake_program = stake_program;
+    schedule_stake.stake_accounts = stake_accounts;
+
+    Ok(())
+  }
+
+  #[access_control(is_owner(&ctx.accounts.owner.key, &ctx.accounts.vault))]
+  pub fn set_sol_reserve(
+    ctx: Context<SetSolReserveContext>,
+    sol_reserve: u64,
+  ) -> Result<()> {
+
+    let vault = &ctx.accounts.vault;
+
+    let vault_reserve = &mut ctx.accounts.vault_reserve;
+
+    vault_reserve.obj_type = ObjType::VaultReserve;
+    vault_reserve.nonce = ctx.bumps.vault_reserve;
+    vault_reserve.vault_id = vault.key();
+    vault_reserve.sol_reserve = sol_reserve;
+
+    Ok(())
+  }
+
+  /// Sets the SOL owed to users by SOL schedules. Admins can only raise it, lowering it below
+  /// what redemptions have not settled yet frees SOL for `withdraw_sol` and is left to the owner
+  #[access_control(is_admin(&ctx.accounts.admin.key, &ctx.accounts.vault))]
+  pub fn set_sol_liabilities(
+    ctx: Context<SetSolLiabilitiesContext>,
+    sol_liabilities: u64,
+  ) -> Result<()> {
+
+    let vault = &ctx.accounts.vault;
+
+    let vault_reserve = &mut ctx.accounts.vault_reserve;
+    if sol_liabilities < vault_reserve.sol_liabilities {
+      is_owner(ctx.accounts.admin.key, vault)?;
+    }
+
+    vault_reserve.obj_type = ObjType::VaultReserve;
+    vault_reserve.nonce = ctx.bumps.vault_reserve;
+    vault_reserve.vault_id = vault.key();
+    vault_reserve.sol_liabilities = sol_liabilities;
+
+    Ok(())
+  }
+
+  #[access_control(is_owner(&ctx.accounts.owner.key, &ctx.accounts.vault))]
+  pub fn set_claim_fee(
+    ctx: Context<SetClaimFeeContext>,
+    treasury: Pubkey,
+    fee_lamports: u64,
+    fee_bps: u16,
+  ) -> Result<()> {
+    require!(fee_bps <= BPS_DENOMINATOR, ErrorCode::InvalidFee);
+
+    let vault = &ctx.accounts.vault;
+
+    let vault_fee = &mut ctx.accounts.vault_fee;
+
+    vault_fee.obj_type = ObjType::VaultFee;
+    vault_fee.nonce = ctx.bumps.vault_fee;
+    vault_fee.vault_id = vault.key();
+    vault_fee.treasury = treasury;
+    vault_fee.fee_lamports = fee_lamports;
+    vault_fee.fee_bps = fee_bps;
+
+    Ok(())
+  }
+
+  #[access_control(is_owner(&ctx.accounts.owner.key, &ctx.accounts.vault))]
+  pub fn set_swap_program(
+    ctx: Context<SetSwapProgramContext>,
+    swap_program: Pubkey,
+  ) -> Result<()> {
+
+    let vault = &ctx.accounts.vault;
+
+    let vault_swap = &mut ctx.accounts.vault_swap;
+
+    vault_swap.obj_type = ObjType::VaultSwap;
+    vault_swap.nonce = ctx.bumps.vault_swap;
+    vault_swap.vault_id = vault.key();
+    vault_swap.swap_program = swap_program;
+
+    Ok(())
+  }
+
+  /// Restricts the tokens the vault deposits, distributes and withdraws to `mints`, or lifts
+  /// the restriction when empty. Native SOL is always allowed, wrapped SOL must be listed
+  #[access_control(is_owner(&ctx.accounts.owner.key, &ctx.accounts.vault))]
+  pub fn set_allowed_mints(
+    ctx: Context<SetAllowedMintsContext>,
+    mints: Vec<Pubkey>,
+  ) -> Result<()> {
+    require!(mints.len() <= ALLOWED_MINTS_MAX, ErrorCode::InvalidInput);
+
+    let vault = &ctx.accounts.vault;
+
+    let vault_allowlist = &mut ctx.accounts.vault_allowlist;
+
+    vault_allowlist.obj_type = ObjType::VaultAllowlist;
+    vault_allowlist.nonce = ctx.bumps.vault_allowlist;
+    vault_allowlist.vault_id = vault.key();
+    vault_allowlist.mints = mints;
+
+    Ok(())
+  }
+
+  /// Approves `destination` as a recipient of `withdraw_sol` and `withdraw_token`, from
+  /// `DESTINATION_TIMELOCK` seconds later. Once a destination is added, withdrawals to any
+  /// other recipient fail, and only the owner can create schedules or change who they pay
+  #[access_control(is_owner(&ctx.accounts.owner.key, &ctx.accounts.vault))]
+  pub fn add_withdrawal_destination(
+    ctx: Context<AddWithdrawalDestinationContext>,
+    destination: Pubkey,
+  ) -> Result<()> {
+    let clock = Clock::get().unwrap();
+
+    let vault = &ctx.accounts.vault;
+
+    let vault_destinations = &mut ctx.accounts.vault_destinations;
+    require!(vault_destinations.destinations.len() < DESTINATIONS_MAX, ErrorCode::InvalidInput);
+    require!(
+      vault_destinations.destinations.iter().all(|item| item.address != destination),
+      ErrorCode::InvalidInput
+    );
+
+    vault_destinations.obj_type = ObjType::VaultDestinations;
+    vault_destinations.nonce = ctx.bumps.vault_destinations;
+    vault_destinations.vault_id = vault.key();
+    vault_destinations.destinations.push(WithdrawalDestination {
+      address: destination,
+      timestamp: clock.unix_timestamp + DESTINATION_TIMELOCK,
+    });
+
+    Ok(())
+  }
+
+  /// Removes `destination`, pending or not, with immediate effect. Removing the last one does
+  /// not lift the restriction, withdrawals fail until a destination is added again and
+  /// schedules stay owner-only
+  #[access_control(is_owner(&ctx.accounts.owner.key, &ctx.accounts.vault))]
+  pub fn remove_withdrawal_destination(
+    ctx: Context<RemoveWithdrawalDestinationContext>,
+    destination: Pubkey,
+  ) -> Result<()> {
+
+    let vault_destinations = &mut ctx.accounts.vault_destinations;
+    let count = vault_destinations.destinations.len();
+    vault_destinations.destinations.retain(|item| item.address != destination);
+    require!(vault_destinations.destinations.len() < count, ErrorCode::InvalidInput);
+
+    Ok(())
+  }
+
+  pub fn get_withdrawable_sol(
+    ctx: Context<GetWithdrawableSolContext>,
+  ) -> Result<u64> {
+
+    let vault = &ctx.accounts.vault;
+    let vault_signer = &ctx.accounts.vault_signer;
+
+    let vault_reserve = load_vault_reserve(&ctx.accounts.vault_reserve, &vault.key(), ctx.program_id)?;
+
+    withdrawable_sol(vault_signer, &vault_reserve)
+  }
+
+  pub fn deposit_sol(
+    ctx: Context<DepositSolContext>,
+    amount: u64,
+  ) -> Result<()> {
+    msg!("Coin98Vault: Instruction_DepositSol");
+
+    let payer = &ctx.accounts.payer;
+    let vault = &ctx.accounts.vault;
+    let vault_signer = &ctx.accounts.vault_signer;
+    let vault_token = &ctx.accounts.vault_token;
+
+    let vault_allowlist = load_vault_allowlist(&ctx.accounts.vault_allowlist, &vault.key(), ctx.program_id)?;
+    verify_allowed_mint(&NATIVE_MINT_ID, &vault_allowlist)?;
+    verify_token_account(vault_token, &NATIVE_MINT_ID, Some(vault_signer.key))?;
+
+    transfer_lamport(
+        payer,
+        vault_token,
+        amount,
+        &[]
+      )
+      .expect("Coin98Vault: CPI failed.");
+    sync_native(
+        vault_token,
+      )
+      .expect("Coin98Vault: CPI failed.");
+
+    Ok(())
+  }
+
+  #[access_control(is_admin(&ctx.accounts.admin.key, &ctx.accounts.vault))]
+  pub fn withdraw_sol(
+    ctx: Context<WithdrawSolContext>,
+    amount: u64,
+  ) -> Result<()> {
+
+    let vault = &ctx.accounts.vault;
+    let vault_signer = &ctx.accounts.vault_signer;
+    let recipient = &ctx.accounts.recipient;
+
+    let vault_destinations = load_vault_destinations(&ctx.accounts.vault_destinations, &vault.key(), ctx.program_id)?;
+    verify_withdrawal_destination(recipient.key, &vault_destinations)?;
+
+    let vault_reserve = load_vault_reserve(&ctx.accounts.vault_reserve, &vault.key(), ctx.program_id)?;
+    require!(amount <= withdrawable_sol(vault_signer, &vault_reserve)?, ErrorCode::InsufficientReserve);
+
+    let seeds: &[&[_]] = &[
+      SIGNER_SEED_1,
+      vault.to_account_info().key.as_ref(),
+      &[vault.signer_nonce],
+    ];
+    transfer_lamport(
+        vault_signer,
+        recipient,
+        amount,
+        &[seeds]
+      )
+      .expect("Coin98Vault: CPI failed.");
+
+    Ok(())
+  }
+
+  #[access_control(is_admin(&ctx.accounts.admin.key, &ctx.accounts.vault))]
+  pub fn withdraw_token(
+    ctx: Context<WithdrawTokenContext>,
+    amount: u64,
+  ) -> Result<()> {
+    msg!("Coin98Vault: Instruction_WithdrawToken");
+
+    let vault = &ctx.accounts.vault;
+    let vault_signer = &ctx.accounts.vault_signer;
+    let sender = &ctx.accounts.sender;
+    let recipient = &ctx.accounts.recipient;
+
+    require_keys_eq!(*sender.owner, TOKEN_PROGRAM_ID, ErrorCode::InvalidTokenAccount);
+    let sender_token = TokenAccount::unpack_from_slice(&sender.try_borrow_data()?)
+      .map_err(|_| ErrorCode::InvalidTokenAccount)?;
+    let vault_allowlist = load_vault_allowlist(&ctx.accounts.vault_allowlist, &vault.key(), ctx.program_id)?;
+    verify_allowed_mint(&sender_token.mint, &vault_allowlist)?;
+    let vault_destinations = load_vault_destinations(&ctx.accounts.vault_destinations, &vault.key(), ctx.program_id)?;
+    if vault_destinations.is_some() {
+      let recipient_token = verify_token_account(recipient, &sender_token.mint, None)?;
+      verify_withdrawal_destination(&recipient_token.owner, &vault_destinations)?;
+    }
+
+    let seeds: &[&[_]] = &[
+      SIGNER_SEED_1,
+      vault.to_account_info().key.as_ref(),
+      &[vault.signer_nonce],
+    ];
+    transfer_token(
+        vault_signer,
+        sender,
+        recipient,
+        amount,
+        &[seeds]
+      )
+      .expect("Coin98Vault: CPI failed.");
+
+    Ok(())
+  }
+
+  #[access_control(verify_schedule(&ctx.accounts.schedule, ObjType::Distribution))]
+  pub fn redeem_token<'a>(
+    ctx: Context<'_, '_, '_, 'a, RedeemTokenContext<'a>>,
+    index: u16,
+    timestamp: i64,
+    proofs: Vec<[u8; 32]>,
+    receiving_amount: u64,
+    sending_amount: u64,
+  ) -> Result<()> {
+    msg!("Coin98Vault: Instruction_RedeemToken");
+
+    let vault = &ctx.accounts.vault;
+    let vault_signer = &ctx.accounts.vault_signer;
+    let vault_token0 = &ctx.accounts.vault_token0;
+    let user_token0 = &ctx.accounts.user_token0;
+    let user = &ctx.accounts.user;
+    let clock = Clock::get().unwrap();
+
+    let schedule = &mut ctx.accounts.schedule;
+    let tranche = find_tranche(&ctx.accounts.schedule_tranche, schedule, index)?;
+    if let Some(tranche) = tranche {
+      require!(clock.unix_timestamp >= tranche.timestamp, ErrorCode::ScheduleLocked);
+    }
+    if schedule.timestamp > 0 {
+      // older version of merkle node
+      require!(clock.unix_timestamp >= schedule.timestamp, ErrorCode::ScheduleLocked);
+      verify_proof(index, &redemption_data(index, None, ctx.accounts.user.key, receiving_amount, sending_amount), &proofs, schedule, tranche)?;
+    } else {
+      // version 2 of merkle node
+      require!(clock.unix_timestamp >= timestamp, ErrorCode::ScheduleLocked);
+      verify_proof(index, &redemption_data(index, Some(timestamp), ctx.accounts.user.key, receiving_amount, sending_amount), &proofs, schedule, tranche)?;
+    }
+
+    let user_index: usize = index.into();
+    schedule.redemptions[user_index] = true;
+
+    if schedule.sending_token_mint != solana_program::system_program::ID && sending_amount > 0 {
+      let vault_token1 = ctx.accounts.vault_token1.as_ref().ok_or(ErrorCode::InvalidAccount)?;
+      require_keys_eq!(*vault_token1.key, schedule.sending_token_account, ErrorCode::InvalidAccount);
+      let user_token1 = ctx.accounts.user_token1.as_ref().ok_or(ErrorCode::InvalidAccount)?;
+      verify_token_account(user_token1, &schedule.sending_token_mint, None)?;
+      transfer_token(
+          user,
+          user_token1,
+          vault_token1,
+          sending_amount,
+          &[]
+        )
+        .expect("Coin98Vault: CPI failed.");
+    }
+
+    let vault_fee = load_vault_fee(&ctx.accounts.vault_fee, &vault.key(), ctx.program_id)?;
+    pay_claim_fee_lamports(user, &ctx.accounts.treasury, &vault_fee)?;
+    let treasury = vault_fee.as_ref().map_or(SYSTEM_PROGRAM_ID, |fee| fee.treasury);
+    let fee_amount = vault_fee.as_ref().map_or(0, |fee| fee.token_fee(receiving_amount));
+    let user_amount = receiving_amount - fee_amount;
+
+    let seeds: &[&[_]] = &[
+      SIGNER_SEED_1,
+      vault.to_account_info().key.as_ref(),
+      &[vault.signer_nonce],
+    ];
+
+    if schedule.receiving_token_mint == SYSTEM_PROGRAM_ID {
+      let vault_reserve_info = &ctx.accounts.vault_reserve;
+      let vault_reserve = load_vault_reserve(vault_reserve_info, &vault.key(), ctx.program_id)?;
+      verify_sol_reserve(vault_signer, receiving_amount, &vault_reserve)?;
+
+      transfer_lamport(
+        vault_signer,
+        user,
+        user_amount,
+        &[seeds]
+      ).expect("Coin98Vault: CPI failed.");
+
+      if fee_amount > 0 {
+        let treasury = verify_treasury(&ctx.accounts.treasury, &treasury)?;
+        transfer_lamport(
+          vault_signer,
+          treasury,
+          fee_amount,
+          &[seeds]
+        ).expect("Coin98Vault: CPI failed.");
+      }
+
+      settle_sol_liabilities(vault_reserve_info, vault_reserve, receiving_amount)?;
+    } else {
+      if let Some(associated_token_program) = &ctx.accounts.associated_token_program {
+        create_user_token_account(
+          user,
+          user_token0,
+          &schedule.receiving_token_mint,
+          associated_token_program,
+          &ctx.accounts.user_token0_mint,
+          &ctx.accounts.payer,
+          &ctx.accounts.system_program,
+          &ctx.accounts.token_program,
+        )?;
+      }
+      // wrapped SOL is unwrapped by closing user_token0, which only its owner can authorize
+      let verify_owner = schedule.verify_user_token_owner || schedule.receiving_token_mint == NATIVE_MINT_ID;
+      verify_token_account(user_token0, &schedule.receiving_token_mint, verify_owner.then_some(user.key))?;
+
+      if schedule.receiving_token_account == schedule.receiving_token_mint {
+        // mint-on-claim schedule, vault_token0 is the mint and the vault signer its authority
+        let schedule_mint = ctx.accounts.schedule_mint.as_mut().ok_or(ErrorCode::InvalidAccount)?;
+        let mint_cap = schedule_mint.mint_cap;
+        schedule_mint.minted_amount = schedule_mint.minted_amount
+          .checked_add(receiving_amount)
+          .filter(|minted_amount| *minted_amount <= mint_cap)
+          .ok_or(ErrorCode::MintCapExceeded)?;
+
+        mint_to(
+          vault_signer,
+          vault_token0,
+          user_token0,
+          user_amount,
+          &[seeds]
+        ).expect("Coin98Vault: CPI failed.");
+
+        if fee_amount > 0 {
+          let treasury_token = ctx.accounts.treasury_token.as_ref().ok_or(ErrorCode::InvalidAccount)?;
+          verify_token_account(treasury_token, &schedule.receiving_token_mint, Some(&treasury))?;
+          mint_to(
+            vault_signer,
+            vault_token0,
+            treasury_token,
+            fee_amount,
+            &[seeds]
+          ).expect("Coin98Vault: CPI failed.");
+        }
+      } else {
+        transfer_token(
+          vault_signer,
+          vault_token0,
+          user_token0,
+          user_amount,
+          &[seeds]
+        ).expect("Coin98Vault: CPI failed.");
+
+        if fee_amount > 0 {
+          let treasury_token = ctx.accounts.treasury_token.as_ref().ok_or(ErrorCode::InvalidAccount)?;
+          verify_token_account(treasury_token, &schedule.receiving_token_mint, Some(&treasury))?;
+          transfer_token(
+            vault_signer,
+            vault_token0,
+            treasury_token,
+            fee_amount,
+            &[seeds]
+          ).expect("Coin98Vault: CPI failed.");
+        }
+      }
+
+      if schedule.receiving_token_mint == NATIVE_MINT_ID {
+        // unwrap SOL into the user's wallet
+        close_account(
+          user,
+          user_token0,
+          user,
+          &[]
+        ).expect("Coin98Vault: CPI failed.");
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Same as `redeem_token` for schedules created with `create_bitmap_schedule`, without
+  /// tranches or mint-on-claim
+  pub fn redeem_token_bitmap<'a>(
+    ctx: Context<'_, '_, '_, 'a, RedeemTokenBitmapContext<'a>>,
+    index: u16,
+    timestamp: i64,
+    proofs: Vec<[u8; 32]>,
+    receiving_amount: u64,
+    sending_amount: u64,
+  ) -> Result<()> {
+    msg!("Coin98Vault: Instruction_RedeemTokenBitmap");
+
+    let vault = &ctx.accounts.vault;
+    let vault_signer = &ctx.accounts.vault_signer;
+    let vault_token0 = &ctx.accounts.vault_token0;
+    let user_token0 = &ctx.accounts.user_token0;
+    let user = &ctx.accounts.user;
+    let clock = Clock::get().unwrap();
+
+    // the header is copied out, the bitmap is then borrowed from the account data
+    let schedule = *ctx.accounts.schedule.load()?;
+    require!(schedule.obj_type == ObjType::DistributionBitmap as u8, ErrorCode::InvalidAccount);
+    require!(schedule.is_active != 0, ErrorCode::ScheduleUnavailable);
+    {
+      let mut data = ctx.accounts.schedule.as_ref().try_borrow_mut_data()?;
+      let bitmap = &mut data[8 + BitmapSchedule::LEN..];
+      if schedule.timestamp > 0 {
+        // older version of merkle node
+        require!(clock.unix_timestamp >= schedule.timestamp, ErrorCode::ScheduleLocked);
+        verify_proof_bitmap(index, &redemption_data(index, None, ctx.accounts.user.key, receiving_amount, sending_amount), &proofs, &schedule, bitmap)?;
+      } else {
+        // version 2 of merkle node
+        require!(clock.unix_timestamp >= timestamp, ErrorCode::ScheduleLocked);
+        verify_proof_bitmap(index, &redemption_data(index, Some(timestamp), ctx.accounts.user.key, receiving_amount, sending_amount), &proofs, &schedule, bitmap)?;
+      }
+      BitmapSchedule::set_redeemed(bitmap, index);
+    }
+
+    if schedule.sending_token_mint != solana_program::system_program::ID && sending_amount > 0 {
+      let vault_token1 = ctx.accounts.vault_token1.as_ref().ok_or(ErrorCode::InvalidAccount)?;
+      require_keys_eq!(*vault_token1.key, schedule.sending_token_account, ErrorCode::InvalidAccount);
+      let user_token1 = ctx.accounts.user_token1.as_ref().ok_or(ErrorCode::InvalidAccount)?;
+      verify_token_account(user_token1, &schedule.sending_token_mint, None)?;
+      transfer_token(
+          user,
+          user_token1,
+          vault_token1,
+          sending_amount,
+          &[]
+        )
+        .expect("Coin98Vault: CPI failed.");
+    }
+
+    let vault_fee = load_vault_fee(&ctx.accounts.vault_fee, &vault.key(), ctx.program_id)?;
+    pay_claim_fee_lamports(user, &ctx.accounts.treasury, &vault_fee)?;
+    let treasury = vault_fee.as_ref().map_or(SYSTEM_PROGRAM_ID, |fee| fee.treasury);
+    let fee_amount = vault_fee.as_ref().map_or(0, |fee| fee.token_fee(receiving_amount));
+    let user_amount = receiving_amount - fee_amount;
+
+    let seeds: &[&[_]] = &[
+      SIGNER_SEED_1,
+      vault.to_account_info().key.as_ref(),
+      &[vault.signer_nonce],
+    ];
+
+    if schedule.receiving_token_mint == SYSTEM_PROGRAM_ID {
+      let vault_reserve_info = &ctx.accounts.vault_reserve;
+      let vault_reserve = load_vault_reserve(vault_reserve_info, &vault.key(), ctx.program_id)?;
+      verify_sol_reserve(vault_signer, receiving_amount, &vault_reserve)?;
+
+      transfer_lamport(
+        vault_signer,
+        user,
+        user_amount,
+        &[seeds]
+      ).expect("Coin98Vault: CPI failed.");
+
+      if fee_amount > 0 {
+        let treasury = verify_treasury(&ctx.accounts.treasury, &treasury)?;
+        transfer_lamport(
+          vault_signer,
+          treasury,
+          fee_amount,
+          &[seeds]
+        ).expect("Coin98Vault: CPI failed.");
+      }
+
+      settle_sol_liabilities(vault_reserve_info, vault_reserve, receiving_amount)?;
+    } else {
+      if let Some(associated_token_program) = &ctx.accounts.associated_token_program {
+        create_user_token_account(
+          user,
+          user_token0,
+          &schedule.receiving_token_mint,
+          associated_token_program,
+          &ctx.accounts.user_token0_mint,
+          &ctx.accounts.payer,
+          &ctx.accounts.system_program,
+          &ctx.accounts.token_program,
+        )?;
+      }
+      // wrapped SOL is unwrapped by closing user_token0, which only its owner can authorize
+      let verify_owner = schedule.verify_user_token_owner != 0 || schedule.receiving_token_mint == NATIVE_MINT_ID;
+      verify_token_account(user_token0, &schedule.receiving_token_mint, verify_owner.then_some(user.key))?;
+
+      transfer_token(
+        vault_signer,
+        vault_token0,
+        user_token0,
+        user_amount,
+        &[seeds]
+      ).expect("Coin98Vault: CPI failed.");
+
+      if fee_amount > 0 {
+        let treasury_token = ctx.accounts.treasury_token.as_ref().ok_or(ErrorCode::InvalidAccount)?;
+        verify_token_account(treasury_token, &schedule.receiving_token_mint, Some(&treasury))?;
+        transfer_token(
+          vault_signer,
+          vault_token0,
+          treasury_token,
+          fee_amount,
+          &[seeds]
+        ).expect("Coin98Vault: CPI failed.");
+      }
+
+      if schedule.receiving_token_mint == NATIVE_MINT_ID {
+        // unwrap SOL into the user's wallet
+        close_account(
+          user,
+          user_token0,
+          user,
+          &[]
+        ).expect("Coin98Vault: CPI failed.");
+      }
+    }
+
+    Ok(())
+  }
+
+  #[access_control(verify_schedule(&ctx.accounts.schedule, ObjType::DistributionMulti))]
+  pub fn redeem_token_multi<'a>(
+    ctx: Context<'_, '_, '_, 'a, RedeemTokenMultiContext<'a>>,
+    index: u16,
+    timestamp: i64,
+    proofs: Vec<[u8; 32]>,
+    receiving_token_mint: Pubkey,
+    receiving_amount: u64,
+    sending_amount: u64,
+  ) -> Result<()> {
+    let clock = Clock::get().unwrap();
+
+    let vault = &ctx.accounts.vault;
+    let vault_signer = &ctx.accounts.vault_signer;
+    let user = &ctx.accounts.user;
+
+    let schedule = &mut ctx.accounts.schedule;
+    let tranche = find_tranche(&ctx.accounts.schedule_tranche, schedule, index)?;
+    if let Some(tranche) = tranche {
+      require!(clock.unix_timestamp >= tranche.timestamp, ErrorCode::ScheduleLocked);
+    }
+
+    if schedule.timestamp > 0 {
+      require!(clock.unix_timestamp >= schedule.timestamp, ErrorCode::ScheduleLocked);
+      verify_proof(index, &redemption_multi_data(index, None, ctx.accounts.user.key, receiving_token_mint, receiving_amount, sending_amount), &proofs, schedule, tranche)?;
+    } else {
+      require!(clock.unix_timestamp >= timestamp, ErrorCode::ScheduleLocked);
+      verify_proof(index, &redemption_multi_data(index, Some(timestamp), ctx.accounts.user.key, receiving_token_mint, receiving_amount, sending_amount), &proofs, schedule, tranche)?;
+    }
+
+    let vault_allowlist = load_vault_allowlist(&ctx.accounts.vault_allowlist, &vault.key(), ctx.program_id)?;
+    verify_allowed_mint(&receiving_token_mint, &vault_allowlist)?;
+
+    let user_index: usize = index.into();
+    schedule.redemptions[user_index] = true;
+
+    if schedule.sending_token_mint != solana_program::system_program::ID && sending_amount > 0 {
+      let vault_token1 = ctx.accounts.vault_token1.as_ref().ok_or(ErrorCode::InvalidAccount)?;
+      require_keys_eq!(*vault_token1.key, schedule.sending_token_account, ErrorCode::InvalidAccount);
+      let user_token1 = ctx.accounts.user_token1.as_ref().ok_or(ErrorCode::InvalidAccount)?;
+      verify_token_account(user_token1, &schedule.sending_token_mint, None)?;
+      transfer_token(
+          user,
+          user_token1,
+          vault_token1,
+          sending_amount,
+          &[]
+        )
+        .expect("Coin98Vault: CPI failed.");
+    }
+
+    let vault_fee = load_vault_fee(&ctx.accounts.vault_fee, &vault.key(), ctx.program_id)?;
+    pay_claim_fee_lamports(user, &ctx.accounts.treasury, &vault_fee)?;
+    let treasury = vault_fee.as_ref().map_or(SYSTEM_PROGRAM_ID, |fee| fee.treasury);
+    let fee_amount = vault_fee.as_ref().map_or(0, |fee| fee.token_fee(receiving_amount));
+    let user_amount = receiving_amount - fee_amount;
+
+    let seeds: &[&[_]] = &[
+      SIGNER_SEED_1,
+      vault.to_account_info().key.as_ref(),
+      &[vault.signer_nonce],
+    ];
+
+    if receiving_token_mint == SYSTEM_PROGRAM_ID {
+      let vault_reserve_info = &ctx.accounts.vault_reserve;
+      let vault_reserve = load_vault_reserve(vault_reserve_info, &vault.key(), ctx.program_id)?;
+      verify_sol_reserve(vault_signer, receiving_amount, &vault_reserve)?;
+
+      transfer_lamport(
+        vault_signer,
+        user,
+        user_amount,
+        &[seeds]
+      ).expect("Coin98Vault: CPI failed.");
+
+      if fee_amount > 0 {
+        let treasury = verify_treasury(&ctx.accounts.treasury, &treasury)?;
+        transfer_lamport(
+          vault_signer,
+          treasury,
+          fee_amount,
+          &[seeds]
+        ).expect("Coin98Vault: CPI failed.");
+      }
+
+      settle_sol_liabilities(vault_reserve_info, vault_reserve, receiving_amount)?;
+    } else {
+      let vault_token0 = &ctx.accounts.vault_token0;
+      let user_token0 = &ctx.accounts.user_token0;
+
+      if let Some(associated_token_program) = &ctx.accounts.associated_token_program {
+        create_user_token_account(
+          user,
+          user_token0,
+          &receiving_token_mint,
+          associated_token_program,
+          &ctx.accounts.user_token0_mint,
+          &ctx.accounts.payer,
+          &ctx.accounts.system_program,
+          &ctx.accounts.token_program,
+        )?;
+      }
+      verify_token_account(vault_token0, &receiving_token_mint, Some(vault_signer.key))?;
+      // wrapped SOL is unwrapped by closing user_token0, which only its owner can authorize
+      let verify_owner = schedule.verify_user_token_owner || receiving_token_mint == NATIVE_MINT_ID;
+      verify_token_account(user_token0, &receiving_token_mint, verify_owner.then_some(user.key))?;
+
+      transfer_token(
+        vault_signer,
+        vault_token0,
+        user_token0,
+        user_amount,
+        &[seeds]
+      ).expect("Coin98Vault: CPI failed.");
+
+      if fee_amount > 0 {
+        let treasury_token = ctx.accounts.treasury_token.as_ref().ok_or(ErrorCode::InvalidAccount)?;
+        verify_token_account(treasury_token, &receiving_token_mint, Some(&treasury))?;
+        transfer_token(
+          vault_signer,
+          vault_token0,
+          treasury_token,
+          fee_amount,
+          &[seeds]
+        ).expect("Coin98Vault: CPI failed.");
+      }
+
+      if receiving_token_mint == NATIVE_MINT_ID {
+        // unwrap SOL into the user's wallet
+        close_account(
+          user,
+          user_token0,
+          user,
+          &[]
+        ).expect("Coin98Vault: CPI failed.");
+      }
+    }
+
+    Ok(())
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  #[access_control(verify_schedule(&ctx.accounts.schedule, ObjType::Distribution))]
+  pub fn redeem_token_swap<'a>(
+    ctx: Context<'_, '_, '_, 'a, RedeemTokenSwapContext<'a>>,
+    index: u16,
+    timestamp: i64,
+    proofs: Vec<[u8; 32]>,
+    receiving_amount: u64,
+    sending_amount: u64,
+    output_mint: Pubkey,
+    minimum_amount_out: u64,
+    swap_data: Vec<u8>,
+  ) -> Result<()> {
+    msg!("Coin98Vault: Instruction_RedeemTokenSwap");
+
+    let vault = &ctx.accounts.vault;
+    let vault_signer = &ctx.accounts.vault_signer;
+    let vault_token0 = &ctx.accounts.vault_token0;
+    let user_token0 = &ctx.accounts.user_token0;
+    let user_token_out = &ctx.accounts.user_token_out;
+    let user = &ctx.accounts.user;
+    let clock = Clock::get().unwrap();
+
+    let schedule = &mut ctx.accounts.schedule;
+    require!(schedule.receiving_token_mint != SYSTEM_PROGRAM_ID, ErrorCode::InvalidInput);
+    require_keys_neq!(*user_token_out.key, *user_token0.key, ErrorCode::InvalidAccount);
+
+    let tranche = find_tranche(&ctx.accounts.schedule_tranche, schedule, index)?;
+    if let Some(tranche) = tranche {
+      require!(clock.unix_timestamp >= tranche.timestamp, ErrorCode::ScheduleLocked);
+    }
+    if schedule.timestamp > 0 {
+      require!(clock.unix_timestamp >= schedule.timestamp, ErrorCode::ScheduleLocked);
+      verify_proof(index, &redemption_data(index, None, ctx.accounts.user.key, receiving_amount, sending_amount), &proofs, schedule, tranche)?;
+    } else {
+      require!(clock.unix_timestamp >= timestamp, ErrorCode::ScheduleLocked);
+      verify_proof(index, &redemption_data(index, Some(timestamp), ctx.accounts.user.key, receiving_amount, sending_amount), &proofs, schedule, tranche)?;
+    }
+
+    let user_index: usize = index.into();
+    schedule.redemptions[user_index] = true;
+
+    if schedule.sending_token_mint != solana_program::system_program::ID && sending_amount > 0 {
+      let vault_token1 = ctx.accounts.vault_token1.as_ref().ok_or(ErrorCode::InvalidAccount)?;
+      require_keys_eq!(*vault_token1.key, schedule.sending_token_account, ErrorCode::InvalidAccount);
+      let user_token1 = ctx.accounts.user_token1.as_ref().ok_or(ErrorCode::InvalidAccount)?;
+      verify_token_account(user_token1, &schedule.sending_token_mint, None)?;
+      transfer_token(
+          user,
+          user_token1,
+          vault_token1,
+          sending_amount,
+          &[]
+        )
+        .expect("Coin98Vault: CPI failed.");
+    }
+
+    let vault_fee = load_vault_fee(&ctx.accounts.vault_fee, &vault.key(), ctx.program_id)?;
+    pay_claim_fee_lamports(user, &ctx.accounts.treasury, &vault_fee)?;
+    let treasury = vault_fee.as_ref().map_or(SYSTEM_PROGRAM_ID, |fee| fee.treasury);
+    let fee_amount = vault_fee.as_ref().map_or(0, |fee| fee.token_fee(receiving_amount));
+    let user_amount = receiving_amount - fee_amount;
+
+    let seeds: &[&[_]] = &[
+      SIGNER_SEED_1,
+      vault.to_account_info().key.as_ref(),
+      &[vault.signer_nonce],
+    ];
+
+    verify_token_account(user_token0, &schedule.receiving_token_mint, Some(user.key))?;
+    let amount_out_before = verify_token_account(user_token_out, &output_mint, Some(user.key))?.amount;
+
+    transfer_token(
+      vault_signer,
+      vault_token0,
+      user_token0,
+      user_amount,
+      &[seeds]
+    ).expect("Coin98Vault: CPI failed.");
+
+    if fee_amount > 0 {
+      let treasury_token = ctx.accounts.treasury_token.as_ref().ok_or(ErrorCode::InvalidAccount)?;
+      verify_token_account(treasury_token, &schedule.receiving_token_mint, Some(&treasury))?;
+      transfer_token(
+        vault_signer,
+        vault_token0,
+        treasury_token,
+        fee_amount,
+        &[seeds]
+      ).expect("Coin98Vault: CPI failed.");
+    }
+
+    // the user signs the swap, the vault signer never delegates its authority to the DEX
+    invoke_passthrough(
+      &ctx.accounts.swap_program,
+      ctx.remaining_accounts,
+      swap_data,
+    ).expect("Coin98Vault: CPI failed.");
+
+    let amount_out_after = verify_token_account(user_token_out, &output_mint, Some(user.key))?.amount;
+    require!(amount_out_after.saturating_sub(amount_out_before) >= minimum_amount_out, ErrorCode::SlippageExceeded);
+
+    Ok(())
+  }
+
+  #[access_control(verify_schedule(&ctx.accounts.schedule, ObjType::Distribution))]
+  pub fn redeem_token_stake<'a>(
+    ctx: Context<'_, '_, '_, 'a, RedeemTokenStakeContext<'a>>,
+    index: u16,
+    timestamp: i64,
+    proofs: Vec<[u8; 32]>,
+    receiving_amount: u64,
+    sending_amount: u64,
+    stake_data: Vec<u8>,
+  ) -> Result<()> {
+    msg!("Coin98Vault: Instruction_RedeemTokenStake");
+
+    let vault = &ctx.accounts.vault;
+    let vault_signer = &ctx.accounts.vault_signer;
+    let vault_token0 = &ctx.accounts.vault_token0;
+    let user_token0 = &ctx.accounts.user_token0;
+    let schedule_stake = &ctx.accounts.schedule_stake;
+    let user = &ctx.accounts.user;
+    let clock = Clock::get().unwrap();
+
+    let schedule = &mut ctx.accounts.schedule;
+    require!(schedule.receiving_token_mint != SYSTEM_PROGRAM_ID, ErrorCode::InvalidInput);
+
+    let tranche = find_tranche(&ctx.accounts.schedule_tranche, schedule, index)?;
+    if let Some(tranche) = tranche {
+      require!(clock.unix_timestamp >= tranche.timestamp, ErrorCode::ScheduleLocked);
+    }
+    if schedule.timestamp > 0 {
+      require!(clock.unix_timestamp >= schedule.timestamp, ErrorCode::ScheduleLocked);
+      verify_proof(index, &redemption_data(index, None, ctx.accounts.user.key, receiving_amount, sending_amount), &proofs, schedule, tranche)?;
+    } else {
+      require!(clock.unix_timestamp >= timestamp, ErrorCode::ScheduleLocked);
+      verify_proof(index, &redemption_data(index, Some(timestamp), ctx.accounts.user.key, receiving_amount, sending_amount), &proofs, schedule, tranche)?;
+    }
+
+    let user_index: usize = index.into();
+    schedule.redemptions[user_index] = true;
+
+    if schedule.sending_token_mint != solana_program::system_program::ID && sending_amount > 0 {
+      let vault_token1 = ctx.accounts.vault_token1.as_ref().ok_or(ErrorCode::InvalidAccount)?;
+      require_keys_eq!(*vault_token1.key, schedule.sending_token_account, ErrorCode::InvalidAccount);
+      let user_token1 = ctx.accounts.user_token1.as_ref().ok_or(ErrorCode::InvalidAccount)?;
+      verify_token_account(user_token1, &schedule.sending_token_mint, None)?;
+      transfer_token(
+          user,
+          user_token1,
+          vault_token1,
+          sending_amount,
+          &[]
+        )
+        .expect("Coin98Vault: CPI failed.");
+    }
+
+    let vault_fee = load_vault_fee(&ctx.accounts.vault_fee, &vault.key(), ctx.program_id)?;
+    pay_claim_fee_lamports(user, &ctx.accounts.treasury, &vault_fee)?;
+    let treasury = vault_fee.as_ref().map_or(SYSTEM_PROGRAM_ID, |fee| fee.treasury);
+    let fee_amount = vault_fee.as_ref().map_or(0, |fee| fee.token_fee(receiving_amount));
+    let user_amount = receiving_amount - fee_amount;
+
+    let seeds: &[&[_]] = &[
+      SIGNER_SEED_1,
+      vault.to_account_info().key.as_ref(),
+      &[vault.signer_nonce],
+    ];
+
+    let amount_before = verify_token_account(user_token0, &schedule.receiving_token_mint, Some(user.key))?.amount;
+
+    transfer_token(
+      vault_signer,
+      vault_token0,
+      user_token0,
+      user_amount,
+      &[seeds]
+    ).expect("Coin98Vault: CPI failed.");
+
+    if fee_amount > 0 {
+      let treasury_token = ctx.accounts.treasury_token.as_ref().ok_or(ErrorCode::InvalidAccount)?;
+      verify_token_account(treasury_token, &schedule.receiving_token_mint, Some(&treasury))?;
+      transfer_token(
+        vault_signer,
+        vault_token0,
+        treasury_token,
+        fee_amount,
+        &[seeds]
+      ).expect("Coin98Vault: CPI failed.");
+    }
+
+    verify_stake_accounts(
+      ctx.remaining_accounts,
+      schedule_stake,
+      &[user.key, user_token0.key, ctx.accounts.user_stake.key],
+    )?;
+    // the user signs the deposit, the vault signer never delegates its authority to the staking program
+    invoke_passthrough(
+      &ctx.accounts.stake_program,
+      ctx.remaining_accounts,
+      stake_data,
+    ).expect("Coin98Vault: CPI failed.");
+
+    let amount_after = verify_token_account(user_token0, &schedule.receiving_token_mint, Some(user.key))?.amount;
+    require!(amount_after <= amount_before, ErrorCode::StakeIncomplete);
+
+    Ok(())
+  }
+
+  #[access_control(verify_schedule(&ctx.accounts.schedule, ObjType::DistributionNft))]
+  pub fn redeem_nft<'a>(
+    ctx: Context<'_, '_, '_, 'a, RedeemNftContext<'a>>,
+    index: u16,
+    timestamp: i64,
+    proofs: Vec<[u8; 32]>,
+    asset: Pubkey,
+  ) -> Result<()> {
+    msg!("Coin98Vault: Instruction_RedeemNft");
+
+    let vault = &ctx.accounts.vault;
+    let vault_signer = &ctx.accounts.vault_signer;
+    let vault_token0 = &ctx.accounts.vault_token0;
+    let user_token0 = &ctx.accounts.user_token0;
+    let user = &ctx.accounts.user;
+    let clock = Clock::get().unwrap();
+
+    let schedule = &mut ctx.accounts.schedule;
+    require!(clock.unix_timestamp >= schedule.timestamp && clock.unix_timestamp >= timestamp, ErrorCode::ScheduleLocked);
+    verify_proof_nft(index, timestamp, user.key, &asset, 0, &proofs, schedule)?;
+
+    let user_index: usize = index.into();
+    schedule.redemptions[user_index] = true;
+
+    let vault_fee = load_vault_fee(&ctx.accounts.vault_fee, &vault.key(), ctx.program_id)?;
+    pay_claim_fee_lamports(user, &ctx.accounts.treasury, &vault_fee)?;
+
+    if let Some(associated_token_program) = &ctx.accounts.associated_token_program {
+      create_user_token_account(
+        user,
+        user_token0,
+        &asset,
+        associated_token_program,
+        &ctx.accounts.user_token0_mint,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        &ctx.accounts.token_program,
+      )?;
+    }
+    verify_token_account(vault_token0, &asset, Some(vault_signer.key))?;
+    verify_token_account(user_token0, &asset, Some(user.key))?;
+
+    let seeds: &[&[_]] = &[
+      SIGNER_SEED_1,
+      vault.to_account_info().key.as_ref(),
+      &[vault.signer_nonce],
+    ];
+    transfer_token(
+      vault_signer,
+      vault_token0,
+      user_token0,
+      1,
+      &[seeds]
+    ).expect("Coin98Vault: CPI failed.");
+
+    Ok(())
+  }
+
+  #[access_control(verify_schedule(&ctx.accounts.schedule, ObjType::DistributionNft))]
+  pub fn redeem_nft_edition<'a>(
+    ctx: Context<'_, '_, '_, 'a, RedeemNftEditionContext<'a>>,
+    index: u16,
+    timestamp: i64,
+    proofs: Vec<[u8; 32]>,
+    asset: Pubkey,
+    edition: u64,
+  ) -> Result<()> {
+    msg!("Coin98Vault: Instruction_RedeemNftEdition");
+
+    let vault = &ctx.accounts.vault;
+    let vault_signer = &ctx.accounts.vault_signer;
+    let vault_token0 = &ctx.accounts.vault_token0;
+    let user = &ctx.accounts.user;
+    let clock = Clock::get().unwrap();
+
+    require!(edition > 0, ErrorCode::InvalidInput);
+
+    let schedule = &mut ctx.accounts.schedule;
+    require!(clock.unix_timestamp >= schedule.timestamp && clock.unix_timestamp >= timestamp, ErrorCode::ScheduleLocked);
+    verify_proof_nft(index, timestamp, user.key, &asset, edition, &proofs, schedule)?;
+
+    let user_index: usize = index.into();
+    schedule.redemptions[user_index] = true;
+
+    let vault_fee = load_vault_fee(&ctx.accounts.vault_fee, &vault.key(), ctx.program_id)?;
+    pay_claim_fee_lamports(user, &ctx.accounts.treasury, &vault_fee)?;
+
+    verify_token_account(vault_token0, &asset, Some(vault_signer.key))?;
+    require_keys_eq!(*ctx.accounts.metadata.key, get_metadata_address(&asset), ErrorCode::InvalidAccount);
+    require_keys_eq!(*ctx.accounts.master_edition.key, get_master_edition_address(&asset), ErrorCode::InvalidAccount);
+
+    let seeds: &[&[_]] = &[
+      SIGNER_SEED_1,
+      vault.to_account_info().key.as_ref(),
+      &[vault.signer_nonce],
+    ];
+    mint_new_edition_from_master_edition_via_token(
+      &ctx.accounts.new_metadata,
+      &ctx.accounts.new_edition,
+      &ctx.accounts.master_edition,
+      &ctx.accounts.new_mint,
+      &ctx.accounts.edition_mark,
+      user,
+      user,
+      vault_signer,
+      vault_token0,
+      &ctx.accounts.new_metadata_update_authority,
+      &ctx.accounts.metadata,
+      &ctx.accounts.token_program,
+      &ctx.accounts.system_program,
+      edition,
+      &[seeds]
+    ).expect("Coin98Vault: CPI failed.");
+
+    Ok(())
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  #[access_control(verify_schedule(&ctx.accounts.schedule, ObjType::DistributionNft))]
+  pub fn redeem_compressed_nft<'a>(
+    ctx: Context<'_, '_, '_, 'a, RedeemCompressedNftContext<'a>>,
+    index: u16,
+    timestamp: i64,
+    proofs: Vec<[u8; 32]>,
+    root: [u8; 32],
+    data_hash: [u8; 32],
+    creator_hash: [u8; 32],
+    nonce: u64,
+    leaf_index: u32,
+  ) -> Result<()> {
+    msg!("Coin98Vault: Instruction_RedeemCompressedNft");
+
+    let vault = &ctx.accounts.vault;
+    let vault_signer = &ctx.accounts.vault_signer;
+    let user = &ctx.accounts.user;
+    let merkle_tree = &ctx.accounts.merkle_tree;
+    let clock = Clock::get().unwrap();
+
+    let asset = get_asset_id(merkle_tree.key, nonce);
+
+    let schedule = &mut ctx.accounts.schedule;
+    require!(clock.unix_timestamp >= schedule.timestamp && clock.unix_timestamp >= timestamp, ErrorCode::ScheduleLocked);
+    verify_proof_nft(index, timestamp, user.key, &asset, 0, &proofs, schedule)?;
+
+    let user_index: usize = index.into();
+    schedule.redemptions[user_index] = true;
+
+    let vault_fee = load_vault_fee(&ctx.accounts.vault_fee, &vault.key(), ctx.program_id)?;
+    pay_claim_fee_lamports(user, &ctx.accounts.treasury, &vault_fee)?;
+
+    let seeds: &[&[_]] = &[
+      SIGNER_SEED_1,
+      vault.to_account_info().key.as_ref(),
+      &[vault.signer_nonce],
+    ];
+    transfer_compressed_nft(
+      &ctx.accounts.tree_authority,
+      vault_signer,
+      &ctx.accounts.leaf_delegate,
+      user,
+      merkle_tree,
+      &ctx.accounts.log_wrapper,
+      &ctx.accounts.compression_program,
+      &ctx.accounts.system_program,
+      ctx.remaining_accounts,
+      BubblegumTransferParams {
+        root,
+        data_hash,
+        creator_hash,
+        nonce,
+        index: leaf_index,
+      },
+      &[seeds]
+    ).expect("Coin98Vault: CPI failed.");
+
+    Ok(())
+  }
+
+  #[access_control(verify_schedule(&ctx.accounts.schedule, ObjType::DistributionVoucher))]
+  pub fn redeem_voucher<'a>(
+    ctx: Context<'_, '_, '_, 'a, RedeemVoucherContext<'a>>,
+    amount: u64,
+    nonce: u32,
+    expiry: i64,
+  ) -> Result<()> {
+    msg!("Coin98Vault: Instruction_RedeemVoucher");
+
+    let vault = &ctx.accounts.vault;
+    let schedule = &ctx.accounts.schedule;
+    let vault_signer = &ctx.accounts.vault_signer;
+    let vault_token0 = &ctx.accounts.vault_token0;
+    let user_token0 = &ctx.accounts.user_token0;
+    let user = &ctx.accounts.user;
+    let clock = Clock::get().unwrap();
+
+    require!(clock.unix_timestamp >= schedule.timestamp, ErrorCode::ScheduleLocked);
+    require!(clock.unix_timestamp <= expiry, ErrorCode::VoucherExpired);
+
+    let schedule_voucher = &mut ctx.accounts.schedule_voucher;
+    let voucher = Voucher {
+      schedule: schedule.key(),
+      user: *user.key,
+      amount,
+      nonce,
+      expiry,
+    };
+    verify_voucher(&ctx.accounts.instructions, &schedule_voucher.voucher_signer, &voucher)?;
+
+    let is_nonce_used = schedule_voucher.is_nonce_used(nonce).ok_or(ErrorCode::IndexOutOfRange)?;
+    require!(!is_nonce_used, ErrorCode::Redeemed);
+    schedule_voucher.use_nonce(nonce);
+
+    let vault_fee = load_vault_fee(&ctx.accounts.vault_fee, &vault.key(), ctx.program_id)?;
+    pay_claim_fee_lamports(user, &ctx.accounts.treasury, &vault_fee)?;
+    let treasury = vault_fee.as_ref().map_or(SYSTEM_PROGRAM_ID, |fee| fee.treasury);
+    let fee_amount = vault_fee.as_ref().map_or(0, |fee| fee.token_fee(amount));
+    let user_amount = amount - fee_amount;
+
+    let seeds: &[&[_]] = &[
+      SIGNER_SEED_1,
+      vault.to_account_info().key.as_ref(),
+      &[vault.signer_nonce],
+    ];
+
+    if schedule.receiving_token_mint == SYSTEM_PROGRAM_ID {
+      let vault_reserve_info = &ctx.accounts.vault_reserve;
+      let vault_reserve = load_vault_reserve(vault_reserve_info, &vault.key(), ctx.program_id)?;
+      verify_sol_reserve(vault_signer, amount, &vault_reserve)?;
+
+      transfer_lamport(
+        vault_signer,
+        user,
+        user_amount,
+        &[seeds]
+      ).expect("Coin98Vault: CPI failed.");
+
+      if fee_amount > 0 {
+        let treasury = verify_treasury(&ctx.accounts.treasury, &treasury)?;
+        transfer_lamport(
+          vault_signer,
+          treasury,
+          fee_amount,
+          &[seeds]
+        ).expect("Coin98Vault: CPI failed.");
+      }
+
+      settle_sol_liabilities(vault_reserve_info, vault_reserve, amount)?;
+    } else {
+      verify_token_account(user_token0, &schedule.receiving_token_mint, Some(user.key))?;
+
+      transfer_token(
+        vault_signer,
+        vault_token0,
+        user_token0,
+        user_amount,
+        &[seeds]
+      ).expect("Coin98Vault: CPI failed.");
+
+      if fee_amount > 0 {
+        let treasury_token = ctx.accounts.treasury_token.as_ref().ok_or(ErrorCode::InvalidAccount)?;
+        verify_token_account(treasury_token, &schedule.receiving_token_mint, Some(&treasury))?;
+        transfer_token(
+          vault_signer,
+          vault_token0,
+          treasury_token,
+          fee_amount,
+          &[seeds]
+        ).expect("Coin98Vault: CPI failed.");
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Pays out the difference between the cumulative amount of the user in the current epoch
+  /// and what the user already claimed from the schedule
+  #[access_control(verify_schedule(&ctx.accounts.schedule, ObjType::DistributionEpoch))]
+  pub fn redeem_epoch<'a>(
+    ctx: Context<'_, '_, '_, 'a, RedeemEpochContext<'a>>,
+    proofs: Vec<[u8; 32]>,
+    cumulative_amount: u64,
+  ) -> Result<()> {
+    msg!("Coin98Vault: Instruction_RedeemEpoch");
+
+    let vault = &ctx.accounts.vault;
+    let schedule = &ctx.accounts.schedule;
+    let schedule_epoch = &ctx.accounts.schedule_epoch;
+    let vault_signer = &ctx.accounts.vault_signer;
+    let vault_token0 = &ctx.accounts.vault_token0;
+    let user_token0 = &ctx.accounts.user_token0;
+    let user = &ctx.accounts.user;
+    let clock = Clock::get().unwrap();
+
+    require!(schedule_epoch.epoch > 0, ErrorCode::ScheduleLocked);
+    require!(clock.unix_timestamp >= schedule.timestamp, ErrorCode::ScheduleLocked);
+    verify_proof_epoch(schedule_epoch.epoch, user.key, cumulative_amount, &proofs, schedule)?;
+
+    let epoch_claim = &mut ctx.accounts.epoch_claim;
+    epoch_claim.obj_type = ObjType::EpochClaim;
+    epoch_claim.nonce = ctx.bumps.epoch_claim;
+    epoch_claim.schedule_id = schedule.key();
+    epoch_claim.user = *user.key;
+
+    require!(cumulative_amount > epoch_claim.claimed_amount, ErrorCode::Redeemed);
+    let amount = cumulative_amount - epoch_claim.claimed_amount;
+    epoch_claim.claimed_amount = cumulative_amount;
+
+    let vault_fee = load_vault_fee(&ctx.accounts.vault_fee, &vault.key(), ctx.program_id)?;
+    pay_claim_fee_lamports(user, &ctx.accounts.treasury, &vault_fee)?;
+    let treasury = vault_fee.as_ref().map_or(SYSTEM_PROGRAM_ID, |fee| fee.treasury);
+    let fee_amount = vault_fee.as_ref().map_or(0, |fee| fee.token_fee(amount));
+    let user_amount = amount - fee_amount;
+
+    let seeds: &[&[_]] = &[
+      SIGNER_SEED_1,
+      vault.to_account_info().key.as_ref(),
+      &[vault.signer_nonce],
+    ];
+
+    if schedule.receiving_token_mint == SYSTEM_PROGRAM_ID {
+      let vault_reserve_info = &ctx.accounts.vault_reserve;
+      let vault_reserve = load_vault_reserve(vault_reserve_info, &vault.key(), ctx.program_id)?;
+      verify_sol_reserve(vault_signer, amount, &vault_reserve)?;
+
+      transfer_lamport(
+        vault_signer,
+        user,
+        user_amount,
+        &[seeds]
+      ).expect("Coin98Vault: CPI failed.");
+
+      if fee_amount > 0 {
+        let treasury = verify_treasury(&ctx.accounts.treasury, &treasury)?;
+        transfer_lamport(
+          vault_signer,
+          treasury,
+          fee_amount,
+          &[seeds]
+        ).expect("Coin98Vault: CPI failed.");
+      }
+
+      settle_sol_liabilities(vault_reserve_info, vault_reserve, amount)?;
+    } else {
+      verify_token_account(user_token0, &schedule.receiving_token_mint, Some(user.key))?;
+
+      transfer_token(
+        vault_signer,
+        vault_token0,
+        user_token0,
+        user_amount,
+        &[seeds]
+      ).expect("Coin98Vault: CPI failed.");
+
+      if fee_amount > 0 {
+        let treasury_token = ctx.accounts.treasury_token.as_ref().ok_or(ErrorCode::InvalidAccount)?;
+        verify_token_account(treasury_token, &schedule.receiving_token_mint, Some(&treasury))?;
+        transfer_token(
+          vault_signer,
+          vault_token0,
+          treasury_token,
+          fee_amount,
+          &[seeds]
+        ).expect("Coin98Vault: CPI failed.");
+      }
+    }
+
+    Ok(())
+  }
+
+  pub fn get_claim_status(
+    ctx: Context<GetClaimStatusContext>,
+    index: u16,
+  ) -> Result<ClaimStatus> {
+
+    let schedule = &ctx.accounts.schedule;
+
+    let is_redeemed = schedule.is_redeemed(index).ok_or(ErrorCode::IndexOutOfRange)?;
+
+    Ok(ClaimStatus {
+      index,
+      is_active: schedule.is_active,
+      is_redeemed,
+    })
+  }
+
+  #[access_control(is_owner(&ctx.accounts.owner.key, &ctx.accounts.vault))]
+  pub fn transfer_ownership(
+    ctx: Context<TransferOwnershipContext>,
+    new_owner: Pubkey,
+  ) -> Result<()> {
+
+    let vault = &mut ctx.accounts.vault;
+
+    vault.new_owner = new_owner;
+
+    Ok(())
+  }
+
+  #[access_control(verify_new_owner(&ctx.accounts.new_owner.key, &ctx.accounts.vault))]
+  pub fn accept_ownership(
+    ctx: Context<AcceptOwnershipContext>,
+  ) -> Result<()> {
+
+    let vault = &mut ctx.accounts.vault;
+
+    vault.owner = vault.new_owner;
+    vault.new_owner = anchor_lang::system_program::ID; // Set to empty
+
+    Ok(())
+  }
+
+  pub fn migrate_vault(
+    ctx: Context<MigrateVaultContext>,
+  ) -> Result<()> {
+
+    let owner = &ctx.accounts.owner;
+    let vault_info = &ctx.accounts.vault;
+
+    require_keys_eq!(*vault_info.owner, *ctx.program_id, ErrorCode::InvalidAccount);
+    let mut vault = Vault::try_deserialize_versioned(&vault_info.try_borrow_data()?)?;
+    is_owner(owner.key, &vault)?;
+    require!(vault.version < VAULT_VERSION, ErrorCode::InvalidInput);
+
+    vault.version = VAULT_VERSION;
+    resize_account(vault_info, owner, 16 + Vault::size())?;
+    vault.try_serialize(&mut &mut vault_info.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+  }
+
+  pub fn migrate_schedule(
+    ctx: Context<MigrateScheduleContext>,
+  ) -> Result<()> {
+
+    let admin = &ctx.accounts.admin;
+    let vault_info = &ctx.accounts.vault;
+    let schedule_info = &ctx.accounts.schedule;
+
+    require_keys_eq!(*vault_info.owner, *ctx.program_id, ErrorCode::InvalidAccount);
+    let vault = Vault::try_deserialize_versioned(&vault_info.try_borrow_data()?)?;
+    is_admin(admin.key, &vault)?;
+
+    require_keys_eq!(*schedule_info.owner, *ctx.program_id, ErrorCode::InvalidAccount);
+    let mut schedule = Schedule::try_deserialize_versioned(&schedule_info.try_borrow_data()?)?;
+    require_keys_eq!(schedule.vault_id, vault_info.key(), ErrorCode::InvalidAccount);
+    require!(schedule.version < SCHEDULE_VERSION, ErrorCode::InvalidInput);
+    let user_count = u16::try_from(schedule.redemptions.len()).map_err(|_| ErrorCode::InvalidAccount)?;
+
+    schedule.version = SCHEDULE_VERSION;
+    resize_account(schedule_info, admin, 16 + Schedule::size(user_count))?;
+    schedule.try_serialize(&mut &mut schedule_info.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+  }
+}
+
+/// Grows `account` to `size` bytes if it is smaller, topping up its rent exemption from `payer`
+pub fn resize_account<'a>(account: &AccountInfo<'a>, payer: &AccountInfo<'a>, size: usize) -> Result<()> {
+  if account.data_len() >= size {
+    return Ok(());
+  }
+
+  let rent_exempt = Rent::get()?.minimum_balance(size);
+  let lamports = rent_exempt.saturating_sub(account.lamports());
+  if lamports > 0 {
+    transfer_lamport(
+        payer,
+        account,
+        lamports,
+        &[]
+      )
+      .expect("Coin98Vault: CPI failed.");
+  }
+  account.realloc(size, false)?;
+
+  Ok(())
+}
+
+/// Returns true if the user has root priviledge of the vault
+pub fn is_owner(user: &Pubkey, vault: &Vault) -> Result<()> {
+
+  require_keys_eq!(*user, vault.owner, ErrorCode::Unauthorized);
+
+  Ok(())
+}
+
+/// Returns true if the user is the newly apppointed owner of the vault
+pub fn verify_new_owner(user: &Pubkey, vault: &Vault) -> Result<()> {
+
+  require_keys_eq!(*user, vault.new_owner, ErrorCode::Unauthorized);
+
+  Ok(())
+}
+
+/// Returns true if the user is an admin of a specified vault
+pub fn is_admin(user: &Pubkey, vault: &Vault) -> Result<()> {
+  if *user == vault.owner {
+   return Ok(());
+  }
+
+  let result = vault.admins.iter().position(|&key| key == *user);
+  if result.is_none() {
+    return Err(ErrorCode::Unauthorized.into());
+  }
+
+  Ok(())
+}
+
+pub fn verify_schedule(schedule: &Schedule, expected_type: ObjType) -> Result<()> {
   require!(schedule.obj_type == expected_type, ErrorCode::InvalidAccount);
   require!(schedule.is_active, ErrorCode::ScheduleUnavailable);
 
   Ok(())
 }
 
-pub fn verify_proof(index: u16, timestamp: Option<i64>, user: &Pubkey, receiving_amount: u64, sending_amount: u64, proofs: &Vec<[u8; 32]>, schedule: &Schedule) -> Result<()> {
-  let redemption_data = match timestamp {
+/// Returns the reserve settings of a vault, `None` if its reserve account was never initialized
+pub fn load_vault_reserve(vault_reserve: &AccountInfo, vault: &Pubkey, program_id: &Pubkey) -> Result<Option<VaultReserve>> {
+  load_vault_account(vault_reserve, RESERVE_SEED_1, vault, program_id)
+}
+
+/// Returns the claim fee of a vault, `None` if its fee account was never initialized
+pub fn load_vault_fee(vault_fee: &AccountInfo, vault: &Pubkey, program_id: &Pubkey) -> Result<Option<VaultFee>> {
+  load_vault_account(vault_fee, FEE_SEED_1, vault, program_id)
+}
+
+/// Returns the mint allowlist of a vault, `None` if its allowlist account was never initialized
+pub fn load_vault_allowlist(vault_allowlist: &AccountInfo, vault: &Pubkey, program_id: &Pubkey) -> Result<Option<VaultAllowlist>> {
+  load_vault_account(vault_allowlist, ALLOWLIST_SEED_1, vault, program_id)
+}
+
+/// Returns an error if the vault has an allowlist without `mint`. Native SOL, recorded as
+/// the system program, is always allowed
+pub fn verify_allowed_mint(mint: &Pubkey, vault_allowlist: &Option<VaultAllowlist>) -> Result<()> {
+  if *mint == SYSTEM_PROGRAM_ID {
+    return Ok(());
+  }
+  if let Some(vault_allowlist) = vault_allowlist {
+    require!(vault_allowlist.is_allowed(mint), ErrorCode::MintNotAllowed);
+  }
+
+  Ok(())
+}
+
+/// Returns the withdrawal destinations of a vault, `None` if its destinations account was
+/// never initialized
+pub fn load_vault_destinations(vault_destinations: &AccountInfo, vault: &Pubkey, program_id: &Pubkey) -> Result<Option<VaultDestinations>> {
+  load_vault_account(vault_destinations, DESTINATION_SEED_1, vault, program_id)
+}
+
+/// Returns an error if the vault has withdrawal destinations and `recipient` is not one of
+/// them past its timelock
+pub fn verify_withdrawal_destination(recipient: &Pubkey, vault_destinations: &Option<VaultDestinations>) -> Result<()> {
+  if let Some(vault_destinations) = vault_destinations {
+    let clock = Clock::get().unwrap();
+    require!(vault_destinations.is_allowed(recipient, clock.unix_timestamp), ErrorCode::DestinationNotAllowed);
+  }
+
+  Ok(())
+}
+
+/// Requires `signer` to be the vault owner once the vault has withdrawal destinations. The
+/// merkle roots, voucher signer and stake program of a schedule decide who it pays, so an
+/// admin setting them could otherwise move the vault's funds to any address
+pub fn verify_payout_authority(signer: &Pubkey, vault: &Account<Vault>, vault_destinations: &AccountInfo, program_id: &Pubkey) -> Result<()> {
+  if load_vault_destinations(vault_destinations, &vault.key(), program_id)?.is_some() {
+    is_owner(signer, vault)?;
+  }
+
+  Ok(())
+}
+
+/// Deserializes the optional per vault account derived from `seed`, after checking its address
+fn load_vault_account<T: AccountDeserialize>(account: &AccountInfo, seed: &[u8], vault: &Pubkey, program_id: &Pubkey) -> Result<Option<T>> {
+  let (address, _) = Pubkey::find_program_address(
+    &[
+      seed,
+      vault.as_ref(),
+    ],
+    program_id,
+  );
+  require_keys_eq!(*account.key, address, ErrorCode::InvalidAccount);
+
+  if account.data_is_empty() {
+    return Ok(None);
+  }
+  require_keys_eq!(*account.owner, *program_id, ErrorCode::InvalidAccount);
+  let data = account.try_borrow_data()?;
+  Ok(Some(T::try_deserialize(&mut &data[..])?))
+}
+
+/// Returns an error if the stake instruction uses an account that is neither whitelisted for
+/// the schedule nor one of `user_accounts`
+pub fn verify_stake_accounts(accounts: &[AccountInfo], schedule_stake: &ScheduleStake, user_accounts: &[&Pubkey]) -> Result<()> {
+  for account in accounts {
+    let is_allowed = user_accounts.contains(&account.key) || schedule_stake.stake_accounts.contains(account.key);
+    require!(is_allowed, ErrorCode::InvalidAccount);
+  }
+
+  Ok(())
+}
+
+/// Returns `account` after checking it is the treasury of the vault
+pub fn verify_treasury<'b, 'a>(account: &'b Option<AccountInfo<'a>>, treasury: &Pubkey) -> Result<&'b AccountInfo<'a>> {
+  let account = account.as_ref().ok_or(ErrorCode::InvalidAccount)?;
+  require_keys_eq!(*account.key, *treasury, ErrorCode::InvalidAccount);
+
+  Ok(account)
+}
+
+/// Charges the flat SOL claim fee of the vault to the user
+pub fn pay_claim_fee_lamports<'a>(user: &AccountInfo<'a>, treasury: &Option<AccountInfo<'a>>, vault_fee: &Option<VaultFee>) -> Result<()> {
+  if let Some(vault_fee) = vault_fee {
+    if vault_fee.fee_lamports > 0 {
+      let treasury = verify_treasury(treasury, &vault_fee.treasury)?;
+      transfer_lamport(
+        user,
+        treasury,
+        vault_fee.fee_lamports,
+        &[]
+      ).expect("Coin98Vault: CPI failed.");
+    }
+  }
+
+  Ok(())
+}
+
+/// Returns an error if sending `amount` lamports would take the vault signer below its
+/// rent exempt minimum plus the SOL reserve of the vault
+pub fn verify_sol_reserve(vault_signer: &AccountInfo, amount: u64, vault_reserve: &Option<VaultReserve>) -> Result<()> {
+  let sol_reserve = vault_reserve.as_ref().map_or(0, |reserve| reserve.sol_reserve);
+  let minimum_balance = Rent::get()?.minimum_balance(vault_signer.data_len())
+    .checked_add(sol_reserve)
+    .ok_or(ErrorCode::InsufficientReserve)?;
+  let remaining_balance = vault_signer.lamports()
+    .checked_sub(amount)
+    .ok_or(ErrorCode::InsufficientReserve)?;
+  require!(remaining_balance >= minimum_balance, ErrorCode::InsufficientReserve);
+
+  Ok(())
+}
+
+/// Returns the lamports of the vault signer above its rent exempt minimum, the SOL reserve
+/// and the outstanding SOL liabilities of the vault
+pub fn withdrawable_sol(vault_signer: &AccountInfo, vault_reserve: &Option<VaultReserve>) -> Result<u64> {
+  let (sol_reserve, sol_liabilities) = vault_reserve.as_ref()
+    .map_or((0, 0), |reserve| (reserve.sol_reserve, reserve.sol_liabilities));
+  let minimum_balance = Rent::get()?.minimum_balance(vault_signer.data_len());
+
+  Ok(
+    vault_signer.lamports()
+      .saturating_sub(minimum_balance)
+      .saturating_sub(sol_reserve)
+      .saturating_sub(sol_liabilities)
+  )
+}
+
+/// Deducts a redeemed `amount` from the outstanding SOL liabilities of the vault
+pub fn settle_sol_liabilities(vault_reserve_info: &AccountInfo, vault_reserve: Option<VaultReserve>, amount: u64) -> Result<()> {
+  if let Some(mut vault_reserve) = vault_reserve {
+    vault_reserve.sol_liabilities = vault_reserve.sol_liabilities.saturating_sub(amount);
+    let mut data = vault_reserve_info.try_borrow_mut_data()?;
+    vault_reserve.try_serialize(&mut &mut data[..])?;
+  }
+
+  Ok(())
+}
+
+/// Creates `user_token0` as the associated token account of `user` for `mint` if it does
+/// not exist yet. `payer` funds the rent and can be the user or a relayer
+#[allow(clippy::too_many_arguments)]
+pub fn create_user_token_account<'a>(
+  user: &AccountInfo<'a>,
+  user_token0: &AccountInfo<'a>,
+  mint: &Pubkey,
+  associated_token_program: &AccountInfo<'a>,
+  user_token0_mint: &Option<AccountInfo<'a>>,
+  payer: &Option<AccountInfo<'a>>,
+  system_program: &Program<'a, System>,
+  token_program: &AccountInfo<'a>,
+) -> Result<()> {
+  require!(is_associated_token_program(associated_token_program), ErrorCode::InvalidAccount);
+  let user_token0_mint = user_token0_mint.as_ref().ok_or(ErrorCode::InvalidAccount)?;
+  require_keys_eq!(*user_token0_mint.key, *mint, ErrorCode::InvalidTokenMint);
+  let payer = payer.as_ref().ok_or(ErrorCode::InvalidAccount)?;
+
+  create_associated_token_account_idempotent(
+    payer,
+    user_token0,
+    user,
+    user_token0_mint,
+    &system_program.to_account_info(),
+    token_program,
+  ).expect("Coin98Vault: CPI failed.");
+
+  Ok(())
+}
+
+/// Returns the token account data after checking it is owned by the token program, holds
+/// `mint` and, when given, belongs to `owner`
+pub fn verify_token_account(account: &AccountInfo, mint: &Pubkey, owner: Option<&Pubkey>) -> Result<TokenAccount> {
+  require_keys_eq!(*account.owner, TOKEN_PROGRAM_ID, ErrorCode::InvalidTokenAccount);
+  require!(account.data_len() == TokenAccount::LEN, ErrorCode::InvalidTokenAccount);
+
+  let token_account = TokenAccount::unpack_from_slice(&account.try_borrow_data()?)
+    .map_err(|_| ErrorCode::InvalidTokenAccount)?;
+  require_keys_eq!(token_account.mint, *mint, ErrorCode::InvalidTokenMint);
+  if let Some(owner) = owner {
+    require_keys_eq!(token_account.owner, *owner, ErrorCode::InvalidTokenOwner);
+  }
+
+  Ok(token_account)
+}
+
+/// Returns the tranche of `schedule_tranche` holding `index`, `None` when the index belongs
+/// to the schedule's own merkle root. `schedule_tranche` is required once the schedule has
+/// tranches, so their proofs and unlock times cannot be bypassed by omitting it
+pub fn find_tranche<'a>(schedule_tranche: &'a Option<Account<ScheduleTranche>>, schedule: &Schedule, index: u16) -> Result<Option<&'a Tranche>> {
+  if !schedule.has_tranches() {
+    return Ok(None);
+  }
+  let schedule_tranche = schedule_tranche.as_ref().ok_or(ErrorCode::InvalidAccount)?;
+  if index < schedule.user_count {
+    return Ok(None);
+  }
+
+  Ok(schedule_tranche.find_tranche(index))
+}
+
+pub fn verify_proof(index: u16, redemption_data: &[u8], proofs: &[[u8; 32]], schedule: &Schedule, tranche: Option<&Tranche>) -> Result<()> {
+  // checked before the proof, whose leaf may be valid for an index past user_count
+  let is_redeemed = schedule.is_redeemed(index).ok_or(ErrorCode::IndexOutOfRange)?;
+
+  let root: [u8; 32] = match tranche {
+    Some(tranche) => tranche.merkle_root,
+    None => schedule.merkle_root.clone().try_into().unwrap(),
+  };
+  verify_redemption_proof(redemption_data, proofs, root)?;
+
+  require!(!is_redeemed, ErrorCode::Redeemed);
+
+  Ok(())
+}
+
+/// Same as `verify_proof` against the header and redemption bitmap of a `BitmapSchedule`
+pub fn verify_proof_bitmap(index: u16, redemption_data: &[u8], proofs: &[[u8; 32]], schedule: &BitmapSchedule, bitmap: &[u8]) -> Result<()> {
+  // checked before the proof, whose leaf may be valid for an index past user_count
+  let is_redeemed = schedule.is_redeemed(bitmap, index).ok_or(ErrorCode::IndexOutOfRange)?;
+
+  verify_redemption_proof(redemption_data, proofs, schedule.merkle_root)?;
+
+  require!(!is_redeemed, ErrorCode::Redeemed);
+
+  Ok(())
+}
+
+/// Returns an error unless `proofs` proves the leaf hashed from `redemption_data` under `root`
+fn verify_redemption_proof(redemption_data: &[u8], proofs: &[[u8; 32]], root: [u8; 32]) -> Result<()> {
+  let leaf = hash(redemption_data);
+  let is_valid_proof = shared::verify_proof(proofs.to_vec(), root, leaf.to_bytes());
+  require!(is_valid_proof, ErrorCode::Unauthorized);
+
+  Ok(())
+}
+
+/// Returns the data of a single token merkle node, in version 2 layout when `timestamp` is set
+pub fn redemption_data(index: u16, timestamp: Option<i64>, user: &Pubkey, receiving_amount: u64, sending_amount: u64) -> Vec<u8> {
+  match timestamp {
     Some(timestamp) => { // if timestamp field exists on merkle node
       msg!("Vault V2");
       let redemption_params = RedemptionParamsV2 {
-        index: index,
+        index,
         timestamp,
         address: *user,
-        receiving_amount: receiving_amount,
-        sending_amount: sending_amount,
+        receiving_amount,
+        sending_amount,
       };
       redemption_params.try_to_vec().unwrap()
     },
     None => { // older version of merkle node
       msg!("Old version vault");
       let redemption_params = RedemptionParams {
-        index: index,
+        index,
         address: *user,
-        receiving_amount: receiving_amount,
-        sending_amount: sending_amount,
+        receiving_amount,
+        sending_amount,
       };
       redemption_params.try_to_vec().unwrap()
     }
-  };
-
-  let root: [u8; 32] = schedule.merkle_root.clone().try_into().unwrap();
-  let leaf = hash(&redemption_data[..]);
-  let is_valid_proof = shared::verify_proof(proofs.to_vec(), root, leaf.to_bytes());
-  require!(is_valid_proof, ErrorCode::Unauthorized);
-
-  let user_index: usize = index.into();
-  require!(schedule.redemptions[user_index] == false, ErrorCode::Redeemed);
-
-  Ok(())
+  }
 }
 
-pub fn verify_proof_multi(index: u16, timestamp: Option<i64>, user: &Pubkey, receiving_token_mint: Pubkey, receiving_amount: u64, sending_amount: u64, proofs: &Vec<[u8; 32]>, schedule: &Schedule) -> Result<()> {
-  let redemption_data = match timestamp {
+/// Returns the data of a multi token merkle node, in version 2 layout when `timestamp` is set
+pub fn redemption_multi_data(index: u16, timestamp: Option<i64>, user: &Pubkey, receiving_token_mint: Pubkey, receiving_amount: u64, sending_amount: u64) -> Vec<u8> {
+  match timestamp {
     Some(timestamp) => { // newer version if timestamp field exists on merkle node
       let redemption_params = RedemptionMultiParamsV2 {
-        index: index,
+        index,
         timestamp,
         address: *user,
-        receiving_token_mint: receiving_token_mint,
-        receiving_amount: receiving_amount,
-        sending_amount: sending_amount,
+        receiving_token_mint,
+        receiving_amount,
+        sending_amount,
       };
       redemption_params.try_to_vec().unwrap()
     },
     None => { // older version of merkle node
       let redemption_params = RedemptionMultiParams {
-        index: index,
+        index,
         address: *user,
-        receiving_token_mint: receiving_token_mint,
-        receiving_amount: receiving_amount,
-        sending_amount: sending_amount,
+        receiving_token_mint,
+        receiving_amount,
+        sending_amount,
       };
       redemption_params.try_to_vec().unwrap()
     }
+  }
+}
+
+pub fn verify_proof_epoch(epoch: u64, user: &Pubkey, cumulative_amount: u64, proofs: &[[u8; 32]], schedule: &Schedule) -> Result<()> {
+  let redemption_params = RedemptionEpochParams {
+    epoch,
+    address: *user,
+    cumulative_amount,
+  };
+  let redemption_data = redemption_params.try_to_vec().unwrap();
+
+  let root: [u8; 32] = schedule.merkle_root.clone().try_into().unwrap();
+  let leaf = hash(&redemption_data[..]);
+  let is_valid_proof = shared::verify_proof(proofs.to_vec(), root, leaf.to_bytes());
+  require!(is_valid_proof, ErrorCode::Unauthorized);
+
+  Ok(())
+}
+
+/// Returns an error unless the instruction before the current one is an ed25519 program
+/// instruction verifying the signature of `voucher` by `voucher_signer`
+pub fn verify_voucher(instructions: &AccountInfo, voucher_signer: &Pubkey, voucher: &Voucher) -> Result<()> {
+  let current_index = load_current_index_checked(instructions)?;
+  require!(current_index > 0, ErrorCode::InvalidSignature);
+  let instruction = load_instruction_at_checked(usize::from(current_index - 1), instructions)?;
+
+  let message = voucher.try_to_vec().unwrap();
+  require!(is_ed25519_signature(&instruction, voucher_signer, &message), ErrorCode::InvalidSignature);
+
+  Ok(())
+}
+
+pub fn verify_proof_nft(index: u16, timestamp: i64, user: &Pubkey, asset: &Pubkey, edition: u64, proofs: &[[u8; 32]], schedule: &Schedule) -> Result<()> {
+  // checked before the proof, whose leaf may be valid for an index past user_count
+  let is_redeemed = schedule.is_redeemed(index).ok_or(ErrorCode::IndexOutOfRange)?;
+
+  let redemption_params = RedemptionNftParams {
+    index,
+    timestamp,
+    address: *user,
+    asset: *asset,
+    edition,
   };
+  let redemption_data = redemption_params.try_to_vec().unwrap();
 
   let root: [u8; 32] = schedule.merkle_root.clone().try_into().unwrap();
   let leaf = hash(&redemption_data[..]);
   let is_valid_proof = shared::verify_proof(proofs.to_vec(), root, leaf.to_bytes());
   require!(is_valid_proof, ErrorCode::Unauthorized);
 
-  let user_index: usize = index.into();
-  require!(schedule.redemptions[user_index] == false, ErrorCode::Redeemed);
+  require!(!is_redeemed, ErrorCode::Redeemed);
 
   Ok(())
 }
+
@@ -11,7 +11,7 @@ pub struct DeriveEventIdParam {
 
 pub fn derive_event_id(event_id: u64) -> [u8; 8] {
   let data = DeriveEventIdParam {
-    event_id: event_id,
+    event_id,
   };
   let vec = data.try_to_vec().unwrap();
   let arr: [u8; 8] = vec.try_into().unwrap();
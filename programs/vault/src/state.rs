@@ -1,4 +1,20 @@
 use anchor_lang::prelude::*;
+use anchor_lang::{
+  Discriminator,
+};
+use std::{
+  convert::{
+    TryFrom,
+  },
+};
+
+use crate::constant::{
+  ALLOWED_MINTS_MAX,
+  BPS_DENOMINATOR,
+  DESTINATIONS_MAX,
+  STAKE_ACCOUNTS_MAX,
+  TRANCHES_MAX,
+};
 
 #[repr(u8)]
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
@@ -6,6 +22,21 @@ pub enum ObjType {
   Distribution = 2u8,
   DistributionMulti = 3u8,
   Vault = 1u8,
+  VaultReserve = 4u8,
+  VaultFee = 5u8,
+  VaultSwap = 6u8,
+  ScheduleStake = 7u8,
+  DistributionNft = 8u8,
+  ScheduleMint = 9u8,
+  DistributionVoucher = 10u8,
+  ScheduleVoucher = 11u8,
+  ScheduleTranche = 12u8,
+  DistributionEpoch = 13u8,
+  ScheduleEpoch = 14u8,
+  EpochClaim = 15u8,
+  DistributionBitmap = 16u8,
+  VaultAllowlist = 17u8,
+  VaultDestinations = 18u8,
 }
 
 #[account]
@@ -24,11 +55,77 @@ pub struct Schedule {
   pub sending_token_account: Pubkey,
   pub is_active: bool,
   pub redemptions: Vec<bool>,
+  // version: Layout version, 0 for schedules created before the field, see Schedule::try_deserialize_versioned
+  pub version: u8,
+  // verify_user_token_owner: Whether redeem_token and redeem_token_multi require user_token0 to be owned by the user
+  pub verify_user_token_owner: bool,
+  // user_count: Redemptions proven against merkle_root, the following ones belong to the tranches of the schedule
+  pub user_count: u16,
+}
+
+/// Layout of `Schedule` before `version`
+#[derive(AnchorDeserialize)]
+struct ScheduleV0 {
+  obj_type: ObjType,
+  nonce: u8,
+  event_id: u64,
+  vault_id: Pubkey,
+  timestamp: i64,
+  merkle_root: Vec<u8>,
+  receiving_token_mint: Pubkey,
+  receiving_token_account: Pubkey,
+  sending_token_mint: Pubkey,
+  sending_token_account: Pubkey,
+  is_active: bool,
+  redemptions: Vec<bool>,
 }
 
 impl Schedule {
   pub fn size(user_count: u16) -> usize {
-    1 + 1 + 8 + 32 + 8 + 36 + 32 + 32 + 32 + 32 + 1 + (4 + usize::from(user_count))
+    1 + 1 + 8 + 32 + 8 + 36 + 32 + 32 + 32 + 32 + 1 + (4 + usize::from(user_count)) + 1 + 1 + 2
+  }
+
+  /// Decodes a schedule in the current layout or the one before `version`, see
+  /// `Vault::try_deserialize_versioned`.
+  pub fn try_deserialize_versioned(data: &[u8]) -> Result<Self> {
+    let mut buf = versioned_account_data::<Self>(data)?;
+    let schedule = ScheduleV0::deserialize(&mut buf)
+      .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotDeserialize)?;
+    let size = 16 + Self::size(u16::try_from(schedule.redemptions.len()).unwrap_or(u16::MAX));
+    let version = versioned_account_version(data, buf, size);
+    // legacy schedules have no tranches
+    let user_count = match buf.get(2..4) {
+      Some(user_count) if version > 0 => u16::from_le_bytes([user_count[0], user_count[1]]),
+      _ => u16::try_from(schedule.redemptions.len()).unwrap_or(u16::MAX),
+    };
+
+    Ok(Schedule {
+      obj_type: schedule.obj_type,
+      nonce: schedule.nonce,
+      event_id: schedule.event_id,
+      vault_id: schedule.vault_id,
+      timestamp: schedule.timestamp,
+      merkle_root: schedule.merkle_root,
+      receiving_token_mint: schedule.receiving_token_mint,
+      receiving_token_account: schedule.receiving_token_account,
+      sending_token_mint: schedule.sending_token_mint,
+      sending_token_account: schedule.sending_token_account,
+      is_active: schedule.is_active,
+      redemptions: schedule.redemptions,
+      version,
+      verify_user_token_owner: version > 0 && buf.get(1).is_some_and(|value| *value != 0),
+      user_count,
+    })
+  }
+
+  pub fn is_redeemed(&self, index: u16) -> Option<bool> {
+    self.redemptions.get(usize::from(index)).copied()
+  }
+
+  /// Whether redemptions were appended by `append_schedule_tranche`, which legacy layouts
+  /// do not support
+  pub fn has_tranches(&self) -> bool {
+    self.version > 0 && self.redemptions.len() > usize::from(self.user_count)
   }
 }
 
@@ -39,14 +136,366 @@ pub struct Vault {
   pub owner: Pubkey,
   pub new_owner: Pubkey,
   pub admins: Vec<Pubkey>,
+  // version: Layout version, 0 for vaults created before the field, see Vault::try_deserialize_versioned
+  pub version: u8,
+}
+
+/// Layout of `Vault` before `version`
+#[derive(AnchorDeserialize)]
+struct VaultV0 {
+  obj_type: ObjType,
+  signer_nonce: u8,
+  owner: Pubkey,
+  new_owner: Pubkey,
+  admins: Vec<Pubkey>,
 }
 
 impl Vault {
   pub fn size() -> usize {
-    1 + 1 + 32 + 32 + (4 + 32 * 16)
+    1 + 1 + 32 + 32 + (4 + 32 * 16) + 1
+  }
+
+  /// Decodes a vault in the current layout or the one before `version`. Accounts allocated
+  /// before the field decode as version 0: the byte following `admins` is either missing or
+  /// left over from a longer admin list, so `version` is only read from accounts allocated
+  /// with the current `Vault::size`.
+  pub fn try_deserialize_versioned(data: &[u8]) -> Result<Self> {
+    let mut buf = versioned_account_data::<Self>(data)?;
+    let vault = VaultV0::deserialize(&mut buf)
+      .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotDeserialize)?;
+
+    Ok(Vault {
+      obj_type: vault.obj_type,
+      signer_nonce: vault.signer_nonce,
+      owner: vault.owner,
+      new_owner: vault.new_owner,
+      admins: vault.admins,
+      version: versioned_account_version(data, buf, 16 + Self::size()),
+    })
+  }
+}
+
+/// Returns the account data following the discriminator of `T`
+fn versioned_account_data<T: Discriminator>(data: &[u8]) -> Result<&[u8]> {
+  require!(data.len() >= 8, anchor_lang::error::ErrorCode::AccountDiscriminatorNotFound);
+  require!(data[..8] == T::DISCRIMINATOR, anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch);
+  Ok(&data[8..])
+}
+
+/// Returns the version byte at the start of `remaining`, or 0 if `data` is shorter than the
+/// allocation of the current layout
+fn versioned_account_version(data: &[u8], remaining: &[u8], size: usize) -> u8 {
+  if data.len() < size {
+    return 0;
+  }
+  remaining.first().copied().unwrap_or(0)
+}
+
+#[account]
+pub struct VaultReserve {
+  pub obj_type: ObjType,
+  pub nonce: u8,
+  pub vault_id: Pubkey,
+  // sol_reserve: Lamports the vault signer must keep on top of its rent exemption
+  pub sol_reserve: u64,
+  // sol_liabilities: Lamports owed to users by SOL schedules, settled on redemption
+  pub sol_liabilities: u64,
+}
+
+impl VaultReserve {
+  pub fn size() -> usize {
+    1 + 1 + 32 + 8 + 8
+  }
+}
+
+#[account]
+pub struct VaultFee {
+  pub obj_type: ObjType,
+  pub nonce: u8,
+  pub vault_id: Pubkey,
+  // treasury: Wallet receiving the claim fees, token fees go to its token accounts
+  pub treasury: Pubkey,
+  // fee_lamports: Flat SOL fee paid by the user on each redemption
+  pub fee_lamports: u64,
+  // fee_bps: Share of receiving_amount withheld from the user, in basis points
+  pub fee_bps: u16,
+}
+
+impl VaultFee {
+  pub fn size() -> usize {
+    1 + 1 + 32 + 32 + 8 + 2
+  }
+
+  /// Part of `receiving_amount` paid to the treasury instead of the user
+  pub fn token_fee(&self, receiving_amount: u64) -> u64 {
+    let fee = u128::from(receiving_amount) * u128::from(self.fee_bps) / u128::from(BPS_DENOMINATOR);
+    fee as u64
+  }
+}
+
+#[account]
+pub struct VaultSwap {
+  pub obj_type: ObjType,
+  pub nonce: u8,
+  pub vault_id: Pubkey,
+  // swap_program: DEX program redeem_token_swap is allowed to route through
+  pub swap_program: Pubkey,
+}
+
+impl VaultSwap {
+  pub fn size() -> usize {
+    1 + 1 + 32 + 32
+  }
+}
+
+#[account]
+pub struct VaultAllowlist {
+  pub obj_type: ObjType,
+  pub nonce: u8,
+  pub vault_id: Pubkey,
+  // mints: Tokens the vault may deposit, distribute and withdraw, any token when empty
+  pub mints: Vec<Pubkey>,
+}
+
+impl VaultAllowlist {
+  pub fn size() -> usize {
+    1 + 1 + 32 + (4 + 32 * ALLOWED_MINTS_MAX)
+  }
+
+  pub fn is_allowed(&self, mint: &Pubkey) -> bool {
+    self.mints.is_empty() || self.mints.contains(mint)
+  }
+}
+
+#[account]
+pub struct VaultDestinations {
+  pub obj_type: ObjType,
+  pub nonce: u8,
+  pub vault_id: Pubkey,
+  // destinations: Wallets withdraw_sol and withdraw_token may send to
+  pub destinations: Vec<WithdrawalDestination>,
+}
+
+impl VaultDestinations {
+  pub fn size() -> usize {
+    1 + 1 + 32 + (4 + WithdrawalDestination::size() * DESTINATIONS_MAX)
+  }
+
+  /// Whether `address` is a destination whose timelock ended at `timestamp`
+  pub fn is_allowed(&self, address: &Pubkey, timestamp: i64) -> bool {
+    self.destinations.iter()
+      .any(|destination| destination.address == *address && destination.timestamp <= timestamp)
+  }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct WithdrawalDestination {
+  pub address: Pubkey,
+  // timestamp: Time from which withdrawals to the address are allowed
+  pub timestamp: i64,
+}
+
+impl WithdrawalDestination {
+  pub fn size() -> usize {
+    32 + 8
+  }
+}
+
+#[account]
+pub struct ScheduleStake {
+  pub obj_type: ObjType,
+  pub nonce: u8,
+  pub schedule_id: Pubkey,
+  // stake_program: Staking program redeem_token_stake deposits into
+  pub stake_program: Pubkey,
+  // stake_accounts: Accounts the stake instruction may use besides the user's own accounts
+  pub stake_accounts: Vec<Pubkey>,
+}
+
+impl ScheduleStake {
+  pub fn size() -> usize {
+    1 + 1 + 32 + 32 + (4 + 32 * STAKE_ACCOUNTS_MAX)
+  }
+}
+
+#[account]
+pub struct ScheduleTranche {
+  pub obj_type: ObjType,
+  pub nonce: u8,
+  pub schedule_id: Pubkey,
+  // tranches: Roots appended after the schedule's own merkle_root, in index order
+  pub tranches: Vec<Tranche>,
+}
+
+impl ScheduleTranche {
+  pub fn size() -> usize {
+    1 + 1 + 32 + (4 + Tranche::size() * TRANCHES_MAX)
+  }
+
+  /// Tranche whose index range contains `index`, if any
+  pub fn find_tranche(&self, index: u16) -> Option<&Tranche> {
+    self.tranches.iter()
+      .find(|tranche| tranche.index_start <= index && index < tranche.index_end)
+  }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct Tranche {
+  pub merkle_root: [u8; 32],
+  // index_start..index_end: Range of Schedule.redemptions covered by the tranche
+  pub index_start: u16,
+  pub index_end: u16,
+  // timestamp: Unlock time of the tranche
+  pub timestamp: i64,
+}
+
+impl Tranche {
+  pub fn size() -> usize {
+    32 + 2 + 2 + 8
+  }
+}
+
+#[account]
+pub struct ScheduleMint {
+  pub obj_type: ObjType,
+  pub nonce: u8,
+  pub schedule_id: Pubkey,
+  // mint_cap: Maximum amount the schedule can mint, claim fees included
+  pub mint_cap: u64,
+  pub minted_amount: u64,
+}
+
+impl ScheduleMint {
+  pub fn size() -> usize {
+    1 + 1 + 32 + 8 + 8
+  }
+}
+
+#[account]
+pub struct ScheduleVoucher {
+  pub obj_type: ObjType,
+  pub nonce: u8,
+  pub schedule_id: Pubkey,
+  // voucher_signer: Key signing the vouchers of the schedule
+  pub voucher_signer: Pubkey,
+  // nonces: Bitmap of the voucher nonces already redeemed
+  pub nonces: Vec<u8>,
+}
+
+impl ScheduleVoucher {
+  pub fn size(nonce_count: u32) -> usize {
+    1 + 1 + 32 + 32 + (4 + Self::bitmap_len(nonce_count))
+  }
+
+  pub fn bitmap_len(nonce_count: u32) -> usize {
+    (nonce_count as usize).div_ceil(8)
+  }
+
+  pub fn is_nonce_used(&self, nonce: u32) -> Option<bool> {
+    let byte = self.nonces.get(nonce as usize / 8)?;
+    Some(byte & (1 << (nonce % 8)) != 0)
+  }
+
+  pub fn use_nonce(&mut self, nonce: u32) {
+    self.nonces[nonce as usize / 8] |= 1 << (nonce % 8);
+  }
+}
+
+// Header of a Distribution schedule loaded without deserialization, followed in the account
+// by a bitmap of its redemptions. Fields are ordered to leave no padding.
+#[account(zero_copy)]
+pub struct BitmapSchedule {
+  pub event_id: u64,
+  pub timestamp: i64,
+  pub vault_id: Pubkey,
+  pub merkle_root: [u8; 32],
+  pub receiving_token_mint: Pubkey,
+  pub receiving_token_account: Pubkey,
+  pub sending_token_mint: Pubkey,
+  pub sending_token_account: Pubkey,
+  pub user_count: u16,
+  pub obj_type: u8,
+  pub nonce: u8,
+  pub is_active: u8,
+  pub version: u8,
+  // verify_user_token_owner: Whether redeem_token_bitmap requires user_token0 to be owned by the user
+  pub verify_user_token_owner: u8,
+  pub padding: [u8; 1],
+}
+
+impl BitmapSchedule {
+  pub const LEN: usize = std::mem::size_of::<BitmapSchedule>();
+
+  pub fn size(user_count: u16) -> usize {
+    Self::LEN + Self::bitmap_len(user_count)
+  }
+
+  pub fn bitmap_len(user_count: u16) -> usize {
+    usize::from(user_count).div_ceil(8)
+  }
+
+  /// Returns whether `index` is redeemed in `bitmap`, None past `user_count`
+  pub fn is_redeemed(&self, bitmap: &[u8], index: u16) -> Option<bool> {
+    if index >= self.user_count {
+      return None;
+    }
+    let byte = bitmap.get(usize::from(index) / 8)?;
+    Some(byte & (1 << (index % 8)) != 0)
+  }
+
+  pub fn set_redeemed(bitmap: &mut [u8], index: u16) {
+    bitmap[usize::from(index) / 8] |= 1 << (index % 8);
+  }
+
+  /// Decodes the raw data of a `BitmapSchedule` account, discriminator included, into its
+  /// header and bitmap.
+  pub fn try_from_account_data(data: &[u8]) -> Result<(Self, &[u8])> {
+    let data = versioned_account_data::<Self>(data)?;
+    require!(data.len() >= Self::LEN, anchor_lang::error::ErrorCode::AccountDidNotDeserialize);
+    let schedule: Self = bytemuck::pod_read_unaligned(&data[..Self::LEN]);
+    let bitmap_len = Self::bitmap_len(schedule.user_count).min(data.len() - Self::LEN);
+    Ok((schedule, &data[Self::LEN..Self::LEN + bitmap_len]))
+  }
+}
+
+#[account]
+pub struct ScheduleEpoch {
+  pub obj_type: ObjType,
+  pub nonce: u8,
+  pub schedule_id: Pubkey,
+  // epoch: Epoch of the schedule's current merkle_root, 0 until the first root is posted
+  pub epoch: u64,
+}
+
+impl ScheduleEpoch {
+  pub fn size() -> usize {
+    1 + 1 + 32 + 8
+  }
+}
+
+#[account]
+pub struct EpochClaim {
+  pub obj_type: ObjType,
+  pub nonce: u8,
+  pub schedule_id: Pubkey,
+  pub user: Pubkey,
+  // claimed_amount: Total paid out to the user, fees included, over all epochs
+  pub claimed_amount: u64,
+}
+
+impl EpochClaim {
+  pub fn size() -> usize {
+    1 + 1 + 32 + 32 + 8
   }
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct ClaimStatus {
+  pub index: u16,
+  pub is_active: bool,
+  pub is_redeemed: bool,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Default)]
 pub struct RedemptionParams {
   pub index: u16,
@@ -82,3 +531,32 @@ pub struct RedemptionMultiParamsV2 {
   pub receiving_amount: u64,
   pub sending_amount: u64,
 }
+
+#[derive(AnchorSerialize, AnchorDeserialize, Default)]
+pub struct RedemptionNftParams {
+  pub index: u16,
+  pub timestamp: i64,
+  pub address: Pubkey,
+  // asset: Mint of the NFT, master edition mint to print from or compressed NFT asset id
+  pub asset: Pubkey,
+  // edition: Edition number to print from the master edition, 0 to transfer the asset itself
+  pub edition: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Default)]
+pub struct RedemptionEpochParams {
+  pub epoch: u64,
+  pub address: Pubkey,
+  // cumulative_amount: Total entitlement of the user up to and including the epoch
+  pub cumulative_amount: u64,
+}
+
+/// Message signed by the voucher signer of a schedule, Borsh serialized
+#[derive(AnchorSerialize, AnchorDeserialize, Default)]
+pub struct Voucher {
+  pub schedule: Pubkey,
+  pub user: Pubkey,
+  pub amount: u64,
+  pub nonce: u32,
+  pub expiry: i64,
+}
@@ -0,0 +1,233 @@
+mod common;
+
+use solana_program::{
+  instruction::{
+    Instruction,
+  },
+  pubkey::{
+    Pubkey,
+  },
+  system_program,
+};
+use solana_sdk::{
+  signature::{
+    Keypair,
+    Signer,
+  },
+};
+use vault::error::{
+  ErrorCode,
+};
+use vault::state::{
+  BitmapSchedule,
+};
+use vault_client::account::{
+  decode_bitmap_schedule,
+};
+use vault_client::instruction::{
+  self as vault_instruction,
+  RedeemOptions,
+};
+use vault_client::merkle::{
+  redemption_leaf,
+  MerkleTree,
+};
+use vault_client::pda::{
+  find_schedule_address,
+};
+
+use common::{
+  assert_anchor_error,
+  assert_vault_error,
+  merkle_schedule,
+  recipients,
+  VaultTest,
+  PROGRAM_ID,
+};
+
+const EVENT_ID: u64 = 1;
+
+/// `BitmapSchedule` of `merkle_tree`, paying out of `vault_token`.
+struct BitmapDistribution {
+  vault: Pubkey,
+  schedule: Pubkey,
+  vault_token: Pubkey,
+  merkle_tree: MerkleTree,
+}
+
+impl BitmapDistribution {
+  async fn new(test: &mut VaultTest, user_count: u16, merkle_tree: MerkleTree, mint: &Pubkey, vault_token: &Pubkey) -> Self {
+    let admin = test.admin.insecure_clone();
+    let instruction = vault_instruction::create_bitmap_schedule(
+      &admin.pubkey(),
+      &test.vault,
+      user_count,
+      EVENT_ID,
+      1,
+      merkle_tree.root(),
+      mint,
+      vault_token,
+      &system_program::ID,
+      &system_program::ID,
+      &PROGRAM_ID,
+    );
+    test.process(&[instruction], &[&admin]).await.unwrap();
+
+    BitmapDistribution {
+      vault: test.vault,
+      schedule: find_schedule_address(EVENT_ID, &PROGRAM_ID).0,
+      vault_token: *vault_token,
+      merkle_tree,
+    }
+  }
+
+  /// Redeems `amount` at `index` with the proof of leaf `position`, without fee or sending token.
+  fn redeem(&self, index: u16, position: usize, amount: u64, user: &Pubkey, user_token: &Pubkey) -> Instruction {
+    vault_instruction::redeem_token_bitmap(
+      &self.vault,
+      &self.schedule,
+      index,
+      0,
+      self.merkle_tree.proof(position),
+      amount,
+      0,
+      &self.vault_token,
+      user,
+      user_token,
+      &RedeemOptions::default(),
+      &PROGRAM_ID,
+    )
+  }
+}
+
+#[tokio::test]
+async fn redeem_token_bitmap_sets_a_single_bit() {
+  let mut test = VaultTest::new().await;
+  let vault_signer = test.vault_signer;
+  let mint = test.create_mint(&Pubkey::new_unique(), 6).await;
+  let vault_token = test.create_token_account(&mint, &vault_signer, 1_000).await;
+
+  // the last index of the largest schedule, alone in its tree
+  let user = Keypair::new();
+  let index = u16::MAX - 1;
+  let merkle_tree = MerkleTree::new(vec![redemption_leaf(index, None, &user.pubkey(), 100, 0)]);
+  let distribution = BitmapDistribution::new(&mut test, u16::MAX, merkle_tree, &mint, &vault_token).await;
+  let account = test.account(&distribution.schedule).await.unwrap();
+  assert_eq!(account.data.len(), 16 + BitmapSchedule::size(u16::MAX));
+  let (schedule, bitmap) = decode_bitmap_schedule(&account.data).unwrap();
+  assert_eq!(schedule.user_count, u16::MAX);
+  assert_eq!(schedule.vault_id, test.vault);
+  assert_eq!(schedule.version, vault::constant::SCHEDULE_VERSION);
+  assert!(bitmap.iter().all(|byte| *byte == 0));
+
+  let user_token = test.create_token_account(&mint, &user.pubkey(), 0).await;
+  let instruction = distribution.redeem(index, 0, 100, &user.pubkey(), &user_token);
+  test.process(std::slice::from_ref(&instruction), &[&user]).await.unwrap();
+  assert_eq!(test.token_balance(&user_token).await, 100);
+  assert_eq!(test.token_balance(&vault_token).await, 900);
+
+  let (schedule, bitmap) = test.decode(&distribution.schedule, decode_bitmap_schedule).await;
+  assert_eq!(schedule.is_redeemed(&bitmap, index), Some(true));
+  assert_eq!(schedule.is_redeemed(&bitmap, index - 1), Some(false));
+  assert_eq!(bitmap.iter().filter(|byte| **byte != 0).count(), 1);
+
+  test.refresh_blockhash().await;
+  let result = test.process(&[instruction], &[&user]).await;
+  assert_vault_error(result, ErrorCode::Redeemed);
+}
+
+#[tokio::test]
+async fn redeem_token_bitmap_checks_index_and_status() {
+  let mut test = VaultTest::new().await;
+  let admin = test.admin.insecure_clone();
+  let vault_signer = test.vault_signer;
+  let mint = test.create_mint(&Pubkey::new_unique(), 6).await;
+  let vault_token = test.create_token_account(&mint, &vault_signer, 1_000).await;
+  let recipients = recipients(&[100, 200]);
+  let distribution = BitmapDistribution::new(&mut test, 1, merkle_schedule(&recipients), &mint, &vault_token).await;
+
+  // the second leaf is valid but past user_count
+  let user = recipients[1].user.insecure_clone();
+  let user_token = test.create_token_account(&mint, &user.pubkey(), 0).await;
+  let instruction = distribution.redeem(1, 1, 200, &user.pubkey(), &user_token);
+  let result = test.process(&[instruction], &[&user]).await;
+  assert_vault_error(result, ErrorCode::IndexOutOfRange);
+
+  let user = recipients[0].user.insecure_clone();
+  let user_token = test.create_token_account(&mint, &user.pubkey(), 0).await;
+  let instruction = distribution.redeem(0, 0, 101, &user.pubkey(), &user_token);
+  let result = test.process(&[instruction], &[&user]).await;
+  assert_vault_error(result, ErrorCode::Unauthorized);
+
+  let instruction = vault_instruction::set_bitmap_schedule_status(&admin.pubkey(), &test.vault, &distribution.schedule, false, &PROGRAM_ID);
+  test.process(&[instruction], &[&admin]).await.unwrap();
+  let instruction = distribution.redeem(0, 0, 100, &user.pubkey(), &user_token);
+  let result = test.process(std::slice::from_ref(&instruction), &[&user]).await;
+  assert_vault_error(result, ErrorCode::ScheduleUnavailable);
+
+  let instruction_status = vault_instruction::set_bitmap_schedule_status(&admin.pubkey(), &test.vault, &distribution.schedule, true, &PROGRAM_ID);
+  test.process(&[instruction_status], &[&admin]).await.unwrap();
+  test.refresh_blockhash().await;
+  test.process(&[instruction], &[&user]).await.unwrap();
+  assert_eq!(test.token_balance(&user_token).await, 100);
+
+  // the bitmap layout is not a Schedule
+  let instruction = vault_instruction::set_schedule_status(&admin.pubkey(), &test.vault, &distribution.schedule, false, &PROGRAM_ID);
+  let result = test.process(&[instruction], &[&admin]).await;
+  assert_anchor_error(result, anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch);
+}
+
+#[tokio::test]
+async fn redeem_token_bitmap_checks_user_token_owner() {
+  let mut test = VaultTest::new().await;
+  let admin = test.admin.insecure_clone();
+  let vault_signer = test.vault_signer;
+  let mint = test.create_mint(&Pubkey::new_unique(), 6).await;
+  let vault_token = test.create_token_account(&mint, &vault_signer, 1_000).await;
+  let recipients = recipients(&[100, 200]);
+  let distribution = BitmapDistribution::new(&mut test, 2, merkle_schedule(&recipients), &mint, &vault_token).await;
+  let other_token = test.create_token_account(&mint, &Pubkey::new_unique(), 0).await;
+
+  // without the owner check any account of the receiving mint is paid
+  let user = recipients[0].user.insecure_clone();
+  let instruction = distribution.redeem(0, 0, 100, &user.pubkey(), &other_token);
+  test.process(&[instruction], &[&user]).await.unwrap();
+  assert_eq!(test.token_balance(&other_token).await, 100);
+
+  let instruction = vault_instruction::set_bitmap_schedule_owner_check(&admin.pubkey(), &test.vault, &distribution.schedule, true, &PROGRAM_ID);
+  test.process(&[instruction], &[&admin]).await.unwrap();
+  let (schedule, _) = test.decode(&distribution.schedule, decode_bitmap_schedule).await;
+  assert_eq!(schedule.verify_user_token_owner, 1);
+
+  let user = recipients[1].user.insecure_clone();
+  let instruction = distribution.redeem(1, 1, 200, &user.pubkey(), &other_token);
+  let result = test.process(&[instruction], &[&user]).await;
+  assert_vault_error(result, ErrorCode::InvalidTokenOwner);
+}
+
+#[tokio::test]
+async fn create_bitmap_schedule_rejects_mint_on_claim() {
+  let mut test = VaultTest::new().await;
+  let admin = test.admin.insecure_clone();
+  let vault_signer = test.vault_signer;
+  let mint = test.create_mint(&vault_signer, 6).await;
+  let vault_token = test.create_token_account(&mint, &vault_signer, 0).await;
+
+  for (user_count, vault_token, error) in [(0, vault_token, ErrorCode::InvalidInput), (1, mint, ErrorCode::InvalidInput)] {
+    let instruction = vault_instruction::create_bitmap_schedule(
+      &admin.pubkey(),
+      &test.vault,
+      user_count,
+      EVENT_ID,
+      1,
+      [0u8; 32],
+      &mint,
+      &vault_token,
+      &system_program::ID,
+      &system_program::ID,
+      &PROGRAM_ID,
+    );
+    let result = test.process(&[instruction], &[&admin]).await;
+    assert_vault_error(result, error);
+  }
+}
@@ -0,0 +1,486 @@
+//! Shared setup of the program-test suite. `coin98_vault` and the mock AMM run as native
+//! builtins next to the SPL programs bundled with solana-program-test.
+//!
+//! A panicking builtin hangs the bank, so negative tests must fail on a program check and
+//! never on a CPI the program unwraps.
+
+#![allow(dead_code)]
+
+use anchor_lang::{
+  InstructionData,
+  ToAccountMetas,
+};
+use solana_program::{
+  account_info::{
+    AccountInfo,
+  },
+  clock::{
+    Clock,
+  },
+  entrypoint::{
+    ProgramResult,
+  },
+  instruction::{
+    Instruction,
+    InstructionError,
+  },
+  native_token::{
+    LAMPORTS_PER_SOL,
+  },
+  program_option::{
+    COption,
+  },
+  program_pack::{
+    Pack,
+  },
+  pubkey::{
+    Pubkey,
+  },
+  rent::{
+    Rent,
+  },
+  system_instruction,
+  system_program,
+};
+use solana_program_test::{
+  find_file,
+  processor,
+  BanksClientError,
+  ProgramTest,
+  ProgramTestContext,
+};
+use solana_sdk::{
+  account::{
+    Account,
+  },
+  signature::{
+    Keypair,
+    Signer,
+  },
+  transaction::{
+    Transaction,
+    TransactionError,
+  },
+};
+use std::{
+  convert::{
+    TryInto,
+  },
+};
+use vault::external::spl_token::{
+  native_mint::ID as NATIVE_MINT_ID,
+  AccountState,
+  TokenAccount,
+  ID as TOKEN_PROGRAM_ID,
+};
+use vault_client::instruction as vault_instruction;
+use vault_client::merkle::{
+  redemption_leaf,
+  MerkleTree,
+};
+use vault_client::pda::{
+  find_schedule_address,
+  find_vault_address,
+  find_vault_derivation_path,
+  find_vault_signer_address,
+};
+
+pub const PROGRAM_ID: Pubkey = vault::ID;
+pub const MINT_LEN: usize = 82;
+pub const VAULT_NAME: &str = "Coin98 Program Test";
+
+fn process_vault_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+  // `entry` ties the slice lifetime to the accounts, which `processor!` does not allow
+  let accounts = Box::leak(Box::new(accounts.to_vec()));
+  vault::entry(program_id, accounts, data)
+}
+
+fn process_mock_amm_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+  let accounts = Box::leak(Box::new(accounts.to_vec()));
+  mock_amm::entry(program_id, accounts, data)
+}
+
+pub fn program_test() -> ProgramTest {
+  let mut program_test = ProgramTest::new("vault", PROGRAM_ID, processor!(process_vault_instruction));
+  program_test.add_program("mock_amm", mock_amm::ID, processor!(process_mock_amm_instruction));
+  program_test.prefer_bpf(false);
+  program_test
+}
+
+/// Program test running the SBF build of `coin98_vault`, so instructions report their real
+/// compute units. `None` unless `vault.so` is found, built by `cargo build-sbf` and found by
+/// `cargo test-sbf`. The mock AMM keeps running natively.
+pub fn sbf_program_test() -> Option<ProgramTest> {
+  find_file("vault.so")?;
+  let mut program_test = ProgramTest::default();
+  program_test.prefer_bpf(true);
+  program_test.add_program("vault", PROGRAM_ID, None);
+  program_test.prefer_bpf(false);
+  program_test.add_program("mock_amm", mock_amm::ID, processor!(process_mock_amm_instruction));
+  Some(program_test)
+}
+
+/// Fixed rate pool of the mock AMM, paying `pool_destination` tokens for `pool_source` ones.
+pub struct MockPool {
+  pub pool: Pubkey,
+  pub pool_authority: Pubkey,
+  pub pool_source: Pubkey,
+  pub pool_destination: Pubkey,
+}
+
+impl MockPool {
+  pub fn swap(&self, user: &Pubkey, user_source: &Pubkey, user_destination: &Pubkey, amount_in: u64, minimum_amount_out: u64) -> Instruction {
+    let accounts = mock_amm::accounts::SwapContext {
+      pool: self.pool,
+      pool_authority: self.pool_authority,
+      user: *user,
+      user_source: *user_source,
+      user_destination: *user_destination,
+      pool_source: self.pool_source,
+      pool_destination: self.pool_destination,
+      token_program: TOKEN_PROGRAM_ID,
+    };
+    let data = mock_amm::instruction::Swap {
+      amount_in,
+      minimum_amount_out,
+    };
+
+    Instruction {
+      program_id: mock_amm::ID,
+      accounts: accounts.to_account_metas(None),
+      data: data.data(),
+    }
+  }
+
+  /// Accounts of `swap` besides the user ones.
+  pub fn accounts(&self) -> Vec<Pubkey> {
+    vec![self.pool, self.pool_authority, self.pool_source, self.pool_destination, TOKEN_PROGRAM_ID]
+  }
+}
+
+/// Recipient of a merkle schedule, see `merkle_schedule`.
+pub struct Recipient {
+  pub user: Keypair,
+  pub receiving_amount: u64,
+  pub sending_amount: u64,
+}
+
+/// Leaves of `recipients` for a schedule created with a non-zero timestamp.
+pub fn merkle_schedule(recipients: &[Recipient]) -> MerkleTree {
+  let leaves = recipients.iter()
+    .enumerate()
+    .map(|(index, recipient)| {
+      redemption_leaf(index as u16, None, &recipient.user.pubkey(), recipient.receiving_amount, recipient.sending_amount)
+    })
+    .collect();
+  MerkleTree::new(leaves)
+}
+
+pub fn recipients(amounts: &[u64]) -> Vec<Recipient> {
+  amounts.iter()
+    .map(|amount| Recipient {
+      user: Keypair::new(),
+      receiving_amount: *amount,
+      sending_amount: 0,
+    })
+    .collect()
+}
+
+/// Vault created by `owner`, with `admin` as its only admin.
+pub struct VaultTest {
+  pub context: ProgramTestContext,
+  pub owner: Keypair,
+  pub admin: Keypair,
+  pub vault: Pubkey,
+  pub vault_signer: Pubkey,
+}
+
+impl VaultTest {
+  pub async fn new() -> Self {
+    Self::with_program_test(program_test()).await
+  }
+
+  pub async fn with_program_test(program_test: ProgramTest) -> Self {
+    let context = program_test.start_with_context().await;
+    let (vault, _) = find_vault_address(&find_vault_derivation_path(VAULT_NAME), &PROGRAM_ID);
+    let (vault_signer, _) = find_vault_signer_address(&vault, &PROGRAM_ID);
+    let mut test = VaultTest {
+      context,
+      owner: Keypair::new(),
+      admin: Keypair::new(),
+      vault,
+      vault_signer,
+    };
+
+    let owner = test.owner.pubkey();
+    let admin = test.admin.pubkey();
+    test.airdrop(&owner, 10 * LAMPORTS_PER_SOL);
+    test.airdrop(&admin, 10 * LAMPORTS_PER_SOL);
+
+    let instructions = [
+      vault_instruction::create_vault(&owner, find_vault_derivation_path(VAULT_NAME), &PROGRAM_ID),
+      vault_instruction::set_vault(&owner, &vault, vec![admin], &PROGRAM_ID),
+    ];
+    let owner = test.owner.insecure_clone();
+    test.process(&instructions, &[&owner]).await.unwrap();
+
+    test
+  }
+
+  /// Creates a `Distribution` schedule of `recipients` paying out of `vault_token`, which
+  /// holds `receiving_token_mint`.
+  pub async fn create_schedule(
+    &mut self,
+    event_id: u64,
+    timestamp: i64,
+    recipients: &[Recipient],
+    receiving_token_mint: &Pubkey,
+    vault_token: &Pubkey,
+  ) -> Pubkey {
+    let admin = self.admin.insecure_clone();
+    let merkle_tree = merkle_schedule(recipients);
+    let instruction = vault_instruction::create_schedule(
+      &admin.pubkey(),
+      &self.vault,
+      recipients.len() as u16,
+      event_id,
+      timestamp,
+      merkle_tree.root(),
+      false,
+      receiving_token_mint,
+      vault_token,
+      &system_program::ID,
+      &system_program::ID,
+      &PROGRAM_ID,
+    );
+    self.process(&[instruction], &[&admin]).await.unwrap();
+    find_schedule_address(event_id, &PROGRAM_ID).0
+  }
+
+  /// Creates a mock AMM pool swapping `source_mint` for `destination_mint` at
+  /// `rate_numerator / rate_denominator`, holding `liquidity` destination tokens.
+  pub async fn create_pool(
+    &mut self,
+    source_mint: &Pubkey,
+    destination_mint: &Pubkey,
+    rate_numerator: u64,
+    rate_denominator: u64,
+    liquidity: u64,
+  ) -> MockPool {
+    let pool_source = Pubkey::new_unique();
+    let pool_destination = Pubkey::new_unique();
+    let (pool, _) = Pubkey::find_program_address(
+      &[
+        mock_amm::POOL_SEED,
+        pool_source.as_ref(),
+        pool_destination.as_ref(),
+      ],
+      &mock_amm::ID,
+    );
+    let (pool_authority, _) = Pubkey::find_program_address(
+      &[
+        mock_amm::AUTHORITY_SEED,
+        pool.as_ref(),
+      ],
+      &mock_amm::ID,
+    );
+    self.set_token_account(&pool_source, source_mint, &pool_authority, 0).await;
+    self.set_token_account(&pool_destination, destination_mint, &pool_authority, liquidity).await;
+
+    let accounts = mock_amm::accounts::InitializePoolContext {
+      payer: self.context.payer.pubkey(),
+      pool,
+      pool_authority,
+      pool_source,
+      pool_destination,
+      system_program: system_program::ID,
+    };
+    let data = mock_amm::instruction::InitializePool {
+      rate_numerator,
+      rate_denominator,
+    };
+    let instruction = Instruction {
+      program_id: mock_amm::ID,
+      accounts: accounts.to_account_metas(None),
+      data: data.data(),
+    };
+    self.process(&[instruction], &[]).await.unwrap();
+
+    MockPool {
+      pool,
+      pool_authority,
+      pool_source,
+      pool_destination,
+    }
+  }
+
+  /// Processes `instructions` paid by the test payer. Identical transactions are rejected by
+  /// the bank, call `refresh_blockhash` before sending the same instructions again.
+  pub async fn process(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<(), BanksClientError> {
+    let transaction = self.transaction(instructions, signers);
+    self.context.banks_client.process_transaction(transaction).await
+  }
+
+  /// Processes `transaction` and returns the compute units it consumed. Native builtins
+  /// consume a fixed amount, see `sbf_program_test`.
+  pub async fn process_metered(&mut self, transaction: Transaction) -> Result<u64, BanksClientError> {
+    let result = self.context.banks_client.process_transaction_with_metadata(transaction).await?;
+    result.result.map_err(BanksClientError::TransactionError)?;
+    Ok(result.metadata.map_or(0, |metadata| metadata.compute_units_consumed))
+  }
+
+  /// Returns `instructions` signed by the test payer and `signers` with the last blockhash.
+  pub fn transaction(&self, instructions: &[Instruction], signers: &[&Keypair]) -> Transaction {
+    let mut all_signers = vec![&self.context.payer];
+    all_signers.extend_from_slice(signers);
+    Transaction::new_signed_with_payer(
+      instructions,
+      Some(&self.context.payer.pubkey()),
+      &all_signers,
+      self.context.last_blockhash,
+    )
+  }
+
+  /// Simulates `instruction` and returns its return data.
+  pub async fn simulate(&mut self, instruction: Instruction) -> Vec<u8> {
+    let transaction = Transaction::new_signed_with_payer(
+      &[instruction],
+      Some(&self.context.payer.pubkey()),
+      &[&self.context.payer],
+      self.context.last_blockhash,
+    );
+    let result = self.context.banks_client.simulate_transaction(transaction).await.unwrap();
+    result.result.unwrap().unwrap();
+    result.simulation_details.unwrap().return_data.map_or(Vec::new(), |return_data| return_data.data)
+  }
+
+  pub async fn refresh_blockhash(&mut self) {
+    self.context.get_new_latest_blockhash().await.unwrap();
+  }
+
+  pub async fn set_unix_timestamp(&mut self, unix_timestamp: i64) {
+    let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp = unix_timestamp;
+    self.context.set_sysvar(&clock);
+  }
+
+  pub async fn unix_timestamp(&mut self) -> i64 {
+    let clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp
+  }
+
+  pub fn airdrop(&mut self, address: &Pubkey, lamports: u64) {
+    let account = Account::new(lamports, 0, &system_program::ID);
+    self.context.set_account(address, &account.into());
+  }
+
+  /// Sends `lamports` from the payer to `address`, keeping its existing data.
+  pub async fn transfer_lamports(&mut self, address: &Pubkey, lamports: u64) {
+    let instruction = system_instruction::transfer(&self.context.payer.pubkey(), address, lamports);
+    self.process(&[instruction], &[]).await.unwrap();
+  }
+
+  pub async fn account(&mut self, address: &Pubkey) -> Option<Account> {
+    self.context.banks_client.get_account(*address).await.unwrap()
+  }
+
+  pub async fn lamports(&mut self, address: &Pubkey) -> u64 {
+    self.account(address).await.map_or(0, |account| account.lamports)
+  }
+
+  pub async fn rent_exempt(&mut self, data_len: usize) -> u64 {
+    let rent: Rent = self.context.banks_client.get_sysvar().await.unwrap();
+    rent.minimum_balance(data_len)
+  }
+
+  /// Creates a mint without freeze authority.
+  pub async fn create_mint(&mut self, mint_authority: &Pubkey, decimals: u8) -> Pubkey {
+    let mint = Pubkey::new_unique();
+    let mut data = vec![0u8; MINT_LEN];
+    data[0..4].copy_from_slice(&1u32.to_le_bytes());
+    data[4..36].copy_from_slice(mint_authority.as_ref());
+    data[44] = decimals;
+    data[45] = 1;
+
+    let lamports = self.rent_exempt(MINT_LEN).await;
+    let account = Account {
+      lamports,
+      data,
+      owner: TOKEN_PROGRAM_ID,
+      executable: false,
+      rent_epoch: 0,
+    };
+    self.context.set_account(&mint, &account.into());
+    mint
+  }
+
+  pub async fn create_token_account(&mut self, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+    let address = Pubkey::new_unique();
+    self.set_token_account(&address, mint, owner, amount).await;
+    address
+  }
+
+  pub async fn set_token_account(&mut self, address: &Pubkey, mint: &Pubkey, owner: &Pubkey, amount: u64) {
+    let rent_exempt = self.rent_exempt(TokenAccount::LEN).await;
+    let is_native = *mint == NATIVE_MINT_ID;
+    let token_account = TokenAccount {
+      mint: *mint,
+      owner: *owner,
+      amount,
+      state: AccountState::Initialized,
+      is_native: if is_native { COption::Some(rent_exempt) } else { COption::None },
+      ..TokenAccount::default()
+    };
+    let mut data = vec![0u8; TokenAccount::LEN];
+    token_account.pack_into_slice(&mut data);
+
+    let lamports = if is_native { rent_exempt + amount } else { rent_exempt };
+    let account = Account {
+      lamports,
+      data,
+      owner: TOKEN_PROGRAM_ID,
+      executable: false,
+      rent_epoch: 0,
+    };
+    self.context.set_account(address, &account.into());
+  }
+
+  pub async fn token_account(&mut self, address: &Pubkey) -> TokenAccount {
+    let account = self.account(address).await.unwrap();
+    TokenAccount::unpack_from_slice(&account.data).unwrap()
+  }
+
+  pub async fn token_balance(&mut self, address: &Pubkey) -> u64 {
+    self.token_account(address).await.amount
+  }
+
+  pub async fn mint_supply(&mut self, mint: &Pubkey) -> u64 {
+    let account = self.account(mint).await.unwrap();
+    u64::from_le_bytes(account.data[36..44].try_into().unwrap())
+  }
+
+  /// Decodes the anchor account at `address` with `decode`.
+  pub async fn decode<T>(&mut self, address: &Pubkey, decode: fn(&[u8]) -> anchor_lang::Result<T>) -> T {
+    let account = self.account(address).await.unwrap();
+    decode(&account.data).unwrap()
+  }
+}
+
+fn custom_error(result: Result<(), BanksClientError>) -> u32 {
+  match result.unwrap_err().unwrap() {
+    TransactionError::InstructionError(_, InstructionError::Custom(code)) => code,
+    error => panic!("Expected a custom program error, got {:?}", error),
+  }
+}
+
+pub fn assert_vault_error(result: Result<(), BanksClientError>, error: vault::error::ErrorCode) {
+  assert_eq!(custom_error(result), u32::from(error));
+}
+
+pub fn assert_anchor_error(result: Result<(), BanksClientError>, error: anchor_lang::error::ErrorCode) {
+  assert_eq!(custom_error(result), u32::from(error));
+}
+
+pub fn assert_transaction_error(result: Result<(), BanksClientError>, error: TransactionError) {
+  assert_eq!(result.unwrap_err().unwrap(), error);
+}
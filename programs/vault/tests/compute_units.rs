@@ -0,0 +1,832 @@
+//! Compute units of the program instructions across merkle tree depths, schedule sizes and
+//! payout tokens, measured on the SBF build of the program. The report is written to
+//! `compute-units.md` next to the crate manifest, commit it with each release to track the
+//! changes and compare it with `git diff`. Ignored by default:
+//!
+//! cargo test-sbf --test compute_units -- --ignored --nocapture
+//!
+//! Without `vault.so` the program runs natively, which does not meter compute units, and the
+//! report only holds transaction and schedule sizes.
+
+mod common;
+
+use std::collections::{
+  HashMap,
+};
+
+use anchor_lang::{
+  AccountSerialize,
+};
+use solana_program::{
+  instruction::{
+    Instruction,
+  },
+  native_token::{
+    LAMPORTS_PER_SOL,
+  },
+  pubkey::{
+    Pubkey,
+  },
+  system_program,
+};
+use solana_program_test::{
+  ProgramTest,
+};
+use solana_sdk::{
+  account::{
+    Account,
+  },
+  compute_budget::{
+    ComputeBudgetInstruction,
+  },
+  packet::{
+    PACKET_DATA_SIZE,
+  },
+  signature::{
+    Keypair,
+    Signer,
+  },
+  transaction::{
+    Transaction,
+  },
+};
+use vault::external::spl_token::{
+  native_mint::ID as NATIVE_MINT_ID,
+};
+use vault::state::{
+  Schedule,
+};
+use vault_client::account::{
+  decode_vault,
+};
+use vault_client::instruction::{
+  self as vault_instruction,
+  CompressedNftLeaf,
+  CreateUserTokenAccount,
+  NewEditionAccounts,
+  RedeemOptions,
+};
+use vault_client::merkle::{
+  redemption_epoch_leaf,
+  redemption_leaf,
+  redemption_multi_leaf,
+  redemption_nft_leaf,
+  MerkleTree,
+};
+use vault_client::pda::{
+  find_schedule_address,
+  find_vault_address,
+  find_vault_derivation_path,
+  get_asset_id,
+  get_associated_token_address,
+};
+
+use common::{
+  program_test,
+  sbf_program_test,
+  VaultTest,
+  PROGRAM_ID,
+};
+
+const REPORT_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/compute-units.md");
+const COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+const AMOUNT: u64 = LAMPORTS_PER_SOL;
+const VAULT_BALANCE: u64 = 1_000 * LAMPORTS_PER_SOL;
+
+const TREE_DEPTHS: [u32; 6] = [1, 4, 8, 12, 16, 20];
+const SCHEDULE_USER_COUNTS: [u16; 4] = [16, 1_024, 4_096, 8_192];
+const BITMAP_USER_COUNTS: [u16; 4] = [16, 1_024, 8_192, u16::MAX];
+const DEFAULT_DEPTH: u32 = 10;
+const DEFAULT_USER_COUNT: u16 = 1_024;
+
+/// Payout of a benchmarked redemption.
+#[derive(Clone, Copy)]
+enum TokenType {
+  Token,
+  /// Token paid to an associated token account created by the redemption
+  TokenNewAccount,
+  WrappedSol,
+  Sol,
+}
+
+impl TokenType {
+  fn name(self) -> &'static str {
+    match self {
+      TokenType::Token => "token",
+      TokenType::TokenNewAccount => "token, new account",
+      TokenType::WrappedSol => "wrapped SOL",
+      TokenType::Sol => "SOL",
+    }
+  }
+}
+
+/// One benchmarked transaction. `compute_units` is `None` for transactions over
+/// `PACKET_DATA_SIZE`, which a validator would not accept, when the program runs natively and
+/// for transactions not processed, the reason of which is `unprocessed`. `schedule_size` is
+/// the data size of the schedule a redemption reads.
+struct Measurement {
+  instruction: &'static str,
+  case: String,
+  compute_units: Option<u64>,
+  transaction_size: usize,
+  schedule_size: Option<usize>,
+  unprocessed: Option<&'static str>,
+}
+
+impl Measurement {
+  /// Returns the compute units as reported, or why they are missing.
+  fn compute_units_cell(&self) -> String {
+    match (self.compute_units, self.unprocessed) {
+      (Some(units), _) => units.to_string(),
+      (None, Some(reason)) => format!("not processed, {}", reason),
+      (None, None) if self.transaction_size > PACKET_DATA_SIZE => String::from("too large"),
+      (None, None) => String::from("not metered"),
+    }
+  }
+}
+
+/// Vault funded with a token, wrapped SOL and SOL, and the measurements taken so far.
+/// `metered` is false when the program runs natively.
+struct Benchmark {
+  test: VaultTest,
+  metered: bool,
+  mint: Pubkey,
+  vault_token: Pubkey,
+  vault_wrapped_sol: Pubkey,
+  event_id: u64,
+  measurements: Vec<Measurement>,
+}
+
+impl Benchmark {
+  async fn new(program_test: ProgramTest, metered: bool) -> Self {
+    let mut test = VaultTest::with_program_test(program_test).await;
+    let vault_signer = test.vault_signer;
+    let mint = test.create_mint(&Pubkey::new_unique(), 9).await;
+    let vault_token = test.create_token_account(&mint, &vault_signer, VAULT_BALANCE).await;
+    let vault_wrapped_sol = test.create_token_account(&NATIVE_MINT_ID, &vault_signer, VAULT_BALANCE).await;
+    test.airdrop(&vault_signer, VAULT_BALANCE);
+
+    Benchmark {
+      test,
+      metered,
+      mint,
+      vault_token,
+      vault_wrapped_sol,
+      event_id: 0,
+      measurements: Vec::new(),
+    }
+  }
+
+  /// Processes `instructions` with the maximum compute unit limit.
+  async fn process(&mut self, instructions: &[Instruction], signers: &[&Keypair]) {
+    let transaction = self.transaction(instructions, signers);
+    self.test.process_metered(transaction).await.unwrap();
+  }
+
+  /// Processes `instructions` like `process` and records their compute units as a case of
+  /// `instruction`.
+  async fn measure(&mut self, instruction: &'static str, case: String, instructions: &[Instruction], signers: &[&Keypair]) -> Option<u64> {
+    let transaction = self.transaction(instructions, signers);
+    let transaction_size = transaction_size(&transaction);
+    let compute_units = if transaction_size <= PACKET_DATA_SIZE {
+      let compute_units = self.test.process_metered(transaction).await.unwrap();
+      self.metered.then_some(compute_units)
+    } else {
+      None
+    };
+
+    self.measurements.push(Measurement {
+      instruction,
+      case,
+      compute_units,
+      transaction_size,
+      schedule_size: None,
+      unprocessed: None,
+    });
+    compute_units
+  }
+
+  /// Records the size of a transaction of `instruction` that cannot be processed by the
+  /// program test, because of `reason`.
+  fn measure_unprocessed(&mut self, instruction: &'static str, case: String, instructions: &[Instruction], signers: &[&Keypair], reason: &'static str) {
+    let transaction = self.transaction(instructions, signers);
+    self.measurements.push(Measurement {
+      instruction,
+      case,
+      compute_units: None,
+      transaction_size: transaction_size(&transaction),
+      schedule_size: None,
+      unprocessed: Some(reason),
+    });
+  }
+
+  /// Same as `measure` for a redemption from `schedule`, also recording its data size.
+  async fn measure_redemption(&mut self, instruction: &'static str, case: String, instructions: &[Instruction], signers: &[&Keypair], schedule: &Pubkey) -> Option<u64> {
+    let compute_units = self.measure(instruction, case, instructions, signers).await;
+    let schedule_size = self.test.account(schedule).await.unwrap().data.len();
+    self.measurements.last_mut().unwrap().schedule_size = Some(schedule_size);
+    compute_units
+  }
+
+  fn transaction(&self, instructions: &[Instruction], signers: &[&Keypair]) -> Transaction {
+    let mut all_instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(COMPUTE_UNIT_LIMIT)];
+    all_instructions.extend_from_slice(instructions);
+    self.test.transaction(&all_instructions, signers)
+  }
+
+  fn next_schedule(&mut self) -> (u64, Pubkey) {
+    self.event_id += 1;
+    (self.event_id, find_schedule_address(self.event_id, &PROGRAM_ID).0)
+  }
+
+  fn new_user(&mut self) -> Keypair {
+    let user = Keypair::new();
+    self.test.airdrop(&user.pubkey(), LAMPORTS_PER_SOL);
+    user
+  }
+
+  async fn create_schedules(&mut self) -> Vec<(u16, Option<u64>)> {
+    let admin = self.test.admin.insecure_clone();
+    let mut results = Vec::new();
+    for user_count in SCHEDULE_USER_COUNTS {
+      let (event_id, _) = self.next_schedule();
+      let instruction = vault_instruction::create_schedule(&admin.pubkey(), &self.test.vault, user_count, event_id, 1, [0u8; 32], false, &self.mint, &self.vault_token, &system_program::ID, &system_program::ID, &PROGRAM_ID);
+      let units = self.measure("create_schedule", format!("{} users", user_count), &[instruction], &[&admin]).await;
+      results.push((user_count, units));
+    }
+    for user_count in BITMAP_USER_COUNTS {
+      let (event_id, _) = self.next_schedule();
+      let instruction = vault_instruction::create_bitmap_schedule(&admin.pubkey(), &self.test.vault, user_count, event_id, 1, [0u8; 32], &self.mint, &self.vault_token, &system_program::ID, &system_program::ID, &PROGRAM_ID);
+      self.measure("create_bitmap_schedule", format!("{} users", user_count), &[instruction], &[&admin]).await;
+    }
+    results
+  }
+
+  async fn redeem_token(&mut self, depth: u32, user_count: u16, token_type: TokenType) -> Option<u64> {
+    let admin = self.test.admin.insecure_clone();
+    let user = self.new_user();
+    let (mint, vault_token) = match token_type {
+      TokenType::Token | TokenType::TokenNewAccount => (self.mint, self.vault_token),
+      TokenType::WrappedSol => (NATIVE_MINT_ID, self.vault_wrapped_sol),
+      TokenType::Sol => (system_program::ID, self.test.vault_signer),
+    };
+    let user_token = match token_type {
+      TokenType::Token | TokenType::WrappedSol => self.test.create_token_account(&mint, &user.pubkey(), 0).await,
+      TokenType::TokenNewAccount => get_associated_token_address(&user.pubkey(), &mint),
+      TokenType::Sol => user.pubkey(),
+    };
+    let create_user_token = match token_type {
+      TokenType::TokenNewAccount => Some(CreateUserTokenAccount {
+        mint,
+        payer: user.pubkey(),
+      }),
+      _ => None,
+    };
+
+    let merkle_tree = merkle_tree(depth, redemption_leaf(0, None, &user.pubkey(), AMOUNT, 0));
+    let (event_id, schedule) = self.next_schedule();
+    let instruction = vault_instruction::create_schedule(&admin.pubkey(), &self.test.vault, user_count, event_id, 1, merkle_tree.root(), false, &mint, &vault_token, &system_program::ID, &system_program::ID, &PROGRAM_ID);
+    self.process(&[instruction], &[&admin]).await;
+
+    let instruction = vault_instruction::redeem_token(&self.test.vault, &schedule, 0, 0, merkle_tree.proof(0), AMOUNT, 0, &vault_token, &user.pubkey(), &user_token, &RedeemOptions { create_user_token0: create_user_token, ..RedeemOptions::default() }, &PROGRAM_ID);
+    let case = format!("depth {}, {} users, {}", depth, user_count, token_type.name());
+    self.measure_redemption("redeem_token", case, &[instruction], &[&user], &schedule).await
+  }
+
+  async fn redeem_token_multi(&mut self, depth: u32) -> Option<u64> {
+    let admin = self.test.admin.insecure_clone();
+    let user = self.new_user();
+    let mint = self.mint;
+    let vault_token = self.vault_token;
+    let user_token = self.test.create_token_account(&mint, &user.pubkey(), 0).await;
+
+    let merkle_tree = merkle_tree(depth, redemption_multi_leaf(0, None, &user.pubkey(), &mint, AMOUNT, 0));
+    let (event_id, schedule) = self.next_schedule();
+    let instruction = vault_instruction::create_schedule(&admin.pubkey(), &self.test.vault, DEFAULT_USER_COUNT, event_id, 1, merkle_tree.root(), true, &mint, &vault_token, &system_program::ID, &system_program::ID, &PROGRAM_ID);
+    self.process(&[instruction], &[&admin]).await;
+
+    let instruction = vault_instruction::redeem_token_multi(&self.test.vault, &schedule, 0, 0, merkle_tree.proof(0), &mint, AMOUNT, 0, &vault_token, &user.pubkey(), &user_token, &RedeemOptions::default(), &PROGRAM_ID);
+    let case = format!("depth {}, {} users, {}", depth, DEFAULT_USER_COUNT, TokenType::Token.name());
+    self.measure_redemption("redeem_token_multi", case, &[instruction], &[&user], &schedule).await
+  }
+
+  async fn redeem_token_bitmap(&mut self, depth: u32, user_count: u16) -> Option<u64> {
+    let admin = self.test.admin.insecure_clone();
+    let user = self.new_user();
+    let mint = self.mint;
+    let vault_token = self.vault_token;
+    let user_token = self.test.create_token_account(&mint, &user.pubkey(), 0).await;
+
+    let merkle_tree = merkle_tree(depth, redemption_leaf(0, None, &user.pubkey(), AMOUNT, 0));
+    let (event_id, schedule) = self.next_schedule();
+    let instruction = vault_instruction::create_bitmap_schedule(&admin.pubkey(), &self.test.vault, user_count, event_id, 1, merkle_tree.root(), &mint, &vault_token, &system_program::ID, &system_program::ID, &PROGRAM_ID);
+    self.process(&[instruction], &[&admin]).await;
+
+    let instruction = vault_instruction::redeem_token_bitmap(&self.test.vault, &schedule, 0, 0, merkle_tree.proof(0), AMOUNT, 0, &vault_token, &user.pubkey(), &user_token, &RedeemOptions::default(), &PROGRAM_ID);
+    let case = format!("depth {}, {} users, {}", depth, user_count, TokenType::Token.name());
+    self.measure_redemption("redeem_token_bitmap", case, &[instruction], &[&user], &schedule).await
+  }
+
+  async fn create_vault(&mut self) {
+    let owner = self.test.owner.insecure_clone();
+    let admin = self.test.admin.pubkey();
+    let vault_path = find_vault_derivation_path("Coin98 Compute Units");
+    let (vault, _) = find_vault_address(&vault_path, &PROGRAM_ID);
+
+    let instruction = vault_instruction::create_vault(&owner.pubkey(), vault_path, &PROGRAM_ID);
+    self.measure("create_vault", String::from("new vault"), &[instruction], &[&owner]).await;
+    let instruction = vault_instruction::set_vault(&owner.pubkey(), &vault, vec![admin], &PROGRAM_ID);
+    self.measure("set_vault", String::from("1 admin"), &[instruction], &[&owner]).await;
+  }
+
+  /// Appends a tranche of `DEFAULT_USER_COUNT` indexes to a schedule and redeems its first one.
+  async fn redeem_token_tranche(&mut self) {
+    let admin = self.test.admin.insecure_clone();
+    let user = self.new_user();
+    let mint = self.mint;
+    let vault_token = self.vault_token;
+    let user_token = self.test.create_token_account(&mint, &user.pubkey(), 0).await;
+
+    let (event_id, schedule) = self.next_schedule();
+    let instruction = vault_instruction::create_schedule(&admin.pubkey(), &self.test.vault, DEFAULT_USER_COUNT, event_id, 1, [0u8; 32], false, &mint, &vault_token, &system_program::ID, &system_program::ID, &PROGRAM_ID);
+    self.process(&[instruction], &[&admin]).await;
+
+    let merkle_tree = merkle_tree(DEFAULT_DEPTH, redemption_leaf(DEFAULT_USER_COUNT, None, &user.pubkey(), AMOUNT, 0));
+    let instruction = vault_instruction::append_schedule_tranche(&admin.pubkey(), &self.test.vault, &schedule, DEFAULT_USER_COUNT, 1, merkle_tree.root(), &PROGRAM_ID);
+    let case = format!("{} users onto {} users", DEFAULT_USER_COUNT, DEFAULT_USER_COUNT);
+    self.measure("append_schedule_tranche", case, &[instruction], &[&admin]).await;
+
+    let options = RedeemOptions {
+      use_tranches: true,
+      ..RedeemOptions::default()
+    };
+    let instruction = vault_instruction::redeem_token(&self.test.vault, &schedule, DEFAULT_USER_COUNT, 0, merkle_tree.proof(0), AMOUNT, 0, &vault_token, &user.pubkey(), &user_token, &options, &PROGRAM_ID);
+    let case = format!("depth {}, {} users, {}, tranche", DEFAULT_DEPTH, 2 * DEFAULT_USER_COUNT, TokenType::Token.name());
+    self.measure_redemption("redeem_token", case, &[instruction], &[&user], &schedule).await;
+  }
+
+  async fn redeem_token_mint(&mut self) {
+    let admin = self.test.admin.insecure_clone();
+    let user = self.new_user();
+    let vault_signer = self.test.vault_signer;
+    let mint = self.test.create_mint(&vault_signer, 9).await;
+    let user_token = self.test.create_token_account(&mint, &user.pubkey(), 0).await;
+
+    let merkle_tree = merkle_tree(DEFAULT_DEPTH, redemption_leaf(0, None, &user.pubkey(), AMOUNT, 0));
+    let (event_id, schedule) = self.next_schedule();
+    let instruction = vault_instruction::create_schedule(&admin.pubkey(), &self.test.vault, DEFAULT_USER_COUNT, event_id, 1, merkle_tree.root(), false, &mint, &mint, &system_program::ID, &system_program::ID, &PROGRAM_ID);
+    self.process(&[instruction], &[&admin]).await;
+    let instruction = vault_instruction::set_schedule_mint(&admin.pubkey(), &self.test.vault, &schedule, AMOUNT, &PROGRAM_ID);
+    self.measure("set_schedule_mint", String::from("new cap"), &[instruction], &[&admin]).await;
+
+    let options = RedeemOptions {
+      mint_on_claim: true,
+      ..RedeemOptions::default()
+    };
+    let instruction = vault_instruction::redeem_token(&self.test.vault, &schedule, 0, 0, merkle_tree.proof(0), AMOUNT, 0, &mint, &user.pubkey(), &user_token, &options, &PROGRAM_ID);
+    let case = format!("depth {}, {} users, minted {}", DEFAULT_DEPTH, DEFAULT_USER_COUNT, TokenType::Token.name());
+    self.measure_redemption("redeem_token", case, &[instruction], &[&user], &schedule).await;
+  }
+
+  async fn set_swap_program(&mut self) {
+    let owner = self.test.owner.insecure_clone();
+    let instruction = vault_instruction::set_swap_program(&owner.pubkey(), &self.test.vault, &mock_amm::ID, &PROGRAM_ID);
+    self.measure("set_swap_program", String::from("mock AMM"), &[instruction], &[&owner]).await;
+  }
+
+  /// Redeems through the mock AMM, set with `set_swap_program`.
+  async fn redeem_token_swap(&mut self, depth: u32) {
+    let admin = self.test.admin.insecure_clone();
+    let user = self.new_user();
+    let mint = self.mint;
+    let vault_token = self.vault_token;
+    let output_mint = self.test.create_mint(&Pubkey::new_unique(), 9).await;
+    let user_token = self.test.create_token_account(&mint, &user.pubkey(), 0).await;
+    let user_token_out = self.test.create_token_account(&output_mint, &user.pubkey(), 0).await;
+    let pool = self.test.create_pool(&mint, &output_mint, 1, 1, VAULT_BALANCE).await;
+
+    let merkle_tree = merkle_tree(depth, redemption_leaf(0, None, &user.pubkey(), AMOUNT, 0));
+    let (event_id, schedule) = self.next_schedule();
+    let instruction = vault_instruction::create_schedule(&admin.pubkey(), &self.test.vault, DEFAULT_USER_COUNT, event_id, 1, merkle_tree.root(), false, &mint, &vault_token, &system_program::ID, &system_program::ID, &PROGRAM_ID);
+    self.process(&[instruction], &[&admin]).await;
+
+    let swap_instruction = pool.swap(&user.pubkey(), &user_token, &user_token_out, AMOUNT, AMOUNT);
+    let instruction = vault_instruction::redeem_token_swap(&self.test.vault, &schedule, 0, 0, merkle_tree.proof(0), AMOUNT, 0, &output_mint, AMOUNT, &vault_token, None, &user.pubkey(), &user_token, None, &user_token_out, None, false, &swap_instruction, &PROGRAM_ID);
+    let case = format!("depth {}, {} users, mock AMM", depth, DEFAULT_USER_COUNT);
+    self.measure_redemption("redeem_token_swap", case, &[instruction], &[&user], &schedule).await;
+  }
+
+  /// Redeems into the mock AMM standing in for a staking program. `set_schedule_stake` is
+  /// recorded for the first depth only, its cost does not depend on the tree.
+  async fn redeem_token_stake(&mut self, depth: u32) {
+    let admin = self.test.admin.insecure_clone();
+    let user = self.new_user();
+    let mint = self.mint;
+    let vault_token = self.vault_token;
+    let stake_mint = self.test.create_mint(&Pubkey::new_unique(), 9).await;
+    let user_token = self.test.create_token_account(&mint, &user.pubkey(), 0).await;
+    let user_stake = self.test.create_token_account(&stake_mint, &user.pubkey(), 0).await;
+    let pool = self.test.create_pool(&mint, &stake_mint, 1, 1, VAULT_BALANCE).await;
+
+    let merkle_tree = merkle_tree(depth, redemption_leaf(0, None, &user.pubkey(), AMOUNT, 0));
+    let (event_id, schedule) = self.next_schedule();
+    let instruction = vault_instruction::create_schedule(&admin.pubkey(), &self.test.vault, DEFAULT_USER_COUNT, event_id, 1, merkle_tree.root(), false, &mint, &vault_token, &system_program::ID, &system_program::ID, &PROGRAM_ID);
+    self.process(&[instruction], &[&admin]).await;
+    let stake_accounts = pool.accounts();
+    let case = format!("{} accounts", stake_accounts.len());
+    let instruction = vault_instruction::set_schedule_stake(&admin.pubkey(), &self.test.vault, &schedule, &mock_amm::ID, stake_accounts, &PROGRAM_ID);
+    if depth == TREE_DEPTHS[0] {
+      self.measure("set_schedule_stake", case, &[instruction], &[&admin]).await;
+    } else {
+      self.process(&[instruction], &[&admin]).await;
+    }
+
+    let stake_instruction = pool.swap(&user.pubkey(), &user_token, &user_stake, AMOUNT, 0);
+    let instruction = vault_instruction::redeem_token_stake(&self.test.vault, &schedule, 0, 0, merkle_tree.proof(0), AMOUNT, 0, &vault_token, None, &user.pubkey(), &user_token, None, &user_stake, None, false, &stake_instruction, &PROGRAM_ID);
+    let case = format!("depth {}, {} users, mock AMM", depth, DEFAULT_USER_COUNT);
+    self.measure_redemption("redeem_token_stake", case, &[instruction], &[&user], &schedule).await;
+  }
+
+  /// Redeems an NFT held by the vault. Editions and compressed NFTs need Token Metadata and
+  /// Bubblegum, which the program test does not load, only their transaction size is recorded.
+  async fn redeem_nfts(&mut self) {
+    let admin = self.test.admin.insecure_clone();
+    let user = self.new_user();
+    let vault_signer = self.test.vault_signer;
+    let asset = self.test.create_mint(&Pubkey::new_unique(), 0).await;
+    let vault_token = self.test.create_token_account(&asset, &vault_signer, 1).await;
+    let user_token = self.test.create_token_account(&asset, &user.pubkey(), 0).await;
+    let merkle_tree_account = Pubkey::new_unique();
+    let compressed_asset = get_asset_id(&merkle_tree_account, 0);
+
+    let nft_tree = merkle_tree(DEFAULT_DEPTH, redemption_nft_leaf(0, 0, &user.pubkey(), &asset, 0));
+    let (event_id, schedule) = self.next_schedule();
+    let instruction = vault_instruction::create_nft_schedule(&admin.pubkey(), &self.test.vault, DEFAULT_USER_COUNT, event_id, 0, nft_tree.root(), &PROGRAM_ID);
+    self.measure("create_nft_schedule", format!("{} users", DEFAULT_USER_COUNT), &[instruction], &[&admin]).await;
+
+    let instruction = vault_instruction::redeem_nft(&self.test.vault, &schedule, 0, 0, nft_tree.proof(0), &asset, &vault_token, &user.pubkey(), &user_token, None, None, &PROGRAM_ID);
+    let case = format!("depth {}, {} users, token", DEFAULT_DEPTH, DEFAULT_USER_COUNT);
+    self.measure_redemption("redeem_nft", case, &[instruction], &[&user], &schedule).await;
+
+    let edition_tree = merkle_tree(DEFAULT_DEPTH, redemption_nft_leaf(1, 0, &user.pubkey(), &asset, 1));
+    let new_edition = NewEditionAccounts {
+      new_mint: Pubkey::new_unique(),
+      new_metadata: Pubkey::new_unique(),
+      new_edition: Pubkey::new_unique(),
+      edition_mark: Pubkey::new_unique(),
+      new_metadata_update_authority: Pubkey::new_unique(),
+    };
+    let instruction = vault_instruction::redeem_nft_edition(&self.test.vault, &schedule, 1, 0, edition_tree.proof(0), &asset, 1, &vault_token, &user.pubkey(), &new_edition, None, &PROGRAM_ID);
+    let case = format!("depth {}, {} users, edition 1", DEFAULT_DEPTH, DEFAULT_USER_COUNT);
+    self.measure_unprocessed("redeem_nft_edition", case, &[instruction], &[&user], "needs Token Metadata");
+
+    let compressed_tree = merkle_tree(DEFAULT_DEPTH, redemption_nft_leaf(2, 0, &user.pubkey(), &compressed_asset, 0));
+    let leaf = CompressedNftLeaf {
+      merkle_tree: merkle_tree_account,
+      tree_authority: Pubkey::new_unique(),
+      leaf_delegate: vault_signer,
+      root: [0u8; 32],
+      data_hash: [0u8; 32],
+      creator_hash: [0u8; 32],
+      nonce: 0,
+      leaf_index: 0,
+      proof: vec![Pubkey::new_unique(); 3],
+    };
+    let instruction = vault_instruction::redeem_compressed_nft(&self.test.vault, &schedule, 2, 0, compressed_tree.proof(0), &user.pubkey(), &leaf, None, &PROGRAM_ID);
+    let case = format!("depth {}, {} users, 3 proof nodes", DEFAULT_DEPTH, DEFAULT_USER_COUNT);
+    self.measure_unprocessed("redeem_compressed_nft", case, &[instruction], &[&user], "needs Bubblegum");
+  }
+
+  async fn redeem_voucher(&mut self) {
+    let admin = self.test.admin.insecure_clone();
+    let user = self.new_user();
+    let voucher_signer = Keypair::new();
+    let mint = self.mint;
+    let vault_token = self.vault_token;
+    let user_token = self.test.create_token_account(&mint, &user.pubkey(), 0).await;
+
+    let nonce_count = u32::from(DEFAULT_USER_COUNT);
+    let (event_id, schedule) = self.next_schedule();
+    let instruction = vault_instruction::create_voucher_schedule(&admin.pubkey(), &self.test.vault, event_id, nonce_count, 0, &voucher_signer.pubkey(), &mint, &vault_token, &PROGRAM_ID);
+    self.measure("create_voucher_schedule", format!("{} nonces", nonce_count), &[instruction], &[&admin]).await;
+
+    let expiry = self.test.unix_timestamp().await + 3600;
+    let message = vault_instruction::voucher_message(&schedule, &user.pubkey(), AMOUNT, 0, expiry);
+    let signature = voucher_signer.sign_message(&message);
+    let instructions = [
+      vault_instruction::ed25519_instruction(&voucher_signer.pubkey(), &signature.into(), &message),
+      vault_instruction::redeem_voucher(&self.test.vault, &schedule, AMOUNT, 0, expiry, &vault_token, &user.pubkey(), &user_token, None, &PROGRAM_ID),
+    ];
+    let case = format!("{} nonces, {}", nonce_count, TokenType::Token.name());
+    self.measure("redeem_voucher", case, &instructions, &[&user]).await;
+  }
+
+  async fn redeem_epoch(&mut self, depth: u32) {
+    let admin = self.test.admin.insecure_clone();
+    let user = self.new_user();
+    let mint = self.mint;
+    let vault_token = self.vault_token;
+    let user_token = self.test.create_token_account(&mint, &user.pubkey(), 0).await;
+
+    let merkle_tree = merkle_tree(depth, redemption_epoch_leaf(1, &user.pubkey(), AMOUNT));
+    let (event_id, schedule) = self.next_schedule();
+    let instruction = vault_instruction::create_epoch_schedule(&admin.pubkey(), &self.test.vault, event_id, &mint, &vault_token, &PROGRAM_ID);
+    self.measure("create_epoch_schedule", String::from("new schedule"), &[instruction], &[&admin]).await;
+    let instruction = vault_instruction::set_schedule_epoch(&admin.pubkey(), &self.test.vault, &schedule, 1, 0, merkle_tree.root(), &PROGRAM_ID);
+    self.measure("set_schedule_epoch", String::from("first epoch"), &[instruction], &[&admin]).await;
+
+    let instruction = vault_instruction::redeem_epoch(&self.test.vault, &schedule, merkle_tree.proof(0), AMOUNT, &vault_token, &user.pubkey(), &user_token, None, &PROGRAM_ID);
+    let case = format!("depth {}, {}", depth, TokenType::Token.name());
+    self.measure("redeem_epoch", case, &[instruction], &[&user]).await;
+  }
+
+  async fn vault_transfers(&mut self) {
+    let admin = self.test.admin.insecure_clone();
+    let vault = self.test.vault;
+    let mint = self.mint;
+    let admin_token = self.test.create_token_account(&mint, &admin.pubkey(), 0).await;
+
+    let instruction = vault_instruction::withdraw_token(&admin.pubkey(), &vault, &self.vault_token, &admin_token, AMOUNT, &PROGRAM_ID);
+    self.measure("withdraw_token", TokenType::Token.name().to_string(), &[instruction], &[&admin]).await;
+    let instruction = vault_instruction::deposit_sol(&admin.pubkey(), &vault, &self.vault_wrapped_sol, AMOUNT, &PROGRAM_ID);
+    self.measure("deposit_sol", TokenType::WrappedSol.name().to_string(), &[instruction], &[&admin]).await;
+    let instruction = vault_instruction::withdraw_sol(&admin.pubkey(), &vault, &admin.pubkey(), AMOUNT, &PROGRAM_ID);
+    self.measure("withdraw_sol", TokenType::Sol.name().to_string(), &[instruction], &[&admin]).await;
+  }
+
+  /// Toggles the settings of a schedule and a bitmap schedule of `DEFAULT_USER_COUNT` users.
+  async fn schedule_settings(&mut self) {
+    let admin = self.test.admin.insecure_clone();
+    let vault = self.test.vault;
+    let mint = self.mint;
+    let vault_token = self.vault_token;
+
+    let (event_id, schedule) = self.next_schedule();
+    let (bitmap_event_id, bitmap_schedule) = self.next_schedule();
+    let instructions = [
+      vault_instruction::create_schedule(&admin.pubkey(), &vault, DEFAULT_USER_COUNT, event_id, 1, [0u8; 32], false, &mint, &vault_token, &system_program::ID, &system_program::ID, &PROGRAM_ID),
+      vault_instruction::create_bitmap_schedule(&admin.pubkey(), &vault, DEFAULT_USER_COUNT, bitmap_event_id, 1, [0u8; 32], &mint, &vault_token, &system_program::ID, &system_program::ID, &PROGRAM_ID),
+    ];
+    self.process(&instructions, &[&admin]).await;
+
+    let case = format!("{} users", DEFAULT_USER_COUNT);
+    let instruction = vault_instruction::set_schedule_status(&admin.pubkey(), &vault, &schedule, false, &PROGRAM_ID);
+    self.measure("set_schedule_status", case.clone(), &[instruction], &[&admin]).await;
+    let instruction = vault_instruction::set_schedule_owner_check(&admin.pubkey(), &vault, &schedule, true, &PROGRAM_ID);
+    self.measure("set_schedule_owner_check", case.clone(), &[instruction], &[&admin]).await;
+    let instruction = vault_instruction::get_claim_status(&schedule, DEFAULT_USER_COUNT - 1, &PROGRAM_ID);
+    self.measure("get_claim_status", case.clone(), &[instruction], &[]).await;
+    let instruction = vault_instruction::set_bitmap_schedule_status(&admin.pubkey(), &vault, &bitmap_schedule, false, &PROGRAM_ID);
+    self.measure("set_bitmap_schedule_status", case.clone(), &[instruction], &[&admin]).await;
+    let instruction = vault_instruction::set_bitmap_schedule_owner_check(&admin.pubkey(), &vault, &bitmap_schedule, true, &PROGRAM_ID);
+    self.measure("set_bitmap_schedule_owner_check", case, &[instruction], &[&admin]).await;
+  }
+
+  /// Replaces the data of the program account at `address`, keeping it rent exempt.
+  async fn set_program_account(&mut self, address: &Pubkey, data: Vec<u8>) {
+    let account = Account {
+      lamports: self.test.rent_exempt(data.len()).await,
+      data,
+      owner: PROGRAM_ID,
+      executable: false,
+      rent_epoch: 0,
+    };
+    self.test.context.set_account(address, &account.into());
+  }
+
+  /// Migrates the vault and a schedule of `DEFAULT_USER_COUNT` users from their first layout.
+  async fn migrations(&mut self) {
+    let owner = self.test.owner.insecure_clone();
+    let admin = self.test.admin.insecure_clone();
+    let vault = self.test.vault;
+    let mint = self.mint;
+    let vault_token = self.vault_token;
+
+    // a vault allocated for its serialized size, before the version byte
+    let mut data = Vec::new();
+    self.test.decode(&vault, decode_vault).await.try_serialize(&mut data).unwrap();
+    data.pop();
+    self.set_program_account(&vault, data).await;
+    let instruction = vault_instruction::migrate_vault(&owner.pubkey(), &vault, &PROGRAM_ID);
+    self.measure("migrate_vault", String::from("version 0"), &[instruction], &[&owner]).await;
+
+    // a schedule allocated with the padding of the first layout, without the version and
+    // verify_user_token_owner bytes
+    let (event_id, schedule) = self.next_schedule();
+    let instruction = vault_instruction::create_schedule(&admin.pubkey(), &vault, DEFAULT_USER_COUNT, event_id, 1, [0u8; 32], false, &mint, &vault_token, &system_program::ID, &system_program::ID, &PROGRAM_ID);
+    self.process(&[instruction], &[&admin]).await;
+    let schedule_size = Schedule::size(DEFAULT_USER_COUNT);
+    let mut data = self.test.account(&schedule).await.unwrap().data;
+    data.drain(8 + schedule_size - 2..8 + schedule_size);
+    self.set_program_account(&schedule, data).await;
+    let instruction = vault_instruction::migrate_schedule(&admin.pubkey(), &vault, &schedule, &PROGRAM_ID);
+    self.measure("migrate_schedule", format!("version 0, {} users", DEFAULT_USER_COUNT), &[instruction], &[&admin]).await;
+  }
+
+  async fn vault_settings(&mut self) {
+    let owner = self.test.owner.insecure_clone();
+    let admin = self.test.admin.insecure_clone();
+    let vault = self.test.vault;
+
+    let instruction = vault_instruction::set_sol_reserve(&owner.pubkey(), &vault, AMOUNT, &PROGRAM_ID);
+    self.measure("set_sol_reserve", String::from("new reserve"), &[instruction], &[&owner]).await;
+    let instruction = vault_instruction::set_sol_liabilities(&admin.pubkey(), &vault, AMOUNT, &PROGRAM_ID);
+    self.measure("set_sol_liabilities", String::from("raised by an admin"), &[instruction], &[&admin]).await;
+    let instruction = vault_instruction::get_withdrawable_sol(&vault, &PROGRAM_ID);
+    self.measure("get_withdrawable_sol", String::from("reserve and liabilities"), &[instruction], &[]).await;
+    let instruction = vault_instruction::set_claim_fee(&owner.pubkey(), &vault, &Pubkey::new_unique(), 5_000, 10, &PROGRAM_ID);
+    self.measure("set_claim_fee", String::from("lamports and bps"), &[instruction], &[&owner]).await;
+    let instruction = vault_instruction::set_allowed_mints(&owner.pubkey(), &vault, vec![self.mint, NATIVE_MINT_ID], &PROGRAM_ID);
+    self.measure("set_allowed_mints", String::from("2 mints"), &[instruction], &[&owner]).await;
+  }
+
+  /// Adds then removes a withdrawal destination. Run last, withdrawals are restricted once a
+  /// destination was added.
+  async fn withdrawal_destinations(&mut self) {
+    let owner = self.test.owner.insecure_clone();
+    let vault = self.test.vault;
+    let destination = self.test.admin.pubkey();
+
+    let instruction = vault_instruction::add_withdrawal_destination(&owner.pubkey(), &vault, &destination, &PROGRAM_ID);
+    self.measure("add_withdrawal_destination", String::from("first destination"), &[instruction], &[&owner]).await;
+    let instruction = vault_instruction::remove_withdrawal_destination(&owner.pubkey(), &vault, &destination, &PROGRAM_ID);
+    self.measure("remove_withdrawal_destination", String::from("last destination"), &[instruction], &[&owner]).await;
+  }
+
+  /// Returns the measurements as a markdown table, in the order they were taken, followed by
+  /// the redemptions of schedules and bitmap schedules of the same users side by side. The
+  /// change of each case is computed against the `previous` compute units.
+  fn report(&self, previous: &HashMap<(String, String), u64>) -> String {
+    let mut report = String::from("# Compute units\n\n");
+    if self.metered {
+      report.push_str("Generated by `cargo test-sbf --test compute_units -- --ignored` on the SBF build of the program. ");
+    } else {
+      report.push_str("Generated by `cargo test --test compute_units -- --ignored` without the SBF build of the program. ");
+      report.push_str("**Compute units are not metered**: the program ran natively, only transaction and schedule sizes are measured. ");
+    }
+    report.push_str("Regenerate it where the Solana platform tools are installed with:\n\n");
+    report.push_str("```\ncargo test-sbf --manifest-path programs/vault/Cargo.toml --test compute_units -- --ignored --nocapture\n```\n\n");
+    report.push_str("and compare it with the committed report with `git diff programs/vault/compute-units.md`. ");
+    report.push_str("Change is the difference with the compute units of the report it replaced.\n\n");
+    report.push_str("Transactions over the packet size are not processed. ");
+    report.push_str("`redeem_token` and `redeem_token_multi` deserialize and rewrite the whole schedule, `redeem_token_bitmap` reads its header and one byte of the bitmap.\n\n");
+    report.push_str("| Instruction | Case | Compute units | Change | Transaction size | Schedule size |\n");
+    report.push_str("| --- | --- | ---: | ---: | ---: | ---: |\n");
+    for measurement in self.measurements.iter() {
+      let change = match (measurement.compute_units, previous.get(&(measurement.instruction.to_string(), measurement.case.clone()))) {
+        (Some(units), Some(previous_units)) => format!("{:+}", i128::from(units) - i128::from(*previous_units)),
+        _ => String::new(),
+      };
+      let schedule_size = measurement.schedule_size.map_or_else(String::new, |size| size.to_string());
+      report.push_str(&format!("| `{}` | {} | {} | {} | {} | {} |\n", measurement.instruction, measurement.case, measurement.compute_units_cell(), change, measurement.transaction_size, schedule_size));
+    }
+
+    report.push_str("\n## Bitmap schedules\n\n");
+    report.push_str(&format!("Redemptions with proofs of depth {} from a `Schedule`, before bitmap schedules, and from a `BitmapSchedule` of the same users. ", DEFAULT_DEPTH));
+    report.push_str("A `Schedule` of 65535 users is over the size of an account the program can create.\n\n");
+    report.push_str("| Users | `redeem_token` | `redeem_token_bitmap` | Schedule size | Bitmap schedule size |\n");
+    report.push_str("| ---: | ---: | ---: | ---: | ---: |\n");
+    for user_count in BITMAP_USER_COUNTS {
+      let case = format!("depth {}, {} users, {}", DEFAULT_DEPTH, user_count, TokenType::Token.name());
+      let schedule = self.find("redeem_token", &case);
+      let bitmap = self.find("redeem_token_bitmap", &case);
+      let cells = |measurement: Option<&Measurement>| match measurement {
+        Some(measurement) => (measurement.compute_units_cell(), measurement.schedule_size.map_or_else(String::new, |size| size.to_string())),
+        None => (String::from("not measured"), String::new()),
+      };
+      let (schedule_units, schedule_size) = cells(schedule);
+      let (bitmap_units, bitmap_size) = cells(bitmap);
+      report.push_str(&format!("| {} | {} | {} | {} | {} |\n", user_count, schedule_units, bitmap_units, schedule_size, bitmap_size));
+    }
+    report
+  }
+
+  /// Returns the measurement of `case` of `instruction`.
+  fn find(&self, instruction: &str, case: &str) -> Option<&Measurement> {
+    self.measurements.iter().find(|measurement| measurement.instruction == instruction && measurement.case == case)
+  }
+}
+
+/// Returns the compute units of each instruction and case of the main table of `report`.
+fn previous_compute_units(report: &str) -> HashMap<(String, String), u64> {
+  report.lines()
+    .filter(|line| line.starts_with("| `"))
+    .filter_map(|line| {
+      let cells: Vec<&str> = line.split('|').map(str::trim).collect();
+      let units = cells.get(3)?.parse().ok()?;
+      Some(((cells[1].trim_matches('`').to_string(), cells[2].to_string()), units))
+    })
+    .collect()
+}
+
+/// Returns a tree of `2^depth` leaves, `leaf` first.
+fn merkle_tree(depth: u32, leaf: [u8; 32]) -> MerkleTree {
+  let mut leaves = vec![leaf];
+  leaves.extend((1..1u32 << depth).map(|index| {
+    let mut filler = [0u8; 32];
+    filler[..4].copy_from_slice(&index.to_le_bytes());
+    filler
+  }));
+  MerkleTree::new(leaves)
+}
+
+/// Returns the wire size of `transaction`: its signatures, prefixed with their count as a
+/// single byte, and its message.
+fn transaction_size(transaction: &Transaction) -> usize {
+  1 + 64 * transaction.signatures.len() + transaction.message_data().len()
+}
+
+#[tokio::test]
+#[ignore = "writes compute-units.md, run with cargo test-sbf --test compute_units -- --ignored"]
+async fn compute_units_report() {
+  let mut benchmark = match sbf_program_test() {
+    Some(program_test) => Benchmark::new(program_test, true).await,
+    None => Benchmark::new(program_test(), false).await,
+  };
+
+  benchmark.create_vault().await;
+  let create_units = benchmark.create_schedules().await;
+  assert!(!benchmark.metered || create_units.iter().all(|(_, units)| units.is_some()));
+
+  for depth in TREE_DEPTHS {
+    benchmark.redeem_token(depth, DEFAULT_USER_COUNT, TokenType::Token).await;
+  }
+  let mut schedule_units = Vec::new();
+  for user_count in SCHEDULE_USER_COUNTS {
+    let units = benchmark.redeem_token(DEFAULT_DEPTH, user_count, TokenType::Token).await;
+    schedule_units.push((user_count, units));
+  }
+  for token_type in [TokenType::TokenNewAccount, TokenType::WrappedSol, TokenType::Sol] {
+    benchmark.redeem_token(DEFAULT_DEPTH, DEFAULT_USER_COUNT, token_type).await;
+  }
+  benchmark.redeem_token_tranche().await;
+  benchmark.redeem_token_mint().await;
+
+  for depth in TREE_DEPTHS {
+    benchmark.redeem_token_multi(depth).await;
+  }
+
+  for depth in TREE_DEPTHS {
+    benchmark.redeem_token_bitmap(depth, DEFAULT_USER_COUNT).await;
+  }
+  let mut bitmap_units = Vec::new();
+  for user_count in BITMAP_USER_COUNTS {
+    let units = benchmark.redeem_token_bitmap(DEFAULT_DEPTH, user_count).await;
+    bitmap_units.push((user_count, units));
+  }
+
+  benchmark.set_swap_program().await;
+  for depth in TREE_DEPTHS {
+    benchmark.redeem_token_swap(depth).await;
+  }
+  for depth in TREE_DEPTHS {
+    benchmark.redeem_token_stake(depth).await;
+  }
+  benchmark.redeem_nfts().await;
+  benchmark.redeem_voucher().await;
+  benchmark.redeem_epoch(DEFAULT_DEPTH).await;
+  benchmark.schedule_settings().await;
+  benchmark.migrations().await;
+  benchmark.vault_settings().await;
+  benchmark.vault_transfers().await;
+  benchmark.withdrawal_destinations().await;
+
+  let previous = previous_compute_units(&std::fs::read_to_string(REPORT_PATH).unwrap_or_default());
+  let report = benchmark.report(&previous);
+  println!("{}", report);
+  // a native run does not replace compute units measured on the SBF build
+  if benchmark.metered || previous.is_empty() {
+    std::fs::write(REPORT_PATH, report).unwrap();
+  }
+  if !benchmark.metered {
+    return;
+  }
+
+  let schedule_units: Vec<(u16, u64)> = schedule_units.into_iter().map(|(user_count, units)| (user_count, units.unwrap())).collect();
+  let bitmap_units: Vec<(u16, u64)> = bitmap_units.into_iter().map(|(user_count, units)| (user_count, units.unwrap())).collect();
+  // the bitmap is never deserialized, its size does not change the cost of a redemption
+  for (user_count, units) in bitmap_units.iter() {
+    if let Some((_, schedule_units)) = schedule_units.iter().find(|(count, _)| count == user_count && *count >= 1_024) {
+      assert!(units < schedule_units, "{} users: {} bitmap, {} schedule", user_count, units, schedule_units);
+    }
+  }
+  let min = bitmap_units.iter().map(|(_, units)| units).min().unwrap();
+  let max = bitmap_units.iter().map(|(_, units)| units).max().unwrap();
+  assert!(max - min < 1_000, "{:?}", bitmap_units);
+}
+
+#[test]
+fn previous_compute_units_read_main_table() {
+  let report = "\
+| Instruction | Case | Compute units | Change | Transaction size | Schedule size |
+| --- | --- | ---: | ---: | ---: | ---: |
+| `redeem_token` | depth 1, 1024 users, token | 1000 | -12 | 678 | 1263 |
+| `redeem_token` | depth 20, 1024 users, token | too large |  | 1287 | 1263 |
+| `create_vault` | new vault | not metered |  | 416 |  |
+| 1024 | 1000 | 500 | 1263 | 360 |
+";
+  let previous = previous_compute_units(report);
+
+  assert_eq!(previous.len(), 1);
+  assert_eq!(previous.get(&(String::from("redeem_token"), String::from("depth 1, 1024 users, token"))), Some(&1000));
+}
@@ -0,0 +1,182 @@
+mod common;
+
+use solana_program::{
+  instruction::{
+    Instruction,
+  },
+  native_token::{
+    LAMPORTS_PER_SOL,
+  },
+  pubkey::{
+    Pubkey,
+  },
+};
+use solana_sdk::{
+  signature::{
+    Keypair,
+    Signer,
+  },
+};
+use vault::error::{
+  ErrorCode,
+};
+use vault_client::account::{
+  decode_epoch_claim,
+  decode_schedule_epoch,
+};
+use vault_client::instruction as vault_instruction;
+use vault_client::merkle::{
+  redemption_epoch_leaf,
+  MerkleTree,
+};
+use vault_client::pda::{
+  find_epoch_claim_address,
+  find_schedule_address,
+  find_schedule_epoch_address,
+};
+
+use common::{
+  assert_vault_error,
+  VaultTest,
+  PROGRAM_ID,
+};
+
+const EVENT_ID: u64 = 1;
+
+struct EpochTest {
+  test: VaultTest,
+  schedule: Pubkey,
+  vault_token: Pubkey,
+  users: Vec<Keypair>,
+  user_tokens: Vec<Pubkey>,
+}
+
+impl EpochTest {
+  async fn new() -> Self {
+    let mut test = VaultTest::new().await;
+    let admin = test.admin.insecure_clone();
+    let vault_signer = test.vault_signer;
+    let mint = test.create_mint(&Pubkey::new_unique(), 6).await;
+    let vault_token = test.create_token_account(&mint, &vault_signer, 1_000_000).await;
+
+    let users = vec![Keypair::new(), Keypair::new()];
+    let mut user_tokens = Vec::new();
+    for user in users.iter() {
+      // the user pays for its claim account
+      test.airdrop(&user.pubkey(), LAMPORTS_PER_SOL);
+      user_tokens.push(test.create_token_account(&mint, &user.pubkey(), 0).await);
+    }
+
+    let instruction = vault_instruction::create_epoch_schedule(&admin.pubkey(), &test.vault, EVENT_ID, &mint, &vault_token, &PROGRAM_ID);
+    test.process(&[instruction], &[&admin]).await.unwrap();
+
+    EpochTest {
+      test,
+      schedule: find_schedule_address(EVENT_ID, &PROGRAM_ID).0,
+      vault_token,
+      users,
+      user_tokens,
+    }
+  }
+
+  /// Returns the tree of the cumulative amounts of `users` in `epoch`.
+  fn merkle_tree(&self, epoch: u64, cumulative_amounts: &[u64]) -> MerkleTree {
+    let leaves = self.users.iter()
+      .zip(cumulative_amounts)
+      .map(|(user, cumulative_amount)| redemption_epoch_leaf(epoch, &user.pubkey(), *cumulative_amount))
+      .collect();
+    MerkleTree::new(leaves)
+  }
+
+  fn set_schedule_epoch(&self, epoch: u64, merkle_tree: &MerkleTree) -> Instruction {
+    vault_instruction::set_schedule_epoch(
+      &self.test.admin.pubkey(),
+      &self.test.vault,
+      &self.schedule,
+      epoch,
+      0,
+      merkle_tree.root(),
+      &PROGRAM_ID,
+    )
+  }
+
+  fn redeem_epoch(&self, index: usize, merkle_tree: &MerkleTree, cumulative_amount: u64) -> Instruction {
+    vault_instruction::redeem_epoch(
+      &self.test.vault,
+      &self.schedule,
+      merkle_tree.proof(index),
+      cumulative_amount,
+      &self.vault_token,
+      &self.users[index].pubkey(),
+      &self.user_tokens[index],
+      None,
+      &PROGRAM_ID,
+    )
+  }
+}
+
+#[tokio::test]
+async fn redeem_epoch_pays_cumulative_difference() {
+  let mut epoch_test = EpochTest::new().await;
+  let admin = epoch_test.test.admin.insecure_clone();
+  let user = epoch_test.users[0].insecure_clone();
+
+  let merkle_tree1 = epoch_test.merkle_tree(1, &[100, 50]);
+  let result = epoch_test.test.process(&[epoch_test.redeem_epoch(0, &merkle_tree1, 100)], &[&user]).await;
+  assert_vault_error(result, ErrorCode::ScheduleLocked);
+
+  let instruction = epoch_test.set_schedule_epoch(1, &merkle_tree1);
+  epoch_test.test.process(&[instruction], &[&admin]).await.unwrap();
+  let instruction = epoch_test.redeem_epoch(0, &merkle_tree1, 100);
+  epoch_test.test.refresh_blockhash().await;
+  epoch_test.test.process(std::slice::from_ref(&instruction), &[&user]).await.unwrap();
+  assert_eq!(epoch_test.test.token_balance(&epoch_test.user_tokens[0]).await, 100);
+
+  epoch_test.test.refresh_blockhash().await;
+  let result = epoch_test.test.process(&[instruction], &[&user]).await;
+  assert_vault_error(result, ErrorCode::Redeemed);
+
+  let merkle_tree2 = epoch_test.merkle_tree(2, &[250, 50]);
+  let instruction = epoch_test.set_schedule_epoch(2, &merkle_tree2);
+  epoch_test.test.process(&[instruction], &[&admin]).await.unwrap();
+
+  // proofs of a previous epoch no longer verify
+  epoch_test.test.refresh_blockhash().await;
+  let result = epoch_test.test.process(&[epoch_test.redeem_epoch(0, &merkle_tree1, 100)], &[&user]).await;
+  assert_vault_error(result, ErrorCode::Unauthorized);
+
+  let instruction = epoch_test.redeem_epoch(0, &merkle_tree2, 250);
+  epoch_test.test.process(&[instruction], &[&user]).await.unwrap();
+  assert_eq!(epoch_test.test.token_balance(&epoch_test.user_tokens[0]).await, 250);
+
+  let (epoch_claim, _) = find_epoch_claim_address(&epoch_test.schedule, &user.pubkey(), &PROGRAM_ID);
+  let epoch_claim = epoch_test.test.decode(&epoch_claim, decode_epoch_claim).await;
+  assert_eq!(epoch_claim.user, user.pubkey());
+  assert_eq!(epoch_claim.claimed_amount, 250);
+
+  // a user skipping epochs claims the whole cumulative amount at once
+  let user = epoch_test.users[1].insecure_clone();
+  let instruction = epoch_test.redeem_epoch(1, &merkle_tree2, 50);
+  epoch_test.test.process(&[instruction], &[&user]).await.unwrap();
+  assert_eq!(epoch_test.test.token_balance(&epoch_test.user_tokens[1]).await, 50);
+}
+
+#[tokio::test]
+async fn set_schedule_epoch_requires_increasing_epoch() {
+  let mut epoch_test = EpochTest::new().await;
+  let admin = epoch_test.test.admin.insecure_clone();
+  let merkle_tree = epoch_test.merkle_tree(2, &[100, 50]);
+
+  let instruction = epoch_test.set_schedule_epoch(2, &merkle_tree);
+  epoch_test.test.process(&[instruction], &[&admin]).await.unwrap();
+  let (schedule_epoch, _) = find_schedule_epoch_address(&epoch_test.schedule, &PROGRAM_ID);
+  let schedule_epoch = epoch_test.test.decode(&schedule_epoch, decode_schedule_epoch).await;
+  assert_eq!(schedule_epoch.epoch, 2);
+
+  for epoch in [1, 2] {
+    epoch_test.test.refresh_blockhash().await;
+    let instruction = epoch_test.set_schedule_epoch(epoch, &merkle_tree);
+    let result = epoch_test.test.process(&[instruction], &[&admin]).await;
+    assert_vault_error(result, ErrorCode::InvalidInput);
+  }
+}
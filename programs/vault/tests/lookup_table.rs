@@ -0,0 +1,121 @@
+mod common;
+
+use solana_program::{
+  pubkey::{
+    Pubkey,
+  },
+  system_program,
+};
+use solana_sdk::{
+  packet::{
+    PACKET_DATA_SIZE,
+  },
+  signature::{
+    Keypair,
+    Signer,
+  },
+  transaction::{
+    VersionedTransaction,
+  },
+};
+use vault_client::instruction::{
+  self as vault_instruction,
+  CreateUserTokenAccount,
+  RedeemOptions,
+};
+use vault_client::lookup_table::{
+  compile_message_v0,
+  create_vault_lookup_table,
+  decode_lookup_table,
+  vault_lookup_table_addresses,
+};
+use vault_client::merkle::{
+  redemption_multi_leaf,
+  MerkleTree,
+};
+use vault_client::pda::{
+  find_schedule_address,
+  get_associated_token_address,
+};
+
+use common::{
+  VaultTest,
+  PROGRAM_ID,
+};
+
+const EVENT_ID: u64 = 1;
+const AMOUNT: u64 = 100;
+const DEPTH: u32 = 17;
+
+#[tokio::test]
+async fn redeem_token_multi_fits_with_vault_lookup_table() {
+  let mut test = VaultTest::new().await;
+  let admin = test.admin.insecure_clone();
+  let vault_signer = test.vault_signer;
+  let mint = test.create_mint(&Pubkey::new_unique(), 6).await;
+  let vault_token = test.create_token_account(&mint, &vault_signer, 1_000).await;
+
+  // a tree of 2^DEPTH leaves, whose proof does not fit in a legacy transaction with the
+  // creation of the user token account
+  let user = Keypair::new();
+  test.airdrop(&user.pubkey(), 1_000_000_000);
+  let mut leaves = vec![redemption_multi_leaf(0, None, &user.pubkey(), &mint, AMOUNT, 0)];
+  leaves.extend((1..1u32 << DEPTH).map(|index| {
+    let mut leaf = [0u8; 32];
+    leaf[..4].copy_from_slice(&index.to_le_bytes());
+    leaf
+  }));
+  let merkle_tree = MerkleTree::new(leaves);
+  let instruction = vault_instruction::create_schedule(&admin.pubkey(), &test.vault, 1, EVENT_ID, 1, merkle_tree.root(), true, &mint, &vault_token, &system_program::ID, &system_program::ID, &PROGRAM_ID);
+  test.process(&[instruction], &[&admin]).await.unwrap();
+  let schedule = find_schedule_address(EVENT_ID, &PROGRAM_ID).0;
+
+  // the table is created from a rooted slot and usable from the slot after it is extended
+  test.context.warp_to_slot(2).unwrap();
+  let addresses = vault_lookup_table_addresses(&test.vault, &[schedule], &[vault_token], &PROGRAM_ID);
+  let (lookup_table, batches) = create_vault_lookup_table(&admin.pubkey(), &admin.pubkey(), 1, &addresses);
+  for batch in batches {
+    test.process(&batch, &[&admin]).await.unwrap();
+  }
+  test.context.warp_to_slot(3).unwrap();
+  let account = test.account(&lookup_table).await.unwrap();
+  let lookup_table = decode_lookup_table(&lookup_table, &account.data).unwrap();
+  assert_eq!(lookup_table.addresses, addresses);
+
+  let user_token = get_associated_token_address(&user.pubkey(), &mint);
+  let create_user_token = CreateUserTokenAccount {
+    mint,
+    payer: user.pubkey(),
+  };
+  let instruction = vault_instruction::redeem_token_multi(
+    &test.vault,
+    &schedule,
+    0,
+    0,
+    merkle_tree.proof(0),
+    &mint,
+    AMOUNT,
+    0,
+    &vault_token,
+    &user.pubkey(),
+    &user_token,
+    &RedeemOptions {
+      create_user_token0: Some(create_user_token),
+      ..RedeemOptions::default()
+    },
+    &PROGRAM_ID,
+  );
+  let legacy_transaction = test.transaction(std::slice::from_ref(&instruction), &[&user]);
+  let legacy_size = 1 + 64 * legacy_transaction.signatures.len() + legacy_transaction.message_data().len();
+  assert!(legacy_size > PACKET_DATA_SIZE, "{}", legacy_size);
+
+  let blockhash = test.context.get_new_latest_blockhash().await.unwrap();
+  let payer = test.context.payer.insecure_clone();
+  let message = compile_message_v0(&payer.pubkey(), &[instruction], &[lookup_table], blockhash).unwrap();
+  let transaction = VersionedTransaction::try_new(message, &[&payer, &user]).unwrap();
+  let size = 1 + 64 * transaction.signatures.len() + transaction.message.serialize().len();
+  assert!(size <= PACKET_DATA_SIZE, "{}", size);
+
+  test.context.banks_client.process_transaction(transaction).await.unwrap();
+  assert_eq!(test.token_balance(&user_token).await, AMOUNT);
+}
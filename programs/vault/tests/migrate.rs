@@ -0,0 +1,157 @@
+mod common;
+
+use anchor_lang::{
+  AccountSerialize,
+};
+use solana_program::{
+  pubkey::{
+    Pubkey,
+  },
+};
+use solana_sdk::{
+  account::{
+    Account,
+  },
+  signature::{
+    Keypair,
+    Signer,
+  },
+};
+use vault::error::{
+  ErrorCode,
+};
+use vault::state::{
+  Schedule,
+  Vault,
+};
+use vault_client::account::{
+  decode_schedule,
+  decode_vault,
+};
+use vault_client::instruction::{
+  self as vault_instruction,
+  RedeemOptions,
+};
+
+use common::{
+  assert_anchor_error,
+  assert_vault_error,
+  merkle_schedule,
+  recipients,
+  VaultTest,
+  PROGRAM_ID,
+};
+
+/// Replaces the data of the program account at `address`, keeping it rent exempt.
+async fn set_program_account(test: &mut VaultTest, address: &Pubkey, data: Vec<u8>) {
+  let account = Account {
+    lamports: test.rent_exempt(data.len()).await,
+    data,
+    owner: PROGRAM_ID,
+    executable: false,
+    rent_epoch: 0,
+  };
+  test.context.set_account(address, &account.into());
+}
+
+#[tokio::test]
+async fn migrate_vault_reallocates_legacy_layout() {
+  let mut test = VaultTest::new().await;
+  let owner = test.owner.insecure_clone();
+  let admin = test.admin.pubkey();
+  let vault_address = test.vault;
+
+  // a vault allocated for its serialized size, before the version byte
+  let mut data = Vec::new();
+  test.decode(&vault_address, decode_vault).await.try_serialize(&mut data).unwrap();
+  data.pop();
+  set_program_account(&mut test, &vault_address, data).await;
+  let vault = test.decode(&vault_address, decode_vault).await;
+  assert_eq!(vault.version, 0);
+  assert_eq!(vault.admins, vec![admin]);
+
+  let instruction = vault_instruction::set_vault(&owner.pubkey(), &vault_address, vec![admin], &PROGRAM_ID);
+  let result = test.process(&[instruction], &[&owner]).await;
+  assert_anchor_error(result, anchor_lang::error::ErrorCode::AccountDidNotDeserialize);
+
+  let other_owner = Keypair::new();
+  test.airdrop(&other_owner.pubkey(), 1_000_000_000);
+  let instruction = vault_instruction::migrate_vault(&other_owner.pubkey(), &vault_address, &PROGRAM_ID);
+  let result = test.process(&[instruction], &[&other_owner]).await;
+  assert_vault_error(result, ErrorCode::Unauthorized);
+
+  let instruction = vault_instruction::migrate_vault(&owner.pubkey(), &vault_address, &PROGRAM_ID);
+  test.process(&[instruction], &[&owner]).await.unwrap();
+  let account = test.account(&vault_address).await.unwrap();
+  assert_eq!(account.data.len(), 16 + Vault::size());
+  assert_eq!(account.lamports, test.rent_exempt(16 + Vault::size()).await);
+  let vault = decode_vault(&account.data).unwrap();
+  assert_eq!(vault.version, vault::constant::VAULT_VERSION);
+  assert_eq!(vault.owner, owner.pubkey());
+  assert_eq!(vault.admins, vec![admin]);
+
+  let instructions = [
+    vault_instruction::set_vault(&owner.pubkey(), &vault_address, vec![admin], &PROGRAM_ID),
+    vault_instruction::migrate_vault(&owner.pubkey(), &vault_address, &PROGRAM_ID),
+  ];
+  let result = test.process(&instructions, &[&owner]).await;
+  assert_vault_error(result, ErrorCode::InvalidInput);
+}
+
+#[tokio::test]
+async fn migrate_schedule_keeps_redemptions() {
+  let mut test = VaultTest::new().await;
+  let admin = test.admin.insecure_clone();
+  let vault_signer = test.vault_signer;
+  let mint = test.create_mint(&Pubkey::new_unique(), 6).await;
+  let vault_token = test.create_token_account(&mint, &vault_signer, 1_000).await;
+  let recipients = recipients(&[100, 200]);
+  let schedule_address = test.create_schedule(1, 1, &recipients, &mint, &vault_token).await;
+
+  // a schedule allocated before the version, verify_user_token_owner and user_count fields
+  let mut data = test.account(&schedule_address).await.unwrap().data;
+  data.drain(8 + Schedule::size(2) - 4..8 + Schedule::size(2));
+  set_program_account(&mut test, &schedule_address, data).await;
+  assert_eq!(test.decode(&schedule_address, decode_schedule).await.version, 0);
+
+  // legacy schedules keep working until they are migrated
+  let user = recipients[0].user.insecure_clone();
+  let user_token = test.create_token_account(&mint, &user.pubkey(), 0).await;
+  let instruction = vault_instruction::redeem_token(
+    &test.vault,
+    &schedule_address,
+    0,
+    0,
+    merkle_schedule(&recipients).proof(0),
+    100,
+    0,
+    &vault_token,
+    &user.pubkey(),
+    &user_token,
+    &RedeemOptions::default(),
+    &PROGRAM_ID,
+  );
+  test.process(&[instruction], &[&user]).await.unwrap();
+  assert_eq!(test.token_balance(&user_token).await, 100);
+
+  // the owner check and tranches are only stored in the current layout
+  let instruction = vault_instruction::append_schedule_tranche(&admin.pubkey(), &test.vault, &schedule_address, 1, 1, [1u8; 32], &PROGRAM_ID);
+  let result = test.process(&[instruction], &[&admin]).await;
+  assert_vault_error(result, ErrorCode::InvalidInput);
+  let instruction_owner_check = vault_instruction::set_schedule_owner_check(&admin.pubkey(), &test.vault, &schedule_address, true, &PROGRAM_ID);
+  let result = test.process(std::slice::from_ref(&instruction_owner_check), &[&admin]).await;
+  assert_vault_error(result, ErrorCode::InvalidInput);
+
+  let instruction = vault_instruction::migrate_schedule(&admin.pubkey(), &test.vault, &schedule_address, &PROGRAM_ID);
+  test.process(&[instruction], &[&admin]).await.unwrap();
+  let account = test.account(&schedule_address).await.unwrap();
+  assert_eq!(account.data.len(), 16 + Schedule::size(2));
+  let schedule = decode_schedule(&account.data).unwrap();
+  assert_eq!(schedule.version, vault::constant::SCHEDULE_VERSION);
+  assert_eq!(schedule.redemptions, vec![true, false]);
+  assert_eq!(schedule.user_count, 2);
+
+  test.refresh_blockhash().await;
+  test.process(&[instruction_owner_check], &[&admin]).await.unwrap();
+  assert!(test.decode(&schedule_address, decode_schedule).await.verify_user_token_owner);
+}
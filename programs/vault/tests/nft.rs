@@ -0,0 +1,226 @@
+//! Token Metadata and Bubblegum are not loaded in the program test, edition and compressed
+//! redemptions are only covered up to the checks preceding their CPI.
+
+mod common;
+
+use solana_program::{
+  pubkey::{
+    Pubkey,
+  },
+};
+use solana_sdk::{
+  signature::{
+    Keypair,
+    Signer,
+  },
+};
+use vault::error::{
+  ErrorCode,
+};
+use vault_client::account::{
+  decode_schedule,
+};
+use vault_client::instruction::{
+  self as vault_instruction,
+  CompressedNftLeaf,
+  CreateUserTokenAccount,
+  NewEditionAccounts,
+};
+use vault_client::merkle::{
+  redemption_nft_leaf,
+  MerkleTree,
+};
+use vault_client::pda::{
+  find_schedule_address,
+  get_asset_id,
+  get_associated_token_address,
+};
+
+use common::{
+  assert_vault_error,
+  VaultTest,
+  PROGRAM_ID,
+};
+
+const EVENT_ID: u64 = 1;
+
+/// Creates an NFT schedule with one leaf per `(asset, edition, timestamp)` for `user`.
+async fn create_nft_schedule(test: &mut VaultTest, user: &Pubkey, leaves: &[(Pubkey, u64, i64)]) -> (Pubkey, MerkleTree) {
+  let admin = test.admin.insecure_clone();
+  let leaves = leaves.iter()
+    .enumerate()
+    .map(|(index, (asset, edition, timestamp))| redemption_nft_leaf(index as u16, *timestamp, user, asset, *edition))
+    .collect();
+  let merkle_tree = MerkleTree::new(leaves);
+
+  let instruction = vault_instruction::create_nft_schedule(
+    &admin.pubkey(),
+    &test.vault,
+    merkle_tree.leaf_count() as u16,
+    EVENT_ID,
+    0,
+    merkle_tree.root(),
+    &PROGRAM_ID,
+  );
+  test.process(&[instruction], &[&admin]).await.unwrap();
+  (find_schedule_address(EVENT_ID, &PROGRAM_ID).0, merkle_tree)
+}
+
+fn new_edition_accounts() -> NewEditionAccounts {
+  NewEditionAccounts {
+    new_mint: Pubkey::new_unique(),
+    new_metadata: Pubkey::new_unique(),
+    new_edition: Pubkey::new_unique(),
+    edition_mark: Pubkey::new_unique(),
+    new_metadata_update_authority: Pubkey::new_unique(),
+  }
+}
+
+fn compressed_nft_leaf(merkle_tree: &Pubkey, nonce: u64) -> CompressedNftLeaf {
+  CompressedNftLeaf {
+    merkle_tree: *merkle_tree,
+    tree_authority: Pubkey::new_unique(),
+    leaf_delegate: Pubkey::new_unique(),
+    root: [0u8; 32],
+    data_hash: [0u8; 32],
+    creator_hash: [0u8; 32],
+    nonce,
+    leaf_index: 0,
+    proof: vec![],
+  }
+}
+
+#[tokio::test]
+async fn redeem_nft_transfers_asset() {
+  let mut test = VaultTest::new().await;
+  let vault_signer = test.vault_signer;
+  let user = Keypair::new();
+  let asset = test.create_mint(&Pubkey::new_unique(), 0).await;
+  let other_asset = test.create_mint(&Pubkey::new_unique(), 0).await;
+  let vault_token = test.create_token_account(&asset, &vault_signer, 1).await;
+  let other_vault_token = test.create_token_account(&other_asset, &vault_signer, 1).await;
+  let (schedule, merkle_tree) = create_nft_schedule(&mut test, &user.pubkey(), &[(asset, 0, 0)]).await;
+
+  let user_token = get_associated_token_address(&user.pubkey(), &asset);
+  let create_user_token0 = CreateUserTokenAccount {
+    mint: asset,
+    payer: test.context.payer.pubkey(),
+  };
+  let redeem_nft = |vault_token: &Pubkey| vault_instruction::redeem_nft(
+    &test.vault,
+    &schedule,
+    0,
+    0,
+    merkle_tree.proof(0),
+    &asset,
+    vault_token,
+    &user.pubkey(),
+    &user_token,
+    Some(&create_user_token0),
+    None,
+    &PROGRAM_ID,
+  );
+  let wrong_vault_token = redeem_nft(&other_vault_token);
+  let instruction = redeem_nft(&vault_token);
+
+  let result = test.process(&[wrong_vault_token], &[&user]).await;
+  assert_vault_error(result, ErrorCode::InvalidTokenMint);
+
+  test.process(&[instruction], &[&user]).await.unwrap();
+  assert_eq!(test.token_balance(&vault_token).await, 0);
+  assert_eq!(test.token_balance(&user_token).await, 1);
+  let schedule = test.decode(&schedule, decode_schedule).await;
+  assert_eq!(schedule.redemptions, vec![true]);
+}
+
+#[tokio::test]
+async fn redeem_nft_rejects_other_asset() {
+  let mut test = VaultTest::new().await;
+  let vault_signer = test.vault_signer;
+  let user = Keypair::new();
+  let asset = test.create_mint(&Pubkey::new_unique(), 0).await;
+  let other_asset = test.create_mint(&Pubkey::new_unique(), 0).await;
+  let other_vault_token = test.create_token_account(&other_asset, &vault_signer, 1).await;
+  let user_token = test.create_token_account(&other_asset, &user.pubkey(), 0).await;
+  let (schedule, merkle_tree) = create_nft_schedule(&mut test, &user.pubkey(), &[(asset, 0, 0)]).await;
+
+  let instruction = vault_instruction::redeem_nft(
+    &test.vault,
+    &schedule,
+    0,
+    0,
+    merkle_tree.proof(0),
+    &other_asset,
+    &other_vault_token,
+    &user.pubkey(),
+    &user_token,
+    None,
+    None,
+    &PROGRAM_ID,
+  );
+  let result = test.process(&[instruction], &[&user]).await;
+  assert_vault_error(result, ErrorCode::Unauthorized);
+}
+
+#[tokio::test]
+async fn redeem_nft_edition_rejects_invalid_edition() {
+  let mut test = VaultTest::new().await;
+  let user = Keypair::new();
+  let asset = Pubkey::new_unique();
+  let vault_token = Pubkey::new_unique();
+  let (schedule, merkle_tree) = create_nft_schedule(&mut test, &user.pubkey(), &[(asset, 1, 0)]).await;
+
+  let redeem_edition = |edition: u64| vault_instruction::redeem_nft_edition(
+    &test.vault,
+    &schedule,
+    0,
+    0,
+    merkle_tree.proof(0),
+    &asset,
+    edition,
+    &vault_token,
+    &user.pubkey(),
+    &new_edition_accounts(),
+    None,
+    &PROGRAM_ID,
+  );
+  let zero_edition = redeem_edition(0);
+  let other_edition = redeem_edition(2);
+
+  let result = test.process(&[zero_edition], &[&user]).await;
+  assert_vault_error(result, ErrorCode::InvalidInput);
+
+  let result = test.process(&[other_edition], &[&user]).await;
+  assert_vault_error(result, ErrorCode::Unauthorized);
+}
+
+#[tokio::test]
+async fn redeem_compressed_nft_rejects_locked_or_other_asset() {
+  let mut test = VaultTest::new().await;
+  let user = Keypair::new();
+  let merkle_tree_account = Pubkey::new_unique();
+  let asset = get_asset_id(&merkle_tree_account, 7);
+  let unlock_timestamp = test.unix_timestamp().await + 3600;
+  let (schedule, merkle_tree) = create_nft_schedule(&mut test, &user.pubkey(), &[(asset, 0, unlock_timestamp)]).await;
+
+  let redeem_compressed = |nonce: u64| vault_instruction::redeem_compressed_nft(
+    &test.vault,
+    &schedule,
+    0,
+    unlock_timestamp,
+    merkle_tree.proof(0),
+    &user.pubkey(),
+    &compressed_nft_leaf(&merkle_tree_account, nonce),
+    None,
+    &PROGRAM_ID,
+  );
+  let locked = redeem_compressed(7);
+  let other_asset = redeem_compressed(8);
+
+  let result = test.process(&[locked], &[&user]).await;
+  assert_vault_error(result, ErrorCode::ScheduleLocked);
+
+  test.set_unix_timestamp(unlock_timestamp).await;
+  let result = test.process(&[other_asset], &[&user]).await;
+  assert_vault_error(result, ErrorCode::Unauthorized);
+}
@@ -0,0 +1,831 @@
+mod common;
+
+use solana_program::{
+  instruction::{
+    Instruction,
+  },
+  native_token::{
+    LAMPORTS_PER_SOL,
+  },
+  pubkey::{
+    Pubkey,
+  },
+  system_program,
+};
+use solana_program_test::{
+  BanksClientError,
+};
+use solana_sdk::{
+  signature::{
+    Keypair,
+    Signer,
+  },
+};
+use vault::error::{
+  ErrorCode,
+};
+use vault::external::spl_token::{
+  native_mint::ID as NATIVE_MINT_ID,
+};
+use vault_client::account::{
+  decode_claim_status,
+  decode_schedule,
+  decode_schedule_mint,
+  decode_vault_reserve,
+  is_redeemed_from_slice,
+  redemption_data_slice,
+  redemption_offset,
+  redemptions_len_data_slice,
+  redemptions_len_from_slice,
+};
+use vault_client::instruction::{
+  self as vault_instruction,
+  ClaimFeeAccounts,
+  CreateUserTokenAccount,
+  RedeemOptions,
+};
+use vault_client::merkle::{
+  redemption_leaf,
+  redemption_multi_leaf,
+  MerkleTree,
+};
+use vault_client::pda::{
+  find_schedule_address,
+  find_schedule_mint_address,
+  find_vault_reserve_address,
+  get_associated_token_address,
+};
+
+use common::{
+  assert_vault_error,
+  merkle_schedule,
+  recipients,
+  Recipient,
+  VaultTest,
+  PROGRAM_ID,
+};
+
+const EVENT_ID: u64 = 1;
+const VAULT_BALANCE: u64 = 1_000_000;
+
+/// Distribution schedule unlocked since `timestamp`, funded with `VAULT_BALANCE`.
+struct TokenSchedule {
+  vault: Pubkey,
+  schedule: Pubkey,
+  mint: Pubkey,
+  vault_token: Pubkey,
+  recipients: Vec<Recipient>,
+  merkle_tree: MerkleTree,
+}
+
+impl TokenSchedule {
+  async fn new(test: &mut VaultTest, amounts: &[u64], timestamp: i64) -> Self {
+    let vault_signer = test.vault_signer;
+    let mint = test.create_mint(&Pubkey::new_unique(), 6).await;
+    let vault_token = test.create_token_account(&mint, &vault_signer, VAULT_BALANCE).await;
+    let recipients = recipients(amounts);
+    let schedule = test.create_schedule(EVENT_ID, timestamp, &recipients, &mint, &vault_token).await;
+    let merkle_tree = merkle_schedule(&recipients);
+
+    TokenSchedule {
+      vault: test.vault,
+      schedule,
+      mint,
+      vault_token,
+      recipients,
+      merkle_tree,
+    }
+  }
+
+  fn user(&self, index: u16) -> &Keypair {
+    &self.recipients[usize::from(index)].user
+  }
+
+  fn redeem(&self, index: u16, vault_token: &Pubkey, user_token: &Pubkey) -> Instruction {
+    self.redeem_with_tranches(index, vault_token, user_token, false)
+  }
+
+  fn redeem_with_tranches(&self, index: u16, vault_token: &Pubkey, user_token: &Pubkey, use_tranches: bool) -> Instruction {
+    let recipient = &self.recipients[usize::from(index)];
+    vault_instruction::redeem_token(
+      &self.vault,
+      &self.schedule,
+      index,
+      0,
+      self.merkle_tree.proof(usize::from(index)),
+      recipient.receiving_amount,
+      recipient.sending_amount,
+      vault_token,
+      &recipient.user.pubkey(),
+      user_token,
+      &RedeemOptions {
+        use_tranches,
+        ..RedeemOptions::default()
+      },
+      &PROGRAM_ID,
+    )
+  }
+}
+
+#[tokio::test]
+async fn redeem_token_transfers_receiving_amount() {
+  let mut test = VaultTest::new().await;
+  let token_schedule = TokenSchedule::new(&mut test, &[100, 200], 1).await;
+  let user = token_schedule.user(1).insecure_clone();
+  let user_token = test.create_token_account(&token_schedule.mint, &user.pubkey(), 0).await;
+
+  let instruction = token_schedule.redeem(1, &token_schedule.vault_token, &user_token);
+  test.process(&[instruction], &[&user]).await.unwrap();
+  assert_eq!(test.token_balance(&user_token).await, 200);
+  assert_eq!(test.token_balance(&token_schedule.vault_token).await, VAULT_BALANCE - 200);
+
+  let schedule = test.decode(&token_schedule.schedule, decode_schedule).await;
+  assert_eq!(schedule.redemptions, vec![false, true]);
+  let return_data = test.simulate(vault_instruction::get_claim_status(&token_schedule.schedule, 1, &PROGRAM_ID)).await;
+  let claim_status = decode_claim_status(&return_data).unwrap();
+  assert!(claim_status.is_active);
+  assert!(claim_status.is_redeemed);
+}
+
+#[tokio::test]
+async fn redemption_data_slice_reads_flags_within_user_count() {
+  let mut test = VaultTest::new().await;
+  let token_schedule = TokenSchedule::new(&mut test, &[100, 200], 1).await;
+  let user = token_schedule.user(1).insecure_clone();
+  let user_token = test.create_token_account(&token_schedule.mint, &user.pubkey(), 0).await;
+
+  let instruction = token_schedule.redeem(1, &token_schedule.vault_token, &user_token);
+  test.process(&[instruction], &[&user]).await.unwrap();
+
+  let data = test.account(&token_schedule.schedule).await.unwrap().data;
+  let slice = |(offset, length): (usize, usize)| &data[offset..offset + length];
+  let user_count = redemptions_len_from_slice(slice(redemptions_len_data_slice())).unwrap();
+  assert_eq!(user_count, 2);
+  assert_eq!(is_redeemed_from_slice(slice(redemption_data_slice(0, user_count).unwrap())), Some(false));
+  assert_eq!(is_redeemed_from_slice(slice(redemption_data_slice(1, user_count).unwrap())), Some(true));
+  // the byte after the last flag is the layout version
+  assert_eq!(redemption_data_slice(2, user_count), None);
+  assert_eq!(redemption_offset(u16::MAX, user_count), None);
+}
+
+#[tokio::test]
+async fn redeem_token_rejects_reused_index() {
+  let mut test = VaultTest::new().await;
+  let token_schedule = TokenSchedule::new(&mut test, &[100], 1).await;
+  let user = token_schedule.user(0).insecure_clone();
+  let user_token = test.create_token_account(&token_schedule.mint, &user.pubkey(), 0).await;
+
+  let instruction = token_schedule.redeem(0, &token_schedule.vault_token, &user_token);
+  test.process(std::slice::from_ref(&instruction), &[&user]).await.unwrap();
+
+  test.refresh_blockhash().await;
+  let result = test.process(&[instruction], &[&user]).await;
+  assert_vault_error(result, ErrorCode::Redeemed);
+  assert_eq!(test.token_balance(&user_token).await, 100);
+}
+
+#[tokio::test]
+async fn redeem_token_rejects_wrong_vault_token() {
+  let mut test = VaultTest::new().await;
+  let token_schedule = TokenSchedule::new(&mut test, &[100], 1).await;
+  let vault_signer = test.vault_signer;
+  let user = token_schedule.user(0).insecure_clone();
+  let user_token = test.create_token_account(&token_schedule.mint, &user.pubkey(), 0).await;
+  let other_vault_token = test.create_token_account(&token_schedule.mint, &vault_signer, VAULT_BALANCE).await;
+
+  let instruction = token_schedule.redeem(0, &other_vault_token, &user_token);
+  let result = test.process(&[instruction], &[&user]).await;
+  assert_vault_error(result, ErrorCode::InvalidAccount);
+}
+
+#[tokio::test]
+async fn redeem_token_pays_user_token_of_another_owner() {
+  let mut test = VaultTest::new().await;
+  let token_schedule = TokenSchedule::new(&mut test, &[100], 1).await;
+  let user = token_schedule.user(0).insecure_clone();
+  let other_token = test.create_token_account(&token_schedule.mint, &Pubkey::new_unique(), 0).await;
+  assert!(!test.decode(&token_schedule.schedule, decode_schedule).await.verify_user_token_owner);
+
+  let instruction = token_schedule.redeem(0, &token_schedule.vault_token, &other_token);
+  test.process(&[instruction], &[&user]).await.unwrap();
+  assert_eq!(test.token_balance(&other_token).await, 100);
+}
+
+#[tokio::test]
+async fn redeem_token_rejects_wrong_user_token() {
+  let mut test = VaultTest::new().await;
+  let admin = test.admin.insecure_clone();
+  let token_schedule = TokenSchedule::new(&mut test, &[100], 1).await;
+  let user = token_schedule.user(0).insecure_clone();
+  let other_token = test.create_token_account(&token_schedule.mint, &Pubkey::new_unique(), 0).await;
+
+  // only admins can require the owner check
+  test.airdrop(&user.pubkey(), LAMPORTS_PER_SOL);
+  let instruction = vault_instruction::set_schedule_owner_check(&user.pubkey(), &test.vault, &token_schedule.schedule, true, &PROGRAM_ID);
+  let result = test.process(&[instruction], &[&user]).await;
+  assert_vault_error(result, ErrorCode::Unauthorized);
+
+  let instruction = vault_instruction::set_schedule_owner_check(&admin.pubkey(), &test.vault, &token_schedule.schedule, true, &PROGRAM_ID);
+  test.process(&[instruction], &[&admin]).await.unwrap();
+  assert!(test.decode(&token_schedule.schedule, decode_schedule).await.verify_user_token_owner);
+
+  let instruction = token_schedule.redeem(0, &token_schedule.vault_token, &other_token);
+  let result = test.process(&[instruction], &[&user]).await;
+  assert_vault_error(result, ErrorCode::InvalidTokenOwner);
+
+  let user_token = test.create_token_account(&token_schedule.mint, &user.pubkey(), 0).await;
+  let instruction = token_schedule.redeem(0, &token_schedule.vault_token, &user_token);
+  test.process(&[instruction], &[&user]).await.unwrap();
+  assert_eq!(test.token_balance(&user_token).await, 100);
+}
+
+#[tokio::test]
+async fn redeem_token_rejects_invalid_proof() {
+  let mut test = VaultTest::new().await;
+  let mut token_schedule = TokenSchedule::new(&mut test, &[100, 200], 1).await;
+  let user = token_schedule.user(0).insecure_clone();
+  let user_token = test.create_token_account(&token_schedule.mint, &user.pubkey(), 0).await;
+
+  token_schedule.recipients[0].receiving_amount = 200;
+  let instruction = token_schedule.redeem(0, &token_schedule.vault_token, &user_token);
+  let result = test.process(&[instruction], &[&user]).await;
+  assert_vault_error(result, ErrorCode::Unauthorized);
+}
+
+#[tokio::test]
+async fn redeem_token_rejects_index_out_of_range() {
+  let mut test = VaultTest::new().await;
+  let admin = test.admin.insecure_clone();
+  let vault_signer = test.vault_signer;
+  let mint = test.create_mint(&Pubkey::new_unique(), 6).await;
+  let vault_token = test.create_token_account(&mint, &vault_signer, VAULT_BALANCE).await;
+  let recipients = recipients(&[100, 200]);
+  let merkle_tree = merkle_schedule(&recipients);
+
+  // the root covers two leaves, the schedule only tracks the first one
+  let instruction = vault_instruction::create_schedule(
+    &admin.pubkey(),
+    &test.vault,
+    1,
+    EVENT_ID,
+    1,
+    merkle_tree.root(),
+    false,
+    &mint,
+    &vault_token,
+    &system_program::ID,
+    &system_program::ID,
+    &PROGRAM_ID,
+  );
+  test.process(&[instruction], &[&admin]).await.unwrap();
+  let token_schedule = TokenSchedule {
+    vault: test.vault,
+    schedule: find_schedule_address(EVENT_ID, &PROGRAM_ID).0,
+    mint,
+    vault_token,
+    recipients,
+    merkle_tree,
+  };
+
+  let user = token_schedule.user(1).insecure_clone();
+  let user_token = test.create_token_account(&token_schedule.mint, &user.pubkey(), 0).await;
+  let instruction = token_schedule.redeem(1, &token_schedule.vault_token, &user_token);
+  let result = test.process(&[instruction], &[&user]).await;
+  assert_vault_error(result, ErrorCode::IndexOutOfRange);
+
+  let instruction = vault_instruction::get_claim_status(&token_schedule.schedule, 1, &PROGRAM_ID);
+  let result = test.process(&[instruction], &[]).await;
+  assert_vault_error(result, ErrorCode::IndexOutOfRange);
+}
+
+#[tokio::test]
+async fn redeem_token_rejects_locked_schedule() {
+  let mut test = VaultTest::new().await;
+  let unlock_timestamp = test.unix_timestamp().await + 3600;
+  let token_schedule = TokenSchedule::new(&mut test, &[100], unlock_timestamp).await;
+  let user = token_schedule.user(0).insecure_clone();
+  let user_token = test.create_token_account(&token_schedule.mint, &user.pubkey(), 0).await;
+
+  let instruction = token_schedule.redeem(0, &token_schedule.vault_token, &user_token);
+  let result = test.process(std::slice::from_ref(&instruction), &[&user]).await;
+  assert_vault_error(result, ErrorCode::ScheduleLocked);
+
+  test.set_unix_timestamp(unlock_timestamp).await;
+  test.refresh_blockhash().await;
+  test.process(&[instruction], &[&user]).await.unwrap();
+  assert_eq!(test.token_balance(&user_token).await, 100);
+}
+
+#[tokio::test]
+async fn redeem_token_rejects_inactive_schedule() {
+  let mut test = VaultTest::new().await;
+  let admin = test.admin.insecure_clone();
+  let token_schedule = TokenSchedule::new(&mut test, &[100], 1).await;
+  let user = token_schedule.user(0).insecure_clone();
+  let user_token = test.create_token_account(&token_schedule.mint, &user.pubkey(), 0).await;
+
+  let instruction = vault_instruction::set_schedule_status(&admin.pubkey(), &test.vault, &token_schedule.schedule, false, &PROGRAM_ID);
+  test.process(&[instruction], &[&admin]).await.unwrap();
+
+  let instruction = token_schedule.redeem(0, &token_schedule.vault_token, &user_token);
+  let result = test.process(&[instruction], &[&user]).await;
+  assert_vault_error(result, ErrorCode::ScheduleUnavailable);
+}
+
+#[tokio::test]
+async fn redeem_token_multi_rejects_distribution_schedule() {
+  let mut test = VaultTest::new().await;
+  let token_schedule = TokenSchedule::new(&mut test, &[100], 1).await;
+  let user = token_schedule.user(0).insecure_clone();
+  let user_token = test.create_token_account(&token_schedule.mint, &user.pubkey(), 0).await;
+
+  let instruction = vault_instruction::redeem_token_multi(
+    &test.vault,
+    &token_schedule.schedule,
+    0,
+    0,
+    token_schedule.merkle_tree.proof(0),
+    &token_schedule.mint,
+    100,
+    0,
+    &token_schedule.vault_token,
+    &user.pubkey(),
+    &user_token,
+    &RedeemOptions::default(),
+    &PROGRAM_ID,
+  );
+  let result = test.process(&[instruction], &[&user]).await;
+  assert_vault_error(result, ErrorCode::InvalidAccount);
+}
+
+#[tokio::test]
+async fn redeem_token_creates_user_token_account() {
+  let mut test = VaultTest::new().await;
+  let token_schedule = TokenSchedule::new(&mut test, &[100], 1).await;
+  let user = token_schedule.user(0).insecure_clone();
+  let user_token = get_associated_token_address(&user.pubkey(), &token_schedule.mint);
+  let create_user_token0 = CreateUserTokenAccount {
+    mint: token_schedule.mint,
+    payer: test.context.payer.pubkey(),
+  };
+
+  let recipient = &token_schedule.recipients[0];
+  let instruction = vault_instruction::redeem_token(
+    &test.vault,
+    &token_schedule.schedule,
+    0,
+    0,
+    token_schedule.merkle_tree.proof(0),
+    recipient.receiving_amount,
+    recipient.sending_amount,
+    &token_schedule.vault_token,
+    &user.pubkey(),
+    &user_token,
+    &RedeemOptions {
+      create_user_token0: Some(create_user_token0),
+      ..RedeemOptions::default()
+    },
+    &PROGRAM_ID,
+  );
+  test.process(&[instruction], &[&user]).await.unwrap();
+  let user_token = test.token_account(&user_token).await;
+  assert_eq!(user_token.owner, user.pubkey());
+  assert_eq!(user_token.amount, 100);
+}
+
+#[tokio::test]
+async fn redeem_token_charges_claim_fee() {
+  let mut test = VaultTest::new().await;
+  let owner = test.owner.insecure_clone();
+  let treasury = Keypair::new().pubkey();
+  test.airdrop(&treasury, LAMPORTS_PER_SOL);
+  let token_schedule = TokenSchedule::new(&mut test, &[1_000], 1).await;
+  let user = token_schedule.user(0).insecure_clone();
+  test.airdrop(&user.pubkey(), LAMPORTS_PER_SOL);
+  let user_token = test.create_token_account(&token_schedule.mint, &user.pubkey(), 0).await;
+  let treasury_token = test.create_token_account(&token_schedule.mint, &treasury, 0).await;
+
+  let instruction = vault_instruction::set_claim_fee(&owner.pubkey(), &test.vault, &treasury, 5_000, 100, &PROGRAM_ID);
+  test.process(&[instruction], &[&owner]).await.unwrap();
+
+  let instruction = token_schedule.redeem(0, &token_schedule.vault_token, &user_token);
+  let result = test.process(&[instruction], &[&user]).await;
+  assert_vault_error(result, ErrorCode::InvalidAccount);
+
+  let claim_fee = ClaimFeeAccounts {
+    treasury,
+    treasury_token: Some(treasury_token),
+  };
+  let instruction = vault_instruction::redeem_token(
+    &test.vault,
+    &token_schedule.schedule,
+    0,
+    0,
+    token_schedule.merkle_tree.proof(0),
+    1_000,
+    0,
+    &token_schedule.vault_token,
+    &user.pubkey(),
+    &user_token,
+    &RedeemOptions {
+      claim_fee: Some(claim_fee),
+      ..RedeemOptions::default()
+    },
+    &PROGRAM_ID,
+  );
+  test.process(&[instruction], &[&user]).await.unwrap();
+  assert_eq!(test.token_balance(&user_token).await, 990);
+  assert_eq!(test.token_balance(&treasury_token).await, 10);
+  assert_eq!(test.lamports(&treasury).await, LAMPORTS_PER_SOL + 5_000);
+  assert_eq!(test.lamports(&user.pubkey()).await, LAMPORTS_PER_SOL - 5_000);
+}
+
+#[tokio::test]
+async fn redeem_token_pays_sol_and_settles_liabilities() {
+  let mut test = VaultTest::new().await;
+  let admin = test.admin.insecure_clone();
+  let vault_signer = test.vault_signer;
+  test.airdrop(&vault_signer, 5 * LAMPORTS_PER_SOL);
+  let recipients = recipients(&[LAMPORTS_PER_SOL, 4 * LAMPORTS_PER_SOL]);
+  let schedule = test.create_schedule(EVENT_ID, 1, &recipients, &system_program::ID, &vault_signer).await;
+  let merkle_tree = merkle_schedule(&recipients);
+
+  let instruction = vault_instruction::set_sol_liabilities(&admin.pubkey(), &test.vault, 5 * LAMPORTS_PER_SOL, &PROGRAM_ID);
+  test.process(&[instruction], &[&admin]).await.unwrap();
+
+  let vault = test.vault;
+  let redeem = |index: u16| {
+    let user = &recipients[usize::from(index)].user;
+    vault_instruction::redeem_token(
+      &vault,
+      &schedule,
+      index,
+      0,
+      merkle_tree.proof(usize::from(index)),
+      recipients[usize::from(index)].receiving_amount,
+      0,
+      &vault_signer,
+      &user.pubkey(),
+      &user.pubkey(),
+      &RedeemOptions::default(),
+      &PROGRAM_ID,
+    )
+  };
+  let instruction0 = redeem(0);
+  let instruction1 = redeem(1);
+
+  let user = recipients[0].user.insecure_clone();
+  test.airdrop(&user.pubkey(), LAMPORTS_PER_SOL);
+  test.process(&[instruction0], &[&user]).await.unwrap();
+  assert_eq!(test.lamports(&user.pubkey()).await, 2 * LAMPORTS_PER_SOL);
+  let (vault_reserve, _) = find_vault_reserve_address(&test.vault, &PROGRAM_ID);
+  let vault_reserve = test.decode(&vault_reserve, decode_vault_reserve).await;
+  assert_eq!(vault_reserve.sol_liabilities, 4 * LAMPORTS_PER_SOL);
+
+  // the rent exempt minimum of the vault signer is not redeemable
+  let user = recipients[1].user.insecure_clone();
+  test.airdrop(&user.pubkey(), LAMPORTS_PER_SOL);
+  let result = test.process(&[instruction1], &[&user]).await;
+  assert_vault_error(result, ErrorCode::InsufficientReserve);
+}
+
+/// Redeems `LAMPORTS_PER_SOL` into `user_token` from a wrapped SOL schedule of `user`.
+async fn redeem_wrapped_sol(test: &mut VaultTest, user: &Keypair, user_token: &Pubkey) -> Result<Pubkey, BanksClientError> {
+  let vault_signer = test.vault_signer;
+  let vault_token = test.create_token_account(&NATIVE_MINT_ID, &vault_signer, 2 * LAMPORTS_PER_SOL).await;
+  let recipients = vec![Recipient {
+    user: user.insecure_clone(),
+    receiving_amount: LAMPORTS_PER_SOL,
+    sending_amount: 0,
+  }];
+  let schedule = test.create_schedule(EVENT_ID, 1, &recipients, &NATIVE_MINT_ID, &vault_token).await;
+
+  let instruction = vault_instruction::redeem_token(
+    &test.vault,
+    &schedule,
+    0,
+    0,
+    merkle_schedule(&recipients).proof(0),
+    LAMPORTS_PER_SOL,
+    0,
+    &vault_token,
+    &user.pubkey(),
+    user_token,
+    &RedeemOptions::default(),
+    &PROGRAM_ID,
+  );
+  test.process(&[instruction], &[user]).await?;
+  Ok(vault_token)
+}
+
+#[tokio::test]
+async fn redeem_token_unwraps_wrapped_sol() {
+  let mut test = VaultTest::new().await;
+  let user = Keypair::new();
+  let user_token = test.create_token_account(&NATIVE_MINT_ID, &user.pubkey(), 0).await;
+  let user_token_rent = test.lamports(&user_token).await;
+
+  let vault_token = redeem_wrapped_sol(&mut test, &user, &user_token).await.unwrap();
+  // user_token is closed into the user's wallet
+  assert!(test.account(&user_token).await.is_none());
+  assert_eq!(test.lamports(&user.pubkey()).await, user_token_rent + LAMPORTS_PER_SOL);
+  assert_eq!(test.token_balance(&vault_token).await, LAMPORTS_PER_SOL);
+}
+
+#[tokio::test]
+async fn redeem_token_rejects_wrapped_sol_of_another_owner() {
+  let mut test = VaultTest::new().await;
+  let user = Keypair::new();
+  let other_token = test.create_token_account(&NATIVE_MINT_ID, &Pubkey::new_unique(), 0).await;
+
+  // only the owner of user_token can close it to unwrap the SOL
+  let result = redeem_wrapped_sol(&mut test, &user, &other_token).await.map(|_| ());
+  assert_vault_error(result, ErrorCode::InvalidTokenOwner);
+}
+
+#[tokio::test]
+async fn redeem_token_multi_transfers_leaf_mint() {
+  let mut test = VaultTest::new().await;
+  let admin = test.admin.insecure_clone();
+  let vault_signer = test.vault_signer;
+  let mint0 = test.create_mint(&Pubkey::new_unique(), 6).await;
+  let mint1 = test.create_mint(&Pubkey::new_unique(), 9).await;
+  let vault_token0 = test.create_token_account(&mint0, &vault_signer, VAULT_BALANCE).await;
+  let vault_token1 = test.create_token_account(&mint1, &vault_signer, VAULT_BALANCE).await;
+  let user = Keypair::new();
+  let user_token1 = test.create_token_account(&mint1, &user.pubkey(), 0).await;
+
+  let leaves = vec![
+    redemption_multi_leaf(0, None, &user.pubkey(), &mint0, 100, 0),
+    redemption_multi_leaf(1, None, &user.pubkey(), &mint1, 300, 0),
+  ];
+  let merkle_tree = MerkleTree::new(leaves);
+  let instruction = vault_instruction::create_schedule(
+    &admin.pubkey(),
+    &test.vault,
+    2,
+    EVENT_ID,
+    1,
+    merkle_tree.root(),
+    true,
+    &system_program::ID,
+    &system_program::ID,
+    &system_program::ID,
+    &system_program::ID,
+    &PROGRAM_ID,
+  );
+  test.process(&[instruction], &[&admin]).await.unwrap();
+  let (schedule, _) = find_schedule_address(EVENT_ID, &PROGRAM_ID);
+
+  let vault = test.vault;
+  let redeem_multi = |vault_token: &Pubkey| vault_instruction::redeem_token_multi(
+    &vault,
+    &schedule,
+    1,
+    0,
+    merkle_tree.proof(1),
+    &mint1,
+    300,
+    0,
+    vault_token,
+    &user.pubkey(),
+    &user_token1,
+    &RedeemOptions::default(),
+    &PROGRAM_ID,
+  );
+  let result = test.process(&[redeem_multi(&vault_token0)], &[&user]).await;
+  assert_vault_error(result, ErrorCode::InvalidTokenMint);
+
+  let instruction = redeem_multi(&vault_token1);
+  test.process(&[instruction], &[&user]).await.unwrap();
+  assert_eq!(test.token_balance(&user_token1).await, 300);
+  assert_eq!(test.token_balance(&vault_token1).await, VAULT_BALANCE - 300);
+}
+
+#[tokio::test]
+async fn redeem_token_multi_rejects_wrong_user_token() {
+  let mut test = VaultTest::new().await;
+  let admin = test.admin.insecure_clone();
+  let vault_signer = test.vault_signer;
+  let mint = test.create_mint(&Pubkey::new_unique(), 6).await;
+  let vault_token = test.create_token_account(&mint, &vault_signer, VAULT_BALANCE).await;
+  let user = Keypair::new();
+  let other_token = test.create_token_account(&mint, &Pubkey::new_unique(), 0).await;
+
+  let merkle_tree = MerkleTree::new(vec![redemption_multi_leaf(0, None, &user.pubkey(), &mint, 100, 0)]);
+  let instruction = vault_instruction::create_schedule(
+    &admin.pubkey(),
+    &test.vault,
+    1,
+    EVENT_ID,
+    1,
+    merkle_tree.root(),
+    true,
+    &system_program::ID,
+    &system_program::ID,
+    &system_program::ID,
+    &system_program::ID,
+    &PROGRAM_ID,
+  );
+  test.process(&[instruction], &[&admin]).await.unwrap();
+  let (schedule, _) = find_schedule_address(EVENT_ID, &PROGRAM_ID);
+  let instruction = vault_instruction::set_schedule_owner_check(&admin.pubkey(), &test.vault, &schedule, true, &PROGRAM_ID);
+  test.process(&[instruction], &[&admin]).await.unwrap();
+
+  let instruction = vault_instruction::redeem_token_multi(
+    &test.vault,
+    &schedule,
+    0,
+    0,
+    merkle_tree.proof(0),
+    &mint,
+    100,
+    0,
+    &vault_token,
+    &user.pubkey(),
+    &other_token,
+    &RedeemOptions::default(),
+    &PROGRAM_ID,
+  );
+  let result = test.process(&[instruction], &[&user]).await;
+  assert_vault_error(result, ErrorCode::InvalidTokenOwner);
+}
+
+#[tokio::test]
+async fn redeem_token_multi_rejects_mint_not_allowed() {
+  let mut test = VaultTest::new().await;
+  let owner = test.owner.insecure_clone();
+  let admin = test.admin.insecure_clone();
+  let vault_signer = test.vault_signer;
+  let mint0 = test.create_mint(&Pubkey::new_unique(), 6).await;
+  let mint1 = test.create_mint(&Pubkey::new_unique(), 9).await;
+  let vault_token0 = test.create_token_account(&mint0, &vault_signer, VAULT_BALANCE).await;
+  let vault_token1 = test.create_token_account(&mint1, &vault_signer, VAULT_BALANCE).await;
+  let user = Keypair::new();
+  let user_token0 = test.create_token_account(&mint0, &user.pubkey(), 0).await;
+  let user_token1 = test.create_token_account(&mint1, &user.pubkey(), 0).await;
+
+  // the leaf mints of a multi token schedule are only known at redemption
+  let leaves = vec![
+    redemption_multi_leaf(0, None, &user.pubkey(), &mint0, 100, 0),
+    redemption_multi_leaf(1, None, &user.pubkey(), &mint1, 300, 0),
+  ];
+  let merkle_tree = MerkleTree::new(leaves);
+  let instructions = [
+    vault_instruction::create_schedule(
+      &admin.pubkey(),
+      &test.vault,
+      2,
+      EVENT_ID,
+      1,
+      merkle_tree.root(),
+      true,
+      &system_program::ID,
+      &system_program::ID,
+      &system_program::ID,
+      &system_program::ID,
+      &PROGRAM_ID,
+    ),
+    vault_instruction::set_allowed_mints(&owner.pubkey(), &test.vault, vec![mint0], &PROGRAM_ID),
+  ];
+  test.process(&instructions, &[&admin, &owner]).await.unwrap();
+  let (schedule, _) = find_schedule_address(EVENT_ID, &PROGRAM_ID);
+
+  let vault = test.vault;
+  let redeem_multi = |index: u16, mint: &Pubkey, amount: u64, vault_token: &Pubkey, user_token: &Pubkey| vault_instruction::redeem_token_multi(
+    &vault,
+    &schedule,
+    index,
+    0,
+    merkle_tree.proof(index.into()),
+    mint,
+    amount,
+    0,
+    vault_token,
+    &user.pubkey(),
+    user_token,
+    &RedeemOptions::default(),
+    &PROGRAM_ID,
+  );
+  let result = test.process(&[redeem_multi(1, &mint1, 300, &vault_token1, &user_token1)], &[&user]).await;
+  assert_vault_error(result, ErrorCode::MintNotAllowed);
+
+  test.process(&[redeem_multi(0, &mint0, 100, &vault_token0, &user_token0)], &[&user]).await.unwrap();
+  assert_eq!(test.token_balance(&user_token0).await, 100);
+}
+
+#[tokio::test]
+async fn redeem_token_from_tranche() {
+  let mut test = VaultTest::new().await;
+  let admin = test.admin.insecure_clone();
+  let token_schedule = TokenSchedule::new(&mut test, &[100, 200], 1).await;
+  let unlock_timestamp = test.unix_timestamp().await + 3600;
+
+  let user = Keypair::new();
+  let user_token = test.create_token_account(&token_schedule.mint, &user.pubkey(), 0).await;
+  let leaves = vec![
+    redemption_leaf(2, None, &user.pubkey(), 500, 0),
+    redemption_leaf(3, None, &Pubkey::new_unique(), 600, 0),
+  ];
+  let merkle_tree = MerkleTree::new(leaves);
+  let instruction = vault_instruction::append_schedule_tranche(
+    &admin.pubkey(),
+    &test.vault,
+    &token_schedule.schedule,
+    2,
+    unlock_timestamp,
+    merkle_tree.root(),
+    &PROGRAM_ID,
+  );
+  test.process(&[instruction], &[&admin]).await.unwrap();
+
+  let vault = test.vault;
+  let redeem_tranche = |use_tranches: bool| vault_instruction::redeem_token(
+    &vault,
+    &token_schedule.schedule,
+    2,
+    0,
+    merkle_tree.proof(0),
+    500,
+    0,
+    &token_schedule.vault_token,
+    &user.pubkey(),
+    &user_token,
+    &RedeemOptions {
+      use_tranches,
+      ..RedeemOptions::default()
+    },
+    &PROGRAM_ID,
+  );
+  // the tranche account is required once the schedule has tranches
+  let result = test.process(&[redeem_tranche(false)], &[&user]).await;
+  assert_vault_error(result, ErrorCode::InvalidAccount);
+
+  let result = test.process(&[redeem_tranche(true)], &[&user]).await;
+  assert_vault_error(result, ErrorCode::ScheduleLocked);
+
+  test.set_unix_timestamp(unlock_timestamp).await;
+  test.refresh_blockhash().await;
+  test.process(&[redeem_tranche(true)], &[&user]).await.unwrap();
+  assert_eq!(test.token_balance(&user_token).await, 500);
+
+  // indexes of the initial root also redeem with the tranche account only
+  let user = token_schedule.user(0).insecure_clone();
+  let user_token = test.create_token_account(&token_schedule.mint, &user.pubkey(), 0).await;
+  let instruction = token_schedule.redeem_with_tranches(0, &token_schedule.vault_token, &user_token, false);
+  let result = test.process(&[instruction], &[&user]).await;
+  assert_vault_error(result, ErrorCode::InvalidAccount);
+
+  let instruction = token_schedule.redeem_with_tranches(0, &token_schedule.vault_token, &user_token, true);
+  test.process(&[instruction], &[&user]).await.unwrap();
+  assert_eq!(test.token_balance(&user_token).await, 100);
+}
+
+#[tokio::test]
+async fn redeem_token_mints_up_to_cap() {
+  let mut test = VaultTest::new().await;
+  let admin = test.admin.insecure_clone();
+  let vault_signer = test.vault_signer;
+  let mint = test.create_mint(&vault_signer, 6).await;
+  let recipients = recipients(&[100, 200]);
+  let schedule = test.create_schedule(EVENT_ID, 1, &recipients, &mint, &mint).await;
+  let merkle_tree = merkle_schedule(&recipients);
+
+  let instruction = vault_instruction::set_schedule_mint(&admin.pubkey(), &test.vault, &schedule, 250, &PROGRAM_ID);
+  test.process(&[instruction], &[&admin]).await.unwrap();
+
+  let mut user_tokens = Vec::new();
+  for recipient in recipients.iter() {
+    user_tokens.push(test.create_token_account(&mint, &recipient.user.pubkey(), 0).await);
+  }
+  let vault = test.vault;
+  let redeem_mint = |index: usize| vault_instruction::redeem_token(
+    &vault,
+    &schedule,
+    index as u16,
+    0,
+    merkle_tree.proof(index),
+    recipients[index].receiving_amount,
+    0,
+    &mint,
+    &recipients[index].user.pubkey(),
+    &user_tokens[index],
+    &RedeemOptions {
+      mint_on_claim: true,
+      ..RedeemOptions::default()
+    },
+    &PROGRAM_ID,
+  );
+
+  test.process(&[redeem_mint(0)], &[&recipients[0].user]).await.unwrap();
+  assert_eq!(test.token_balance(&user_tokens[0]).await, 100);
+  assert_eq!(test.mint_supply(&mint).await, 100);
+
+  let result = test.process(&[redeem_mint(1)], &[&recipients[1].user]).await;
+  assert_vault_error(result, ErrorCode::MintCapExceeded);
+
+  let (schedule_mint, _) = find_schedule_mint_address(&schedule, &PROGRAM_ID);
+  let schedule_mint = test.decode(&schedule_mint, decode_schedule_mint).await;
+  assert_eq!(schedule_mint.mint_cap, 250);
+  assert_eq!(schedule_mint.minted_amount, 100);
+}
@@ -0,0 +1,211 @@
+mod common;
+
+use solana_program::{
+  native_token::{
+    LAMPORTS_PER_SOL,
+  },
+  pubkey::{
+    Pubkey,
+  },
+  system_program,
+};
+use solana_sdk::{
+  signature::{
+    Keypair,
+    Signer,
+  },
+};
+use vault::error::{
+  ErrorCode,
+};
+use vault::state::{
+  ObjType,
+};
+use vault_client::account::{
+  decode_claim_status,
+  decode_schedule,
+  decode_schedule_tranche,
+};
+use vault_client::instruction as vault_instruction;
+use vault_client::pda::{
+  find_schedule_address,
+  find_schedule_tranche_address,
+};
+
+use common::{
+  assert_vault_error,
+  merkle_schedule,
+  recipients,
+  VaultTest,
+  PROGRAM_ID,
+};
+
+#[tokio::test]
+async fn create_schedule_by_admin() {
+  let mut test = VaultTest::new().await;
+  let vault_signer = test.vault_signer;
+  let mint = test.create_mint(&Pubkey::new_unique(), 6).await;
+  let vault_token = test.create_token_account(&mint, &vault_signer, 0).await;
+  let recipients = recipients(&[100, 200, 300]);
+
+  let schedule = test.create_schedule(1, 1, &recipients, &mint, &vault_token).await;
+  let schedule = test.decode(&schedule, decode_schedule).await;
+  assert!(schedule.obj_type == ObjType::Distribution);
+  assert_eq!(schedule.vault_id, test.vault);
+  assert_eq!(schedule.merkle_root, merkle_schedule(&recipients).root().to_vec());
+  assert_eq!(schedule.receiving_token_mint, mint);
+  assert_eq!(schedule.receiving_token_account, vault_token);
+  assert!(schedule.is_active);
+  assert_eq!(schedule.redemptions, vec![false; 3]);
+}
+
+#[tokio::test]
+async fn create_schedule_rejects_non_admin() {
+  let mut test = VaultTest::new().await;
+  let user = Keypair::new();
+  test.airdrop(&user.pubkey(), LAMPORTS_PER_SOL);
+
+  let instruction = vault_instruction::create_schedule(
+    &user.pubkey(),
+    &test.vault,
+    1,
+    1,
+    1,
+    [0u8; 32],
+    false,
+    &system_program::ID,
+    &system_program::ID,
+    &system_program::ID,
+    &system_program::ID,
+    &PROGRAM_ID,
+  );
+  let result = test.process(&[instruction], &[&user]).await;
+  assert_vault_error(result, ErrorCode::Unauthorized);
+}
+
+#[tokio::test]
+async fn create_schedule_rejects_invalid_token_accounts() {
+  let mut test = VaultTest::new().await;
+  let admin = test.admin.insecure_clone();
+  let vault_signer = test.vault_signer;
+  let mint = test.create_mint(&vault_signer, 6).await;
+  let other_mint = test.create_mint(&Pubkey::new_unique(), 6).await;
+  let vault_token = test.create_token_account(&mint, &vault_signer, 0).await;
+  let other_vault_token = test.create_token_account(&other_mint, &vault_signer, 0).await;
+  let user_token = test.create_token_account(&mint, &Pubkey::new_unique(), 0).await;
+
+  let vault = test.vault;
+  let create_schedule = |event_id: u64, receiving_token_mint: &Pubkey, receiving_token_account: &Pubkey, sending_token_mint: &Pubkey, sending_token_account: &Pubkey| vault_instruction::create_schedule(
+    &admin.pubkey(),
+    &vault,
+    1,
+    event_id,
+    1,
+    [0u8; 32],
+    false,
+    receiving_token_mint,
+    receiving_token_account,
+    sending_token_mint,
+    sending_token_account,
+    &PROGRAM_ID,
+  );
+
+  // missing account, other mint, other owner, and a mint the vault cannot mint
+  for receiving_token_account in [Pubkey::new_unique(), other_vault_token, user_token] {
+    let instruction = create_schedule(1, &mint, &receiving_token_account, &system_program::ID, &system_program::ID);
+    let result = test.process(&[instruction], &[&admin]).await;
+    assert_vault_error(result, ErrorCode::InvalidReceivingTokenAccount);
+  }
+  let instruction = create_schedule(1, &other_mint, &other_mint, &system_program::ID, &system_program::ID);
+  let result = test.process(&[instruction], &[&admin]).await;
+  assert_vault_error(result, ErrorCode::InvalidReceivingTokenAccount);
+
+  let instruction = create_schedule(1, &mint, &vault_token, &other_mint, &vault_token);
+  let result = test.process(&[instruction], &[&admin]).await;
+  assert_vault_error(result, ErrorCode::InvalidSendingTokenAccount);
+
+  let instructions = [
+    create_schedule(1, &mint, &vault_token, &other_mint, &other_vault_token),
+    create_schedule(2, &mint, &mint, &system_program::ID, &system_program::ID),
+  ];
+  test.process(&instructions, &[&admin]).await.unwrap();
+}
+
+#[tokio::test]
+async fn create_nft_schedule_by_admin() {
+  let mut test = VaultTest::new().await;
+  let admin = test.admin.insecure_clone();
+
+  let instruction = vault_instruction::create_nft_schedule(&admin.pubkey(), &test.vault, 2, 1, 0, [1u8; 32], &PROGRAM_ID);
+  test.process(&[instruction], &[&admin]).await.unwrap();
+  let (schedule, _) = find_schedule_address(1, &PROGRAM_ID);
+  let schedule = test.decode(&schedule, decode_schedule).await;
+  assert!(schedule.obj_type == ObjType::DistributionNft);
+  assert_eq!(schedule.receiving_token_mint, system_program::ID);
+  assert_eq!(schedule.redemptions, vec![false; 2]);
+}
+
+#[tokio::test]
+async fn set_schedule_status_by_admin() {
+  let mut test = VaultTest::new().await;
+  let admin = test.admin.insecure_clone();
+  let user = Keypair::new();
+  let recipients = recipients(&[100]);
+  let schedule = test.create_schedule(1, 1, &recipients, &system_program::ID, &system_program::ID).await;
+
+  let instruction = vault_instruction::set_schedule_status(&user.pubkey(), &test.vault, &schedule, false, &PROGRAM_ID);
+  let result = test.process(&[instruction], &[&user]).await;
+  assert_vault_error(result, ErrorCode::Unauthorized);
+
+  let instruction = vault_instruction::set_schedule_status(&admin.pubkey(), &test.vault, &schedule, false, &PROGRAM_ID);
+  test.process(&[instruction], &[&admin]).await.unwrap();
+  let return_data = test.simulate(vault_instruction::get_claim_status(&schedule, 0, &PROGRAM_ID)).await;
+  let claim_status = decode_claim_status(&return_data).unwrap();
+  assert_eq!(claim_status.index, 0);
+  assert!(!claim_status.is_active);
+  assert!(!claim_status.is_redeemed);
+}
+
+#[tokio::test]
+async fn append_schedule_tranche_extends_redemptions() {
+  let mut test = VaultTest::new().await;
+  let admin = test.admin.insecure_clone();
+  let recipients = recipients(&[100, 200]);
+  let schedule = test.create_schedule(1, 1, &recipients, &system_program::ID, &system_program::ID).await;
+
+  let instruction = vault_instruction::append_schedule_tranche(&admin.pubkey(), &test.vault, &schedule, 0, 1, [1u8; 32], &PROGRAM_ID);
+  let result = test.process(&[instruction], &[&admin]).await;
+  assert_vault_error(result, ErrorCode::InvalidInput);
+
+  let instructions = [
+    vault_instruction::append_schedule_tranche(&admin.pubkey(), &test.vault, &schedule, 3, 10, [1u8; 32], &PROGRAM_ID),
+    vault_instruction::append_schedule_tranche(&admin.pubkey(), &test.vault, &schedule, 1, 20, [2u8; 32], &PROGRAM_ID),
+  ];
+  test.process(&instructions, &[&admin]).await.unwrap();
+
+  let (schedule_tranche, _) = find_schedule_tranche_address(&schedule, &PROGRAM_ID);
+  let schedule_tranche = test.decode(&schedule_tranche, decode_schedule_tranche).await;
+  assert_eq!(schedule_tranche.schedule_id, schedule);
+  assert_eq!(schedule_tranche.tranches.len(), 2);
+  assert_eq!((schedule_tranche.tranches[0].index_start, schedule_tranche.tranches[0].index_end), (2, 5));
+  assert_eq!((schedule_tranche.tranches[1].index_start, schedule_tranche.tranches[1].index_end), (5, 6));
+  assert_eq!(schedule_tranche.tranches[1].merkle_root, [2u8; 32]);
+
+  let schedule = test.decode(&schedule, decode_schedule).await;
+  assert_eq!(schedule.redemptions, vec![false; 6]);
+  assert_eq!(schedule.user_count, 2);
+}
+
+#[tokio::test]
+async fn append_schedule_tranche_rejects_nft_schedule() {
+  let mut test = VaultTest::new().await;
+  let admin = test.admin.insecure_clone();
+
+  let instruction = vault_instruction::create_nft_schedule(&admin.pubkey(), &test.vault, 1, 1, 0, [1u8; 32], &PROGRAM_ID);
+  test.process(&[instruction], &[&admin]).await.unwrap();
+  let (schedule, _) = find_schedule_address(1, &PROGRAM_ID);
+
+  let instruction = vault_instruction::append_schedule_tranche(&admin.pubkey(), &test.vault, &schedule, 1, 1, [1u8; 32], &PROGRAM_ID);
+  let result = test.process(&[instruction], &[&admin]).await;
+  assert_vault_error(result, ErrorCode::InvalidInput);
+}
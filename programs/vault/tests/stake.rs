@@ -0,0 +1,184 @@
+//! The mock AMM stands in for the staking program: its swap moves the redeemed tokens out of
+//! `user_token0` and credits `user_stake`.
+
+mod common;
+
+use solana_program::{
+  instruction::{
+    Instruction,
+  },
+  pubkey::{
+    Pubkey,
+  },
+};
+use solana_sdk::{
+  signature::{
+    Keypair,
+    Signer,
+  },
+};
+use vault::error::{
+  ErrorCode,
+};
+use vault_client::account::{
+  decode_schedule_stake,
+};
+use vault_client::instruction as vault_instruction;
+use vault_client::pda::{
+  find_schedule_stake_address,
+};
+
+use common::{
+  assert_vault_error,
+  merkle_schedule,
+  recipients,
+  MockPool,
+  Recipient,
+  VaultTest,
+  PROGRAM_ID,
+};
+
+const EVENT_ID: u64 = 1;
+const RECEIVING_AMOUNT: u64 = 100;
+
+struct StakeTest {
+  test: VaultTest,
+  schedule: Pubkey,
+  vault_token: Pubkey,
+  recipients: Vec<Recipient>,
+  user_token: Pubkey,
+  user_stake: Pubkey,
+  pool: MockPool,
+}
+
+impl StakeTest {
+  async fn new() -> Self {
+    let mut test = VaultTest::new().await;
+    let vault_signer = test.vault_signer;
+    let mint = test.create_mint(&Pubkey::new_unique(), 6).await;
+    let stake_mint = test.create_mint(&Pubkey::new_unique(), 6).await;
+    let vault_token = test.create_token_account(&mint, &vault_signer, 1_000_000).await;
+    let recipients = recipients(&[RECEIVING_AMOUNT]);
+    let schedule = test.create_schedule(EVENT_ID, 1, &recipients, &mint, &vault_token).await;
+
+    let user = recipients[0].user.pubkey();
+    let user_token = test.create_token_account(&mint, &user, 0).await;
+    let user_stake = test.create_token_account(&stake_mint, &user, 0).await;
+    let pool = test.create_pool(&mint, &stake_mint, 1, 1, 1_000_000).await;
+
+    StakeTest {
+      test,
+      schedule,
+      vault_token,
+      recipients,
+      user_token,
+      user_stake,
+      pool,
+    }
+  }
+
+  fn user(&self) -> Keypair {
+    self.recipients[0].user.insecure_clone()
+  }
+
+  async fn set_schedule_stake(&mut self, stake_accounts: Vec<Pubkey>) {
+    let admin = self.test.admin.insecure_clone();
+    let instruction = vault_instruction::set_schedule_stake(
+      &admin.pubkey(),
+      &self.test.vault,
+      &self.schedule,
+      &mock_amm::ID,
+      stake_accounts,
+      &PROGRAM_ID,
+    );
+    self.test.process(&[instruction], &[&admin]).await.unwrap();
+  }
+
+  fn redeem_stake(&self, amount_staked: u64) -> Instruction {
+    let user = self.user().pubkey();
+    let stake_instruction = self.pool.swap(&user, &self.user_token, &self.user_stake, amount_staked, 0);
+    vault_instruction::redeem_token_stake(
+      &self.test.vault,
+      &self.schedule,
+      0,
+      0,
+      merkle_schedule(&self.recipients).proof(0),
+      RECEIVING_AMOUNT,
+      0,
+      &self.vault_token,
+      None,
+      &user,
+      &self.user_token,
+      None,
+      &self.user_stake,
+      None,
+      false,
+      &stake_instruction,
+      &PROGRAM_ID,
+    )
+  }
+}
+
+#[tokio::test]
+async fn redeem_token_stake_deposits_redeemed_amount() {
+  let mut stake_test = StakeTest::new().await;
+  let user = stake_test.user();
+  let stake_accounts = stake_test.pool.accounts();
+  stake_test.set_schedule_stake(stake_accounts.clone()).await;
+
+  let (schedule_stake, _) = find_schedule_stake_address(&stake_test.schedule, &PROGRAM_ID);
+  let schedule_stake = stake_test.test.decode(&schedule_stake, decode_schedule_stake).await;
+  assert_eq!(schedule_stake.stake_program, mock_amm::ID);
+  assert_eq!(schedule_stake.stake_accounts, stake_accounts);
+
+  let instruction = stake_test.redeem_stake(RECEIVING_AMOUNT);
+  stake_test.test.process(&[instruction], &[&user]).await.unwrap();
+  assert_eq!(stake_test.test.token_balance(&stake_test.user_token).await, 0);
+  assert_eq!(stake_test.test.token_balance(&stake_test.user_stake).await, RECEIVING_AMOUNT);
+}
+
+#[tokio::test]
+async fn redeem_token_stake_rejects_partial_deposit() {
+  let mut stake_test = StakeTest::new().await;
+  let user = stake_test.user();
+  let stake_accounts = stake_test.pool.accounts();
+  stake_test.set_schedule_stake(stake_accounts).await;
+
+  let instruction = stake_test.redeem_stake(RECEIVING_AMOUNT / 2);
+  let result = stake_test.test.process(&[instruction], &[&user]).await;
+  assert_vault_error(result, ErrorCode::StakeIncomplete);
+}
+
+#[tokio::test]
+async fn redeem_token_stake_rejects_account_not_whitelisted() {
+  let mut stake_test = StakeTest::new().await;
+  let user = stake_test.user();
+  let pool_destination = stake_test.pool.pool_destination;
+  let stake_accounts = stake_test.pool.accounts()
+    .into_iter()
+    .filter(|account| *account != pool_destination)
+    .collect();
+  stake_test.set_schedule_stake(stake_accounts).await;
+
+  let instruction = stake_test.redeem_stake(RECEIVING_AMOUNT);
+  let result = stake_test.test.process(&[instruction], &[&user]).await;
+  assert_vault_error(result, ErrorCode::InvalidAccount);
+}
+
+#[tokio::test]
+async fn set_schedule_stake_rejects_too_many_accounts() {
+  let mut stake_test = StakeTest::new().await;
+  let admin = stake_test.test.admin.insecure_clone();
+  let stake_accounts = (0..=vault::constant::STAKE_ACCOUNTS_MAX).map(|_| Pubkey::new_unique()).collect();
+
+  let instruction = vault_instruction::set_schedule_stake(
+    &admin.pubkey(),
+    &stake_test.test.vault,
+    &stake_test.schedule,
+    &mock_amm::ID,
+    stake_accounts,
+    &PROGRAM_ID,
+  );
+  let result = stake_test.test.process(&[instruction], &[&admin]).await;
+  assert_vault_error(result, ErrorCode::InvalidInput);
+}
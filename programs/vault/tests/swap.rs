@@ -0,0 +1,199 @@
+mod common;
+
+use solana_program::{
+  instruction::{
+    Instruction,
+  },
+  pubkey::{
+    Pubkey,
+  },
+};
+use solana_sdk::{
+  signature::{
+    Keypair,
+    Signer,
+  },
+};
+use vault::error::{
+  ErrorCode,
+};
+use vault_client::instruction as vault_instruction;
+use vault_client::merkle::{
+  redemption_leaf,
+  MerkleTree,
+};
+
+use common::{
+  assert_vault_error,
+  merkle_schedule,
+  recipients,
+  MockPool,
+  Recipient,
+  VaultTest,
+  PROGRAM_ID,
+};
+
+const EVENT_ID: u64 = 1;
+const RECEIVING_AMOUNT: u64 = 100;
+
+/// Schedule paying `RECEIVING_AMOUNT` to a single user, who swaps it at twice the rate.
+struct SwapTest {
+  test: VaultTest,
+  schedule: Pubkey,
+  vault_token: Pubkey,
+  output_mint: Pubkey,
+  recipients: Vec<Recipient>,
+  user_token: Pubkey,
+  user_token_out: Pubkey,
+  pool: MockPool,
+}
+
+impl SwapTest {
+  async fn new() -> Self {
+    let mut test = VaultTest::new().await;
+    let owner = test.owner.insecure_clone();
+    let vault_signer = test.vault_signer;
+    let mint = test.create_mint(&Pubkey::new_unique(), 6).await;
+    let output_mint = test.create_mint(&Pubkey::new_unique(), 6).await;
+    let vault_token = test.create_token_account(&mint, &vault_signer, 1_000_000).await;
+    let recipients = recipients(&[RECEIVING_AMOUNT]);
+    let schedule = test.create_schedule(EVENT_ID, 1, &recipients, &mint, &vault_token).await;
+
+    let user = recipients[0].user.pubkey();
+    let user_token = test.create_token_account(&mint, &user, 0).await;
+    let user_token_out = test.create_token_account(&output_mint, &user, 0).await;
+    let pool = test.create_pool(&mint, &output_mint, 2, 1, 1_000_000).await;
+
+    let instruction = vault_instruction::set_swap_program(&owner.pubkey(), &test.vault, &mock_amm::ID, &PROGRAM_ID);
+    test.process(&[instruction], &[&owner]).await.unwrap();
+
+    SwapTest {
+      test,
+      schedule,
+      vault_token,
+      output_mint,
+      recipients,
+      user_token,
+      user_token_out,
+      pool,
+    }
+  }
+
+  fn user(&self) -> Keypair {
+    self.recipients[0].user.insecure_clone()
+  }
+
+  fn redeem_swap(&self, minimum_amount_out: u64, swap_instruction: &Instruction) -> Instruction {
+    self.redeem_swap_at(0, merkle_schedule(&self.recipients).proof(0), false, minimum_amount_out, swap_instruction)
+  }
+
+  fn redeem_swap_at(
+    &self,
+    index: u16,
+    proofs: Vec<[u8; 32]>,
+    use_tranches: bool,
+    minimum_amount_out: u64,
+    swap_instruction: &Instruction,
+  ) -> Instruction {
+    vault_instruction::redeem_token_swap(
+      &self.test.vault,
+      &self.schedule,
+      index,
+      0,
+      proofs,
+      RECEIVING_AMOUNT,
+      0,
+      &self.output_mint,
+      minimum_amount_out,
+      &self.vault_token,
+      None,
+      &self.user().pubkey(),
+      &self.user_token,
+      None,
+      &self.user_token_out,
+      None,
+      use_tranches,
+      swap_instruction,
+      &PROGRAM_ID,
+    )
+  }
+
+  fn swap(&self, minimum_amount_out: u64) -> Instruction {
+    self.pool.swap(&self.user().pubkey(), &self.user_token, &self.user_token_out, RECEIVING_AMOUNT, minimum_amount_out)
+  }
+}
+
+#[tokio::test]
+async fn redeem_token_swap_pays_output_mint() {
+  let mut swap_test = SwapTest::new().await;
+  let user = swap_test.user();
+
+  let instruction = swap_test.redeem_swap(2 * RECEIVING_AMOUNT, &swap_test.swap(2 * RECEIVING_AMOUNT));
+  swap_test.test.process(&[instruction], &[&user]).await.unwrap();
+  assert_eq!(swap_test.test.token_balance(&swap_test.user_token).await, 0);
+  assert_eq!(swap_test.test.token_balance(&swap_test.user_token_out).await, 2 * RECEIVING_AMOUNT);
+  assert_eq!(swap_test.test.token_balance(&swap_test.pool.pool_source).await, RECEIVING_AMOUNT);
+}
+
+#[tokio::test]
+async fn redeem_token_swap_from_tranche() {
+  let mut swap_test = SwapTest::new().await;
+  let admin = swap_test.test.admin.insecure_clone();
+  let user = swap_test.user();
+
+  let merkle_tree = MerkleTree::new(vec![redemption_leaf(1, None, &user.pubkey(), RECEIVING_AMOUNT, 0)]);
+  let instruction = vault_instruction::append_schedule_tranche(
+    &admin.pubkey(),
+    &swap_test.test.vault,
+    &swap_test.schedule,
+    1,
+    1,
+    merkle_tree.root(),
+    &PROGRAM_ID,
+  );
+  swap_test.test.process(&[instruction], &[&admin]).await.unwrap();
+
+  // the tranche account is required once the schedule has tranches
+  let swap_instruction = swap_test.swap(2 * RECEIVING_AMOUNT);
+  let instruction = swap_test.redeem_swap_at(1, merkle_tree.proof(0), false, 2 * RECEIVING_AMOUNT, &swap_instruction);
+  let result = swap_test.test.process(&[instruction], &[&user]).await;
+  assert_vault_error(result, ErrorCode::InvalidAccount);
+
+  let instruction = swap_test.redeem_swap_at(1, merkle_tree.proof(0), true, 2 * RECEIVING_AMOUNT, &swap_instruction);
+  swap_test.test.process(&[instruction], &[&user]).await.unwrap();
+  assert_eq!(swap_test.test.token_balance(&swap_test.user_token_out).await, 2 * RECEIVING_AMOUNT);
+}
+
+#[tokio::test]
+async fn redeem_token_swap_rejects_slippage() {
+  let mut swap_test = SwapTest::new().await;
+  let user = swap_test.user();
+
+  // the AMM accepts the swap, the vault's minimum is stricter
+  let instruction = swap_test.redeem_swap(2 * RECEIVING_AMOUNT + 1, &swap_test.swap(2 * RECEIVING_AMOUNT));
+  let result = swap_test.test.process(&[instruction], &[&user]).await;
+  assert_vault_error(result, ErrorCode::SlippageExceeded);
+}
+
+#[tokio::test]
+async fn redeem_token_swap_rejects_other_swap_program() {
+  let mut swap_test = SwapTest::new().await;
+  let user = swap_test.user();
+
+  let mut swap_instruction = swap_test.swap(0);
+  swap_instruction.program_id = Pubkey::new_unique();
+  let instruction = swap_test.redeem_swap(0, &swap_instruction);
+  let result = swap_test.test.process(&[instruction], &[&user]).await;
+  assert_vault_error(result, ErrorCode::InvalidAccount);
+}
+
+#[tokio::test]
+async fn redeem_token_swap_rejects_same_output_account() {
+  let mut swap_test = SwapTest::new().await;
+  let user = swap_test.user();
+  swap_test.user_token_out = swap_test.user_token;
+
+  let instruction = swap_test.redeem_swap(0, &swap_test.swap(0));
+  let result = swap_test.test.process(&[instruction], &[&user]).await;
+  assert_vault_error(result, ErrorCode::InvalidAccount);
+}
@@ -0,0 +1,468 @@
+mod common;
+
+use solana_program::{
+  native_token::{
+    LAMPORTS_PER_SOL,
+  },
+  pubkey::{
+    Pubkey,
+  },
+  system_program,
+};
+use solana_sdk::{
+  signature::{
+    Keypair,
+    Signer,
+  },
+};
+use vault::constant::{
+  ALLOWED_MINTS_MAX,
+  DESTINATION_TIMELOCK,
+};
+use vault::error::{
+  ErrorCode,
+};
+use vault::external::spl_token::{
+  native_mint::ID as NATIVE_MINT_ID,
+};
+use vault_client::account::{
+  decode_vault,
+  decode_vault_allowlist,
+  decode_vault_destinations,
+  decode_vault_fee,
+  decode_vault_reserve,
+  decode_vault_swap,
+};
+use vault_client::instruction as vault_instruction;
+use vault_client::merkle::{
+  redemption_leaf,
+  MerkleTree,
+};
+use vault_client::pda::{
+  find_schedule_address,
+  find_vault_allowlist_address,
+  find_vault_destinations_address,
+  find_vault_fee_address,
+  find_vault_reserve_address,
+  find_vault_swap_address,
+};
+
+use common::{
+  assert_vault_error,
+  recipients,
+  VaultTest,
+  PROGRAM_ID,
+};
+
+#[tokio::test]
+async fn create_vault_sets_owner_and_admins() {
+  let mut test = VaultTest::new().await;
+
+  let vault = test.decode(&test.vault.clone(), decode_vault).await;
+  assert_eq!(vault.owner, test.owner.pubkey());
+  assert_eq!(vault.new_owner, system_program::ID);
+  assert_eq!(vault.admins, vec![test.admin.pubkey()]);
+}
+
+#[tokio::test]
+async fn set_vault_rejects_admin() {
+  let mut test = VaultTest::new().await;
+  let admin = test.admin.insecure_clone();
+
+  let instruction = vault_instruction::set_vault(&admin.pubkey(), &test.vault, vec![], &PROGRAM_ID);
+  let result = test.process(&[instruction], &[&admin]).await;
+  assert_vault_error(result, ErrorCode::Unauthorized);
+}
+
+#[tokio::test]
+async fn transfer_ownership_requires_acceptance() {
+  let mut test = VaultTest::new().await;
+  let owner = test.owner.insecure_clone();
+  let admin = test.admin.insecure_clone();
+  let new_owner = Keypair::new();
+
+  let instruction = vault_instruction::transfer_ownership(&admin.pubkey(), &test.vault, &admin.pubkey(), &PROGRAM_ID);
+  let result = test.process(&[instruction], &[&admin]).await;
+  assert_vault_error(result, ErrorCode::Unauthorized);
+
+  let instruction = vault_instruction::transfer_ownership(&owner.pubkey(), &test.vault, &new_owner.pubkey(), &PROGRAM_ID);
+  test.process(&[instruction], &[&owner]).await.unwrap();
+  let vault = test.decode(&test.vault.clone(), decode_vault).await;
+  assert_eq!(vault.owner, owner.pubkey());
+  assert_eq!(vault.new_owner, new_owner.pubkey());
+
+  let instruction = vault_instruction::accept_ownership(&admin.pubkey(), &test.vault, &PROGRAM_ID);
+  let result = test.process(&[instruction], &[&admin]).await;
+  assert_vault_error(result, ErrorCode::Unauthorized);
+
+  let instruction = vault_instruction::accept_ownership(&new_owner.pubkey(), &test.vault, &PROGRAM_ID);
+  test.process(&[instruction], &[&new_owner]).await.unwrap();
+  let vault = test.decode(&test.vault.clone(), decode_vault).await;
+  assert_eq!(vault.owner, new_owner.pubkey());
+  assert_eq!(vault.new_owner, system_program::ID);
+}
+
+#[tokio::test]
+async fn withdraw_sol_keeps_reserve_and_liabilities() {
+  let mut test = VaultTest::new().await;
+  let owner = test.owner.insecure_clone();
+  let admin = test.admin.insecure_clone();
+  let vault_signer = test.vault_signer;
+  let recipient = Pubkey::new_unique();
+  test.airdrop(&vault_signer, 5 * LAMPORTS_PER_SOL);
+
+  let instruction = vault_instruction::set_sol_reserve(&admin.pubkey(), &test.vault, LAMPORTS_PER_SOL, &PROGRAM_ID);
+  let result = test.process(&[instruction], &[&admin]).await;
+  assert_vault_error(result, ErrorCode::Unauthorized);
+
+  let instructions = [
+    vault_instruction::set_sol_reserve(&owner.pubkey(), &test.vault, LAMPORTS_PER_SOL, &PROGRAM_ID),
+    vault_instruction::set_sol_liabilities(&admin.pubkey(), &test.vault, 2 * LAMPORTS_PER_SOL, &PROGRAM_ID),
+  ];
+  test.process(&instructions, &[&owner, &admin]).await.unwrap();
+  let (vault_reserve, _) = find_vault_reserve_address(&test.vault, &PROGRAM_ID);
+  let vault_reserve = test.decode(&vault_reserve, decode_vault_reserve).await;
+  assert_eq!(vault_reserve.sol_reserve, LAMPORTS_PER_SOL);
+  assert_eq!(vault_reserve.sol_liabilities, 2 * LAMPORTS_PER_SOL);
+
+  let withdrawable = 2 * LAMPORTS_PER_SOL - test.rent_exempt(0).await;
+  let return_data = test.simulate(vault_instruction::get_withdrawable_sol(&test.vault, &PROGRAM_ID)).await;
+  assert_eq!(return_data, withdrawable.to_le_bytes());
+
+  let instruction = vault_instruction::withdraw_sol(&admin.pubkey(), &test.vault, &recipient, withdrawable + 1, &PROGRAM_ID);
+  let result = test.process(&[instruction], &[&admin]).await;
+  assert_vault_error(result, ErrorCode::InsufficientReserve);
+
+  let instruction = vault_instruction::withdraw_sol(&admin.pubkey(), &test.vault, &recipient, withdrawable, &PROGRAM_ID);
+  test.process(&[instruction], &[&admin]).await.unwrap();
+  assert_eq!(test.lamports(&recipient).await, withdrawable);
+}
+
+#[tokio::test]
+async fn set_sol_liabilities_lowered_by_owner_only() {
+  let mut test = VaultTest::new().await;
+  let owner = test.owner.insecure_clone();
+  let admin = test.admin.insecure_clone();
+  let (vault_reserve, _) = find_vault_reserve_address(&test.vault, &PROGRAM_ID);
+
+  let instruction = vault_instruction::set_sol_liabilities(&admin.pubkey(), &test.vault, 2 * LAMPORTS_PER_SOL, &PROGRAM_ID);
+  test.process(&[instruction], &[&admin]).await.unwrap();
+
+  // an admin zeroing the liabilities could withdraw the SOL owed to users
+  let instruction = vault_instruction::set_sol_liabilities(&admin.pubkey(), &test.vault, 0, &PROGRAM_ID);
+  let result = test.process(&[instruction], &[&admin]).await;
+  assert_vault_error(result, ErrorCode::Unauthorized);
+  assert_eq!(test.decode(&vault_reserve, decode_vault_reserve).await.sol_liabilities, 2 * LAMPORTS_PER_SOL);
+
+  let instruction = vault_instruction::set_sol_liabilities(&owner.pubkey(), &test.vault, LAMPORTS_PER_SOL, &PROGRAM_ID);
+  test.process(&[instruction], &[&owner]).await.unwrap();
+  assert_eq!(test.decode(&vault_reserve, decode_vault_reserve).await.sol_liabilities, LAMPORTS_PER_SOL);
+}
+
+#[tokio::test]
+async fn withdraw_sol_rejects_non_admin() {
+  let mut test = VaultTest::new().await;
+  let user = Keypair::new();
+  let vault_signer = test.vault_signer;
+  test.airdrop(&vault_signer, LAMPORTS_PER_SOL);
+
+  let instruction = vault_instruction::withdraw_sol(&user.pubkey(), &test.vault, &user.pubkey(), 1, &PROGRAM_ID);
+  let result = test.process(&[instruction], &[&user]).await;
+  assert_vault_error(result, ErrorCode::Unauthorized);
+}
+
+#[tokio::test]
+async fn deposit_sol_wraps_into_vault_token() {
+  let mut test = VaultTest::new().await;
+  let vault_signer = test.vault_signer;
+  let payer = test.context.payer.insecure_clone();
+  let vault_token = test.create_token_account(&NATIVE_MINT_ID, &vault_signer, 0).await;
+  let other_token = test.create_token_account(&NATIVE_MINT_ID, &payer.pubkey(), 0).await;
+
+  let instruction = vault_instruction::deposit_sol(&payer.pubkey(), &test.vault, &other_token, LAMPORTS_PER_SOL, &PROGRAM_ID);
+  let result = test.process(&[instruction], &[]).await;
+  assert_vault_error(result, ErrorCode::InvalidTokenOwner);
+
+  let instruction = vault_instruction::deposit_sol(&payer.pubkey(), &test.vault, &vault_token, LAMPORTS_PER_SOL, &PROGRAM_ID);
+  test.process(&[instruction], &[]).await.unwrap();
+  assert_eq!(test.token_balance(&vault_token).await, LAMPORTS_PER_SOL);
+}
+
+#[tokio::test]
+async fn withdraw_token_by_admin() {
+  let mut test = VaultTest::new().await;
+  let admin = test.admin.insecure_clone();
+  let user = Keypair::new();
+  let vault_signer = test.vault_signer;
+  let mint = test.create_mint(&Pubkey::new_unique(), 6).await;
+  let vault_token = test.create_token_account(&mint, &vault_signer, 100).await;
+  let recipient = test.create_token_account(&mint, &user.pubkey(), 0).await;
+
+  let instruction = vault_instruction::withdraw_token(&user.pubkey(), &test.vault, &vault_token, &recipient, 40, &PROGRAM_ID);
+  let result = test.process(&[instruction], &[&user]).await;
+  assert_vault_error(result, ErrorCode::Unauthorized);
+
+  let instruction = vault_instruction::withdraw_token(&admin.pubkey(), &test.vault, &vault_token, &recipient, 40, &PROGRAM_ID);
+  test.process(&[instruction], &[&admin]).await.unwrap();
+  assert_eq!(test.token_balance(&vault_token).await, 60);
+  assert_eq!(test.token_balance(&recipient).await, 40);
+}
+
+#[tokio::test]
+async fn set_claim_fee_by_owner() {
+  let mut test = VaultTest::new().await;
+  let owner = test.owner.insecure_clone();
+  let admin = test.admin.insecure_clone();
+  let treasury = Pubkey::new_unique();
+
+  let instruction = vault_instruction::set_claim_fee(&admin.pubkey(), &test.vault, &treasury, 5_000, 100, &PROGRAM_ID);
+  let result = test.process(&[instruction], &[&admin]).await;
+  assert_vault_error(result, ErrorCode::Unauthorized);
+
+  let instruction = vault_instruction::set_claim_fee(&owner.pubkey(), &test.vault, &treasury, 5_000, 10_001, &PROGRAM_ID);
+  let result = test.process(&[instruction], &[&owner]).await;
+  assert_vault_error(result, ErrorCode::InvalidFee);
+
+  let instruction = vault_instruction::set_claim_fee(&owner.pubkey(), &test.vault, &treasury, 5_000, 100, &PROGRAM_ID);
+  test.process(&[instruction], &[&owner]).await.unwrap();
+  let (vault_fee, _) = find_vault_fee_address(&test.vault, &PROGRAM_ID);
+  let vault_fee = test.decode(&vault_fee, decode_vault_fee).await;
+  assert_eq!(vault_fee.treasury, treasury);
+  assert_eq!(vault_fee.fee_lamports, 5_000);
+  assert_eq!(vault_fee.fee_bps, 100);
+}
+
+#[tokio::test]
+async fn set_swap_program_by_owner() {
+  let mut test = VaultTest::new().await;
+  let owner = test.owner.insecure_clone();
+  let admin = test.admin.insecure_clone();
+
+  let instruction = vault_instruction::set_swap_program(&admin.pubkey(), &test.vault, &mock_amm::ID, &PROGRAM_ID);
+  let result = test.process(&[instruction], &[&admin]).await;
+  assert_vault_error(result, ErrorCode::Unauthorized);
+
+  let instruction = vault_instruction::set_swap_program(&owner.pubkey(), &test.vault, &mock_amm::ID, &PROGRAM_ID);
+  test.process(&[instruction], &[&owner]).await.unwrap();
+  let (vault_swap, _) = find_vault_swap_address(&test.vault, &PROGRAM_ID);
+  let vault_swap = test.decode(&vault_swap, decode_vault_swap).await;
+  assert_eq!(vault_swap.swap_program, mock_amm::ID);
+}
+
+#[tokio::test]
+async fn set_allowed_mints_by_owner() {
+  let mut test = VaultTest::new().await;
+  let owner = test.owner.insecure_clone();
+  let admin = test.admin.insecure_clone();
+  let mint = Pubkey::new_unique();
+
+  let instruction = vault_instruction::set_allowed_mints(&admin.pubkey(), &test.vault, vec![mint], &PROGRAM_ID);
+  let result = test.process(&[instruction], &[&admin]).await;
+  assert_vault_error(result, ErrorCode::Unauthorized);
+
+  let mints = (0..=ALLOWED_MINTS_MAX).map(|_| Pubkey::new_unique()).collect();
+  let instruction = vault_instruction::set_allowed_mints(&owner.pubkey(), &test.vault, mints, &PROGRAM_ID);
+  let result = test.process(&[instruction], &[&owner]).await;
+  assert_vault_error(result, ErrorCode::InvalidInput);
+
+  let instruction = vault_instruction::set_allowed_mints(&owner.pubkey(), &test.vault, vec![mint], &PROGRAM_ID);
+  test.process(&[instruction], &[&owner]).await.unwrap();
+  let (vault_allowlist, _) = find_vault_allowlist_address(&test.vault, &PROGRAM_ID);
+  let allowlist = test.decode(&vault_allowlist, decode_vault_allowlist).await;
+  assert_eq!(allowlist.vault_id, test.vault);
+  assert_eq!(allowlist.mints, vec![mint]);
+  assert!(!allowlist.is_allowed(&Pubkey::new_unique()));
+
+  let instruction = vault_instruction::set_allowed_mints(&owner.pubkey(), &test.vault, vec![], &PROGRAM_ID);
+  test.process(&[instruction], &[&owner]).await.unwrap();
+  let allowlist = test.decode(&vault_allowlist, decode_vault_allowlist).await;
+  assert!(allowlist.is_allowed(&Pubkey::new_unique()));
+}
+
+#[tokio::test]
+async fn allowed_mints_restrict_deposits_schedules_and_withdrawals() {
+  let mut test = VaultTest::new().await;
+  let owner = test.owner.insecure_clone();
+  let admin = test.admin.insecure_clone();
+  let payer = test.context.payer.insecure_clone();
+  let vault_signer = test.vault_signer;
+  let mint = test.create_mint(&Pubkey::new_unique(), 6).await;
+  let other_mint = test.create_mint(&Pubkey::new_unique(), 6).await;
+  let vault_token = test.create_token_account(&mint, &vault_signer, 100).await;
+  let other_vault_token = test.create_token_account(&other_mint, &vault_signer, 100).await;
+  let wsol_vault_token = test.create_token_account(&NATIVE_MINT_ID, &vault_signer, 0).await;
+  let recipient = test.create_token_account(&mint, &admin.pubkey(), 0).await;
+  let other_recipient = test.create_token_account(&other_mint, &admin.pubkey(), 0).await;
+
+  let instruction = vault_instruction::set_allowed_mints(&owner.pubkey(), &test.vault, vec![mint], &PROGRAM_ID);
+  test.process(&[instruction], &[&owner]).await.unwrap();
+
+  let instruction = vault_instruction::withdraw_token(&admin.pubkey(), &test.vault, &other_vault_token, &other_recipient, 40, &PROGRAM_ID);
+  let result = test.process(&[instruction], &[&admin]).await;
+  assert_vault_error(result, ErrorCode::MintNotAllowed);
+  let instruction = vault_instruction::withdraw_token(&admin.pubkey(), &test.vault, &vault_token, &recipient, 40, &PROGRAM_ID);
+  test.process(&[instruction], &[&admin]).await.unwrap();
+  assert_eq!(test.token_balance(&recipient).await, 40);
+
+  // wrapped SOL must be listed, unlike native SOL
+  let instruction = vault_instruction::deposit_sol(&payer.pubkey(), &test.vault, &wsol_vault_token, LAMPORTS_PER_SOL, &PROGRAM_ID);
+  let result = test.process(&[instruction], &[]).await;
+  assert_vault_error(result, ErrorCode::MintNotAllowed);
+
+  let vault = test.vault;
+  let create_schedule = |event_id: u64, receiving_token_mint: &Pubkey, receiving_token_account: &Pubkey, sending_token_mint: &Pubkey, sending_token_account: &Pubkey| vault_instruction::create_schedule(
+    &admin.pubkey(),
+    &vault,
+    1,
+    event_id,
+    1,
+    [0u8; 32],
+    false,
+    receiving_token_mint,
+    receiving_token_account,
+    sending_token_mint,
+    sending_token_account,
+    &PROGRAM_ID,
+  );
+  let instruction = create_schedule(1, &other_mint, &other_vault_token, &system_program::ID, &system_program::ID);
+  let result = test.process(&[instruction], &[&admin]).await;
+  assert_vault_error(result, ErrorCode::MintNotAllowed);
+  let instruction = create_schedule(1, &mint, &vault_token, &other_mint, &other_vault_token);
+  let result = test.process(&[instruction], &[&admin]).await;
+  assert_vault_error(result, ErrorCode::MintNotAllowed);
+
+  let instructions = [
+    create_schedule(1, &mint, &vault_token, &system_program::ID, &system_program::ID),
+    create_schedule(2, &system_program::ID, &system_program::ID, &system_program::ID, &system_program::ID),
+  ];
+  test.process(&instructions, &[&admin]).await.unwrap();
+  assert!(test.account(&find_schedule_address(2, &PROGRAM_ID).0).await.is_some());
+}
+
+#[tokio::test]
+async fn add_withdrawal_destination_by_owner() {
+  let mut test = VaultTest::new().await;
+  let owner = test.owner.insecure_clone();
+  let admin = test.admin.insecure_clone();
+  let treasury = Pubkey::new_unique();
+
+  let instruction = vault_instruction::add_withdrawal_destination(&admin.pubkey(), &test.vault, &treasury, &PROGRAM_ID);
+  let result = test.process(&[instruction], &[&admin]).await;
+  assert_vault_error(result, ErrorCode::Unauthorized);
+
+  let instruction = vault_instruction::add_withdrawal_destination(&owner.pubkey(), &test.vault, &treasury, &PROGRAM_ID);
+  test.process(std::slice::from_ref(&instruction), &[&owner]).await.unwrap();
+  let timestamp = test.unix_timestamp().await;
+  let (vault_destinations, _) = find_vault_destinations_address(&test.vault, &PROGRAM_ID);
+  let destinations = test.decode(&vault_destinations, decode_vault_destinations).await;
+  assert_eq!(destinations.vault_id, test.vault);
+  assert_eq!(destinations.destinations.len(), 1);
+  assert_eq!(destinations.destinations[0].address, treasury);
+  assert_eq!(destinations.destinations[0].timestamp, timestamp + DESTINATION_TIMELOCK);
+
+  test.refresh_blockhash().await;
+  let result = test.process(&[instruction], &[&owner]).await;
+  assert_vault_error(result, ErrorCode::InvalidInput);
+
+  let instruction = vault_instruction::remove_withdrawal_destination(&admin.pubkey(), &test.vault, &treasury, &PROGRAM_ID);
+  let result = test.process(&[instruction], &[&admin]).await;
+  assert_vault_error(result, ErrorCode::Unauthorized);
+
+  let instruction = vault_instruction::remove_withdrawal_destination(&owner.pubkey(), &test.vault, &treasury, &PROGRAM_ID);
+  test.process(std::slice::from_ref(&instruction), &[&owner]).await.unwrap();
+  let destinations = test.decode(&vault_destinations, decode_vault_destinations).await;
+  assert!(destinations.destinations.is_empty());
+
+  test.refresh_blockhash().await;
+  let result = test.process(&[instruction], &[&owner]).await;
+  assert_vault_error(result, ErrorCode::InvalidInput);
+}
+
+#[tokio::test]
+async fn withdrawals_limited_to_destinations_after_timelock() {
+  let mut test = VaultTest::new().await;
+  let owner = test.owner.insecure_clone();
+  let admin = test.admin.insecure_clone();
+  let vault_signer = test.vault_signer;
+  let treasury = Keypair::new().pubkey();
+  test.airdrop(&vault_signer, 5 * LAMPORTS_PER_SOL);
+  let mint = test.create_mint(&Pubkey::new_unique(), 6).await;
+  let vault_token = test.create_token_account(&mint, &vault_signer, 100).await;
+  let treasury_token = test.create_token_account(&mint, &treasury, 0).await;
+  let admin_token = test.create_token_account(&mint, &admin.pubkey(), 0).await;
+
+  let instruction = vault_instruction::add_withdrawal_destination(&owner.pubkey(), &test.vault, &treasury, &PROGRAM_ID);
+  test.process(&[instruction], &[&owner]).await.unwrap();
+
+  // pending destinations cannot receive funds yet
+  let withdraw_sol = vault_instruction::withdraw_sol(&admin.pubkey(), &test.vault, &treasury, LAMPORTS_PER_SOL, &PROGRAM_ID);
+  let withdraw_token = vault_instruction::withdraw_token(&admin.pubkey(), &test.vault, &vault_token, &treasury_token, 40, &PROGRAM_ID);
+  let result = test.process(std::slice::from_ref(&withdraw_sol), &[&admin]).await;
+  assert_vault_error(result, ErrorCode::DestinationNotAllowed);
+  let result = test.process(std::slice::from_ref(&withdraw_token), &[&admin]).await;
+  assert_vault_error(result, ErrorCode::DestinationNotAllowed);
+
+  let timestamp = test.unix_timestamp().await;
+  test.set_unix_timestamp(timestamp + DESTINATION_TIMELOCK).await;
+  test.refresh_blockhash().await;
+  test.process(&[withdraw_sol, withdraw_token], &[&admin]).await.unwrap();
+  assert_eq!(test.lamports(&treasury).await, LAMPORTS_PER_SOL);
+  assert_eq!(test.token_balance(&treasury_token).await, 40);
+
+  let instruction = vault_instruction::withdraw_sol(&admin.pubkey(), &test.vault, &admin.pubkey(), LAMPORTS_PER_SOL, &PROGRAM_ID);
+  let result = test.process(&[instruction], &[&admin]).await;
+  assert_vault_error(result, ErrorCode::DestinationNotAllowed);
+  let instruction = vault_instruction::withdraw_token(&admin.pubkey(), &test.vault, &vault_token, &admin_token, 40, &PROGRAM_ID);
+  let result = test.process(&[instruction], &[&admin]).await;
+  assert_vault_error(result, ErrorCode::DestinationNotAllowed);
+
+  // removing the last destination stops withdrawals until one is added again
+  let instruction = vault_instruction::remove_withdrawal_destination(&owner.pubkey(), &test.vault, &treasury, &PROGRAM_ID);
+  test.process(&[instruction], &[&owner]).await.unwrap();
+  let instruction = vault_instruction::withdraw_token(&admin.pubkey(), &test.vault, &vault_token, &treasury_token, 40, &PROGRAM_ID);
+  let result = test.process(&[instruction], &[&admin]).await;
+  assert_vault_error(result, ErrorCode::DestinationNotAllowed);
+}
+
+#[tokio::test]
+async fn schedules_set_up_by_owner_once_destinations_are_set() {
+  let mut test = VaultTest::new().await;
+  let owner = test.owner.insecure_clone();
+  let admin = test.admin.insecure_clone();
+  let vault_signer = test.vault_signer;
+  let treasury = Keypair::new().pubkey();
+  let mint = test.create_mint(&Pubkey::new_unique(), 6).await;
+  let vault_token = test.create_token_account(&mint, &vault_signer, 100).await;
+  let schedule = test.create_schedule(1, 1, &recipients(&[100]), &mint, &vault_token).await;
+
+  let instruction = vault_instruction::add_withdrawal_destination(&owner.pubkey(), &test.vault, &treasury, &PROGRAM_ID);
+  test.process(&[instruction], &[&owner]).await.unwrap();
+
+  // a schedule paying the admin, which is not an approved destination
+  let merkle_tree = MerkleTree::new(vec![redemption_leaf(0, None, &admin.pubkey(), 100, 0)]);
+  let vault = test.vault;
+  let create_schedule = |signer: &Pubkey| vault_instruction::create_schedule(
+    signer,
+    &vault,
+    1,
+    2,
+    1,
+    merkle_tree.root(),
+    false,
+    &mint,
+    &vault_token,
+    &system_program::ID,
+    &system_program::ID,
+    &PROGRAM_ID,
+  );
+  let result = test.process(&[create_schedule(&admin.pubkey())], &[&admin]).await;
+  assert_vault_error(result, ErrorCode::Unauthorized);
+  let instruction = vault_instruction::append_schedule_tranche(&admin.pubkey(), &test.vault, &schedule, 1, 1, merkle_tree.root(), &PROGRAM_ID);
+  let result = test.process(&[instruction], &[&admin]).await;
+  assert_vault_error(result, ErrorCode::Unauthorized);
+
+  let instruction = create_schedule(&owner.pubkey());
+  test.process(&[instruction], &[&owner]).await.unwrap();
+  let instruction = vault_instruction::append_schedule_tranche(&owner.pubkey(), &test.vault, &schedule, 1, 1, merkle_tree.root(), &PROGRAM_ID);
+  test.process(&[instruction], &[&owner]).await.unwrap();
+}
@@ -0,0 +1,166 @@
+mod common;
+
+use solana_program::{
+  instruction::{
+    Instruction,
+  },
+  pubkey::{
+    Pubkey,
+  },
+};
+use solana_sdk::{
+  signature::{
+    Keypair,
+    Signer,
+  },
+};
+use vault::error::{
+  ErrorCode,
+};
+use vault_client::account::{
+  decode_schedule_voucher,
+};
+use vault_client::instruction as vault_instruction;
+use vault_client::pda::{
+  find_schedule_address,
+  find_schedule_voucher_address,
+};
+
+use common::{
+  assert_vault_error,
+  VaultTest,
+  PROGRAM_ID,
+};
+
+const EVENT_ID: u64 = 1;
+const NONCE_COUNT: u32 = 16;
+const AMOUNT: u64 = 100;
+
+struct VoucherTest {
+  test: VaultTest,
+  schedule: Pubkey,
+  voucher_signer: Keypair,
+  vault_token: Pubkey,
+  user: Keypair,
+  user_token: Pubkey,
+}
+
+impl VoucherTest {
+  async fn new() -> Self {
+    let mut test = VaultTest::new().await;
+    let admin = test.admin.insecure_clone();
+    let vault_signer = test.vault_signer;
+    let voucher_signer = Keypair::new();
+    let mint = test.create_mint(&Pubkey::new_unique(), 6).await;
+    let vault_token = test.create_token_account(&mint, &vault_signer, 1_000_000).await;
+    let user = Keypair::new();
+    let user_token = test.create_token_account(&mint, &user.pubkey(), 0).await;
+
+    let instruction = vault_instruction::create_voucher_schedule(
+      &admin.pubkey(),
+      &test.vault,
+      EVENT_ID,
+      NONCE_COUNT,
+      0,
+      &voucher_signer.pubkey(),
+      &mint,
+      &vault_token,
+      &PROGRAM_ID,
+    );
+    test.process(&[instruction], &[&admin]).await.unwrap();
+
+    VoucherTest {
+      test,
+      schedule: find_schedule_address(EVENT_ID, &PROGRAM_ID).0,
+      voucher_signer,
+      vault_token,
+      user,
+      user_token,
+    }
+  }
+
+  /// Returns the ed25519 instruction of the voucher signed by `signer`, followed by its redemption.
+  fn redeem_voucher(&self, signer: &Keypair, nonce: u32, expiry: i64) -> [Instruction; 2] {
+    let message = vault_instruction::voucher_message(&self.schedule, &self.user.pubkey(), AMOUNT, nonce, expiry);
+    let signature = signer.sign_message(&message);
+    [
+      vault_instruction::ed25519_instruction(&signer.pubkey(), &signature.into(), &message),
+      vault_instruction::redeem_voucher(
+        &self.test.vault,
+        &self.schedule,
+        AMOUNT,
+        nonce,
+        expiry,
+        &self.vault_token,
+        &self.user.pubkey(),
+        &self.user_token,
+        None,
+        &PROGRAM_ID,
+      ),
+    ]
+  }
+}
+
+#[tokio::test]
+async fn redeem_voucher_pays_amount_once() {
+  let mut voucher_test = VoucherTest::new().await;
+  let user = voucher_test.user.insecure_clone();
+  let expiry = voucher_test.test.unix_timestamp().await + 3600;
+
+  let instructions = voucher_test.redeem_voucher(&voucher_test.voucher_signer, 3, expiry);
+  voucher_test.test.process(&instructions, &[&user]).await.unwrap();
+  assert_eq!(voucher_test.test.token_balance(&voucher_test.user_token).await, AMOUNT);
+
+  let (schedule_voucher, _) = find_schedule_voucher_address(&voucher_test.schedule, &PROGRAM_ID);
+  let schedule_voucher = voucher_test.test.decode(&schedule_voucher, decode_schedule_voucher).await;
+  assert_eq!(schedule_voucher.is_nonce_used(3), Some(true));
+  assert_eq!(schedule_voucher.is_nonce_used(4), Some(false));
+
+  voucher_test.test.refresh_blockhash().await;
+  let result = voucher_test.test.process(&instructions, &[&user]).await;
+  assert_vault_error(result, ErrorCode::Redeemed);
+}
+
+#[tokio::test]
+async fn redeem_voucher_rejects_nonce_out_of_range() {
+  let mut voucher_test = VoucherTest::new().await;
+  let user = voucher_test.user.insecure_clone();
+  let expiry = voucher_test.test.unix_timestamp().await + 3600;
+
+  let instructions = voucher_test.redeem_voucher(&voucher_test.voucher_signer, NONCE_COUNT, expiry);
+  let result = voucher_test.test.process(&instructions, &[&user]).await;
+  assert_vault_error(result, ErrorCode::IndexOutOfRange);
+}
+
+#[tokio::test]
+async fn redeem_voucher_rejects_expired_voucher() {
+  let mut voucher_test = VoucherTest::new().await;
+  let user = voucher_test.user.insecure_clone();
+  let expiry = voucher_test.test.unix_timestamp().await - 1;
+
+  let instructions = voucher_test.redeem_voucher(&voucher_test.voucher_signer, 0, expiry);
+  let result = voucher_test.test.process(&instructions, &[&user]).await;
+  assert_vault_error(result, ErrorCode::VoucherExpired);
+}
+
+#[tokio::test]
+async fn redeem_voucher_rejects_other_signer() {
+  let mut voucher_test = VoucherTest::new().await;
+  let user = voucher_test.user.insecure_clone();
+  let expiry = voucher_test.test.unix_timestamp().await + 3600;
+
+  let instructions = voucher_test.redeem_voucher(&Keypair::new(), 0, expiry);
+  let result = voucher_test.test.process(&instructions, &[&user]).await;
+  assert_vault_error(result, ErrorCode::InvalidSignature);
+}
+
+#[tokio::test]
+async fn redeem_voucher_rejects_missing_signature() {
+  let mut voucher_test = VoucherTest::new().await;
+  let user = voucher_test.user.insecure_clone();
+  let expiry = voucher_test.test.unix_timestamp().await + 3600;
+
+  let [_, redeem_voucher] = voucher_test.redeem_voucher(&voucher_test.voucher_signer, 0, expiry);
+  let result = voucher_test.test.process(&[redeem_voucher], &[&user]).await;
+  assert_vault_error(result, ErrorCode::InvalidSignature);
+}
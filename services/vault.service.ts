@@ -225,6 +225,8 @@ export class VaultService {
     sendingAmount: BN,
     recipientAddress: PublicKey,
     feePaymentAddress: PublicKey,
+    treasuryAddress: null | PublicKey,
+    treasuryTokenAddress: null | PublicKey,
     vaultProgramId: PublicKey,
   ): Promise<void> {
 
@@ -237,6 +239,10 @@ export class VaultService {
       scheduleAddress,
     )
 
+    // required once tranches were appended to the schedule
+    const [scheduleTrancheAddress,]: [PublicKey, number] = VaultInstructionService.findScheduleTrancheAddress(scheduleAddress, vaultProgramId)
+    const scheduleTranche = await connection.getAccountInfo(scheduleTrancheAddress)
+
     const transaction = new Transaction()
 
     const redeemInstruction = VaultInstructionService.redeemToken(
@@ -253,6 +259,9 @@ export class VaultService {
       payerAccount.publicKey,
       recipientAddress,
       feePaymentAddress,
+      treasuryAddress,
+      treasuryTokenAddress,
+      scheduleTranche != null ? scheduleTrancheAddress : null,
       vaultProgramId,
     )
     transaction.add(redeemInstruction)
@@ -276,6 +285,8 @@ export class VaultService {
     sendingAmount: BN,
     recipientAddress: PublicKey,
     feePaymentAddress: PublicKey,
+    treasuryAddress: null | PublicKey,
+    treasuryTokenAddress: null | PublicKey,
     vaultProgramId: PublicKey,
   ): Promise<void> {
 
@@ -288,6 +299,10 @@ export class VaultService {
       scheduleAddress,
     )
 
+    // required once tranches were appended to the schedule
+    const [scheduleTrancheAddress,]: [PublicKey, number] = VaultInstructionService.findScheduleTrancheAddress(scheduleAddress, vaultProgramId)
+    const scheduleTranche = await connection.getAccountInfo(scheduleTrancheAddress)
+
     const transaction = new Transaction()
 
     const redeemInstruction = VaultInstructionService.redeemTokenMulti(
@@ -305,6 +320,9 @@ export class VaultService {
       payerAccount.publicKey,
       recipientAddress,
       feePaymentAddress,
+      treasuryAddress,
+      treasuryTokenAddress,
+      scheduleTranche != null ? scheduleTrancheAddress : null,
       vaultProgramId
     )
     transaction.add(redeemInstruction)
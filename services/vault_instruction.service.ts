@@ -115,6 +115,17 @@ export interface Schedule {
   redemptions: boolean[]
 }
 
+// Optional accounts are positional, an omitted one is passed as the program id
+function optionalAccount(
+  address: null | PublicKey,
+  isWritable: boolean,
+  vaultProgramId: PublicKey,
+): AccountMeta {
+  return address != null
+    ? { pubkey: address, isSigner: false, isWritable }
+    : { pubkey: vaultProgramId, isSigner: false, isWritable: false }
+}
+
 export class VaultInstructionService {
 
   static createVault(
@@ -195,11 +206,19 @@ export class VaultInstructionService {
     }
     const data = coder.instruction.encode('createSchedule', request)
 
+    const [vaultAllowlistAddress,]: [PublicKey, number] = this.findVaultAllowlistAddress(vaultAddress, vaultProgramId)
+    const [vaultSignerAddress,]: [PublicKey, number] = this.findVaultSignerAddress(vaultAddress, vaultProgramId)
+    const [vaultDestinationsAddress,]: [PublicKey, number] = this.findVaultDestinationsAddress(vaultAddress, vaultProgramId)
     const keys: AccountMeta[] = [
       { pubkey: rootAddress, isSigner: true, isWritable: false },
       { pubkey: vaultAddress, isSigner: false, isWritable: false, },
       { pubkey: scheduleAddress, isSigner: false, isWritable: true },
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false, },
+      { pubkey: vaultAllowlistAddress, isSigner: false, isWritable: false },
+      { pubkey: vaultSignerAddress, isSigner: false, isWritable: false },
+      { pubkey: receivingTokenAccountAddress, isSigner: false, isWritable: false },
+      { pubkey: sendingTokenAccountAddress, isSigner: false, isWritable: false },
+      { pubkey: vaultDestinationsAddress, isSigner: false, isWritable: false },
     ]
 
     return new TransactionInstruction({
@@ -249,12 +268,16 @@ export class VaultInstructionService {
     }
     const data = coder.instruction.encode('withdrawSol', request)
 
+    const [vaultReserveAddress,]: [PublicKey, number] = this.findVaultReserveAddress(vaultAddress, vaultProgramId)
+    const [vaultDestinationsAddress,]: [PublicKey, number] = this.findVaultDestinationsAddress(vaultAddress, vaultProgramId)
     const keys: AccountMeta[] = [
       { pubkey: ownerAddress, isSigner: true, isWritable: false },
       { pubkey: vaultAddress, isSigner: false, isWritable: false },
       { pubkey: vaultSignerAddress, isSigner: false, isWritable: true },
       { pubkey: recipientAddress, isSigner: false, isWritable: true },
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+      { pubkey: vaultReserveAddress, isSigner: false, isWritable: false },
+      { pubkey: vaultDestinationsAddress, isSigner: false, isWritable: false },
     ]
 
     return new TransactionInstruction({
@@ -279,6 +302,8 @@ export class VaultInstructionService {
     }
     const data = coder.instruction.encode('withdrawToken', request)
 
+    const [vaultAllowlistAddress,]: [PublicKey, number] = this.findVaultAllowlistAddress(vaultAddress, vaultProgramId)
+    const [vaultDestinationsAddress,]: [PublicKey, number] = this.findVaultDestinationsAddress(vaultAddress, vaultProgramId)
     const keys: AccountMeta[] = [
       { pubkey: ownerAddress, isSigner: true, isWritable: false },
       { pubkey: vaultAddress, isSigner: false, isWritable: false },
@@ -286,6 +311,8 @@ export class VaultInstructionService {
       { pubkey: senderAddress, isSigner: false, isWritable: true },
       { pubkey: recipientAddress, isSigner: false, isWritable: true },
       { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: vaultAllowlistAddress, isSigner: false, isWritable: false },
+      { pubkey: vaultDestinationsAddress, isSigner: false, isWritable: false },
     ]
 
     return new TransactionInstruction({
@@ -309,6 +336,9 @@ export class VaultInstructionService {
     userAddress: PublicKey,
     userVestingTokenAddress: PublicKey,
     userFeeTokenAddress: null | PublicKey,
+    treasuryAddress: null | PublicKey,
+    treasuryTokenAddress: null | PublicKey,
+    scheduleTrancheAddress: null | PublicKey,
     vaultProgramId: PublicKey,
   ): TransactionInstruction {
 
@@ -321,13 +351,21 @@ export class VaultInstructionService {
     }
     const data = coder.instruction.encode('redeemToken', request)
 
-    let extraAccounts: AccountMeta[] = []
-    if(vaultFeeTokenAddress != null) {
-      extraAccounts.push({ pubkey: vaultFeeTokenAddress, isSigner: false, isWritable: true })
-    }
-    if(userFeeTokenAddress != null) {
-      extraAccounts.push({ pubkey: userFeeTokenAddress, isSigner: false, isWritable: true })
-    }
+    const [vaultReserveAddress,]: [PublicKey, number] = this.findVaultReserveAddress(vaultAddress, vaultProgramId)
+    const [vaultFeeAddress,]: [PublicKey, number] = this.findVaultFeeAddress(vaultAddress, vaultProgramId)
+    const extraAccounts: AccountMeta[] = [
+      optionalAccount(vaultFeeTokenAddress, true, vaultProgramId),
+      optionalAccount(userFeeTokenAddress, true, vaultProgramId),
+      // associated token program, user token mint and payer, to create the user token account
+      optionalAccount(null, false, vaultProgramId),
+      optionalAccount(null, false, vaultProgramId),
+      optionalAccount(null, false, vaultProgramId),
+      { pubkey: vaultReserveAddress, isSigner: false, isWritable: true },
+      { pubkey: vaultFeeAddress, isSigner: false, isWritable: false },
+      optionalAccount(treasuryAddress, true, vaultProgramId),
+      optionalAccount(treasuryTokenAddress, true, vaultProgramId),
+      optionalAccount(scheduleTrancheAddress, false, vaultProgramId),
+    ]
     const keys: AccountMeta[] = [
       { pubkey: vaultAddress, isSigner: false, isWritable: false },
       { pubkey: scheduleAddress, isSigner: false, isWritable: true },
@@ -361,6 +399,9 @@ export class VaultInstructionService {
     userAddress: PublicKey,
     userVestingTokenAddress: PublicKey,
     userFeeTokenAddress: null | PublicKey,
+    treasuryAddress: null | PublicKey,
+    treasuryTokenAddress: null | PublicKey,
+    scheduleTrancheAddress: null | PublicKey,
     vaultProgramId: PublicKey,
   ): TransactionInstruction {
 
@@ -374,13 +415,23 @@ export class VaultInstructionService {
     }
     const data = coder.instruction.encode('redeemTokenMulti', request)
 
-    let extraAccounts: AccountMeta[] = []
-    if(vaultFeeTokenAddress != null) {
-      extraAccounts.push({ pubkey: vaultFeeTokenAddress, isSigner: false, isWritable: true })
-    }
-    if(userFeeTokenAddress != null) {
-      extraAccounts.push({ pubkey: userFeeTokenAddress, isSigner: false, isWritable: true })
-    }
+    const [vaultReserveAddress,]: [PublicKey, number] = this.findVaultReserveAddress(vaultAddress, vaultProgramId)
+    const [vaultFeeAddress,]: [PublicKey, number] = this.findVaultFeeAddress(vaultAddress, vaultProgramId)
+    const [vaultAllowlistAddress,]: [PublicKey, number] = this.findVaultAllowlistAddress(vaultAddress, vaultProgramId)
+    const extraAccounts: AccountMeta[] = [
+      optionalAccount(vaultFeeTokenAddress, true, vaultProgramId),
+      optionalAccount(userFeeTokenAddress, true, vaultProgramId),
+      // associated token program, user token mint and payer, to create the user token account
+      optionalAccount(null, false, vaultProgramId),
+      optionalAccount(null, false, vaultProgramId),
+      optionalAccount(null, false, vaultProgramId),
+      { pubkey: vaultReserveAddress, isSigner: false, isWritable: true },
+      { pubkey: vaultFeeAddress, isSigner: false, isWritable: false },
+      optionalAccount(treasuryAddress, true, vaultProgramId),
+      optionalAccount(treasuryTokenAddress, true, vaultProgramId),
+      optionalAccount(scheduleTrancheAddress, false, vaultProgramId),
+      { pubkey: vaultAllowlistAddress, isSigner: false, isWritable: false },
+    ]
     const keys: AccountMeta[] = [
       { pubkey: vaultAddress, isSigner: false, isWritable: false },
       { pubkey: scheduleAddress, isSigner: false, isWritable: true },
@@ -505,6 +556,58 @@ export class VaultInstructionService {
     )
   }
 
+  static findVaultReserveAddress(
+    vaultAddress: PublicKey,
+    vaultProgramId: PublicKey,
+  ): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [
+        HashService.sha256('Reserve').slice(0, 8),
+        vaultAddress.toBuffer(),
+      ],
+      vaultProgramId,
+    )
+  }
+
+  static findVaultFeeAddress(
+    vaultAddress: PublicKey,
+    vaultProgramId: PublicKey,
+  ): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [
+        HashService.sha256('Fee').slice(0, 8),
+        vaultAddress.toBuffer(),
+      ],
+      vaultProgramId,
+    )
+  }
+
+  static findVaultAllowlistAddress(
+    vaultAddress: PublicKey,
+    vaultProgramId: PublicKey,
+  ): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [
+        HashService.sha256('Allowlist').slice(0, 8),
+        vaultAddress.toBuffer(),
+      ],
+      vaultProgramId,
+    )
+  }
+
+  static findVaultDestinationsAddress(
+    vaultAddress: PublicKey,
+    vaultProgramId: PublicKey,
+  ): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [
+        HashService.sha256('Destination').slice(0, 8),
+        vaultAddress.toBuffer(),
+      ],
+      vaultProgramId,
+    )
+  }
+
   static findScheduleAddress(
     eventId: BN,
     vaultProgramId: PublicKey,
@@ -531,4 +634,17 @@ export class VaultInstructionService {
       vaultProgramId,
     )
   }
+
+  static findScheduleTrancheAddress(
+    scheduleAddress: PublicKey,
+    vaultProgramId: PublicKey,
+  ): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [
+        HashService.sha256('Tranche').slice(0, 8),
+        scheduleAddress.toBuffer(),
+      ],
+      vaultProgramId,
+    )
+  }
 }
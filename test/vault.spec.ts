@@ -154,6 +154,8 @@ describe("Vault", () => {
       new BN(0),
       userReceiveTokenAccount,
       userReceiveTokenAccount,
+      null,
+      null,
       PROGRAM_ID
     );
   });
@@ -237,6 +239,8 @@ describe("Vault", () => {
       new BN(0),
       userReceiveTokenAccount,
       userReceiveTokenAccount,
+      null,
+      null,
       PROGRAM_ID
     );
   });
@@ -316,6 +320,8 @@ describe("Vault", () => {
       new BN(0),
       userReceiveTokenAccount,
       userReceiveTokenAccount,
+      null,
+      null,
       PROGRAM_ID
     );
   });
@@ -403,6 +409,8 @@ describe("Vault", () => {
       new BN(0),
       userReceiveTokenAccount,
       userReceiveTokenAccount,
+      null,
+      null,
       PROGRAM_ID
     );
 
@@ -420,7 +428,62 @@ describe("Vault", () => {
       new BN(0),
       userReceiveTokenAccount,
       userReceiveTokenAccount,
+      null,
+      null,
       PROGRAM_ID
     );
   });
+
+  it("Withdraw token", async () => {
+    const vaultInfo = await VaultService.getVaultAccountInfo(connection, vaultAddress);
+    const vaultReceiveTokenAccount = await TokenProgramService.createAssociatedTokenAccount(
+      connection,
+      payer,
+      vaultInfo.signer,
+      receivingTokenMint.publicKey
+    );
+    const payerReceiveTokenAccount = await TokenProgramService.createAssociatedTokenAccount(
+      connection,
+      payer,
+      payer.publicKey,
+      receivingTokenMint.publicKey
+    );
+
+    await TokenProgramService.mint(
+      connection,
+      payer,
+      receivingTokenMint.publicKey,
+      vaultReceiveTokenAccount,
+      new BN(100)
+    );
+
+    await VaultService.withdrawToken(
+      connection,
+      payer,
+      vaultAddress,
+      vaultReceiveTokenAccount,
+      payerReceiveTokenAccount,
+      new BN(100),
+      PROGRAM_ID
+    );
+
+    const balance = await connection.getTokenAccountBalance(payerReceiveTokenAccount);
+    expect(balance.value.amount).to.equal("100");
+  });
+
+  it("Withdraw SOL", async () => {
+    const recipient = Keypair.generate();
+
+    await VaultService.withdrawSol(
+      connection,
+      payer,
+      vaultAddress,
+      recipient.publicKey,
+      new BN(LAMPORTS_PER_SOL / 10),
+      PROGRAM_ID
+    );
+
+    const balance = await connection.getBalance(recipient.publicKey);
+    expect(balance).to.equal(LAMPORTS_PER_SOL / 10);
+  });
 });